@@ -0,0 +1,126 @@
+//! Write throughput of execution log storage.
+//!
+//! Streams a 50 MB log through `ExecutionLogWriter` into a file-backed
+//! database and reads it back, then runs the previous one-row-per-execution
+//! append on a smaller log for comparison.
+//!
+//! Run with `cargo bench -p db --bench execution_process_logs`.
+
+use std::{str::FromStr, time::Instant};
+
+use db::models::execution_process_logs::{ExecutionLogWriter, ExecutionProcessLogs};
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+const CHUNKED_BYTES: usize = 50 * 1024 * 1024;
+// The legacy append rewrites the whole row per line, so it only gets a fraction
+// of the log to finish in reasonable time
+const LEGACY_BYTES: usize = 5 * 1024 * 1024;
+
+#[tokio::main]
+async fn main() -> Result<(), sqlx::Error> {
+    let db_path = std::env::temp_dir().join(format!("log-bench-{}.sqlite", Uuid::new_v4()));
+    let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path.display()))?
+        .create_if_missing(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+    // Log rows normally hang off an execution process, skip creating one
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&pool)
+        .await?;
+
+    let line = log_line();
+    println!(
+        "log line: {} bytes, chunked log: {} MB, legacy log: {} MB",
+        line.len(),
+        CHUNKED_BYTES / (1024 * 1024),
+        LEGACY_BYTES / (1024 * 1024)
+    );
+
+    bench_chunked(&pool, &line).await?;
+    bench_legacy(&pool, &line).await?;
+
+    pool.close().await;
+    let _ = std::fs::remove_file(&db_path);
+    Ok(())
+}
+
+fn log_line() -> String {
+    let content = format!(
+        "{{\"type\":\"assistant\",\"message\":{{\"content\":\"{}\"}}}}\n",
+        "x".repeat(160)
+    );
+    format!(
+        "{}\n",
+        serde_json::to_string(&LogMsg::Stdout(content)).unwrap()
+    )
+}
+
+async fn bench_chunked(pool: &SqlitePool, line: &str) -> Result<(), sqlx::Error> {
+    let execution_id = Uuid::new_v4();
+    let mut writer = ExecutionLogWriter::new(pool.clone(), execution_id);
+
+    let start = Instant::now();
+    let mut written = 0;
+    while written < CHUNKED_BYTES {
+        writer.push_line(line).await?;
+        written += line.len();
+    }
+    writer.flush().await?;
+    report("chunked write", written, start.elapsed().as_secs_f64());
+
+    let start = Instant::now();
+    let logs = ExecutionProcessLogs::find_by_execution_id(pool, execution_id)
+        .await?
+        .expect("logs were written");
+    report(
+        "chunked read",
+        logs.logs.len(),
+        start.elapsed().as_secs_f64(),
+    );
+    Ok(())
+}
+
+async fn bench_legacy(pool: &SqlitePool, line: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE legacy_logs (execution_id BLOB PRIMARY KEY, logs TEXT NOT NULL, byte_size INTEGER NOT NULL)",
+    )
+    .execute(pool)
+    .await?;
+
+    let execution_id = Uuid::new_v4();
+    let start = Instant::now();
+    let mut written = 0;
+    while written < LEGACY_BYTES {
+        sqlx::query(
+            r#"INSERT INTO legacy_logs (execution_id, logs, byte_size)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (execution_id) DO UPDATE
+               SET logs = logs || $2,
+                   byte_size = byte_size + $3"#,
+        )
+        .bind(execution_id)
+        .bind(line)
+        .bind(line.len() as i64)
+        .execute(pool)
+        .await?;
+        written += line.len();
+    }
+    report("legacy write", written, start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+fn report(label: &str, bytes: usize, secs: f64) {
+    let mb = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{label:>14}: {mb:7.1} MB in {secs:7.2}s ({:8.1} MB/s)",
+        mb / secs
+    );
+}
@@ -0,0 +1,40 @@
+PRAGMA foreign_keys = ON;
+
+-- 1. Widen the status CHECK so execution processes can wait in the queue
+ALTER TABLE execution_processes
+  ADD COLUMN status_new TEXT NOT NULL DEFAULT 'running'
+    CHECK (status_new IN ('queued',
+                          'running',
+                          'completed',
+                          'failed',
+                          'killed'));
+
+UPDATE execution_processes
+  SET status_new = status;
+
+DROP INDEX IF EXISTS idx_execution_processes_status;
+
+ALTER TABLE execution_processes DROP COLUMN status;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN status_new TO status;
+
+CREATE INDEX idx_execution_processes_status
+        ON execution_processes(status);
+
+-- 2. Optional per-project limit on concurrently running coding agents
+ALTER TABLE projects ADD COLUMN max_concurrent_agents INTEGER;
+
+-- 3. Persistent queue of execution processes waiting for a free slot
+CREATE TABLE execution_queue (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL UNIQUE,
+    project_id           BLOB NOT NULL,
+    priority             INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_queue_order
+        ON execution_queue(priority DESC, created_at ASC);
@@ -0,0 +1,35 @@
+PRAGMA foreign_keys = ON;
+
+-- Recurring attempts, either reusing an existing task or creating one from a template
+CREATE TABLE task_schedules (
+    id               BLOB PRIMARY KEY,
+    project_id       BLOB NOT NULL,
+    task_id          BLOB,
+    task_template_id BLOB,
+    -- The task last created from the template, reused until it's done
+    template_task_id BLOB,
+    cron_expression  TEXT NOT NULL,
+    profile          TEXT NOT NULL,
+    variant          TEXT,
+    base_branch      TEXT NOT NULL,
+    enabled          BOOLEAN NOT NULL DEFAULT TRUE,
+    last_run_at      TEXT,
+    next_run_at      TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+
+    -- A schedule targets exactly one of a task or a template
+    CHECK (
+        (task_id IS NOT NULL AND task_template_id IS NULL)
+        OR
+        (task_id IS NULL AND task_template_id IS NOT NULL)
+    ),
+
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_template_id) REFERENCES task_templates(id) ON DELETE CASCADE,
+    FOREIGN KEY (template_task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_due ON task_schedules(next_run_at) WHERE enabled = TRUE;
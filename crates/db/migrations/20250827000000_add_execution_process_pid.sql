@@ -0,0 +1,7 @@
+PRAGMA foreign_keys = ON;
+
+-- OS process id of the spawned executor, used to re-attach after a server restart
+ALTER TABLE execution_processes ADD COLUMN pid INTEGER;
+
+-- Start time of the process behind `pid`, so a recycled pid isn't mistaken for the executor
+ALTER TABLE execution_processes ADD COLUMN pid_start_time TEXT;
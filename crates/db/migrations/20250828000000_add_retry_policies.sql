@@ -0,0 +1,31 @@
+PRAGMA foreign_keys = ON;
+
+-- Per project retry behaviour for each kind of execution
+CREATE TABLE project_retry_policies (
+    id                        BLOB PRIMARY KEY,
+    project_id                BLOB NOT NULL,
+    run_reason                TEXT NOT NULL
+                                 CHECK (run_reason IN ('setupscript','cleanupscript','codingagent','devserver')),
+    max_attempts              INTEGER NOT NULL DEFAULT 3,
+    backoff_seconds           INTEGER NOT NULL DEFAULT 10,
+    backoff_multiplier        REAL NOT NULL DEFAULT 2.0,
+    -- JSON arrays, an empty list on both means every failure is retryable
+    retryable_exit_codes      TEXT NOT NULL DEFAULT '[]',
+    retryable_stderr_patterns TEXT NOT NULL DEFAULT '[]',
+    created_at                TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, run_reason)
+);
+
+-- Retries point at the execution process they are retrying
+ALTER TABLE execution_processes ADD COLUMN retry_of BLOB REFERENCES execution_processes(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_execution_processes_retry_of ON execution_processes(retry_of);
+
+-- When a failed execution is due to be retried under its project's retry policy,
+-- cleared once the retry starts or the attempt is stopped
+ALTER TABLE execution_processes ADD COLUMN retry_at TEXT;
+
+CREATE INDEX idx_execution_processes_retry_at ON execution_processes(retry_at)
+    WHERE retry_at IS NOT NULL;
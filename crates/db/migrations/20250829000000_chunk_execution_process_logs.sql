@@ -0,0 +1,23 @@
+PRAGMA foreign_keys = ON;
+
+-- Store execution logs as append-only chunks instead of one row per execution
+-- that is rewritten for every line
+CREATE TABLE execution_process_log_chunks (
+    execution_id      BLOB NOT NULL,
+    seq               INTEGER NOT NULL,   -- Order of the chunk within the execution, from 0
+    logs              TEXT NOT NULL,      -- JSONL format (one LogMsg per line)
+    byte_size         INTEGER NOT NULL,
+    inserted_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (execution_id, seq),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_execution_process_log_chunks_inserted_at ON execution_process_log_chunks(inserted_at);
+
+-- Existing logs become the first chunk of their execution
+INSERT INTO execution_process_log_chunks (execution_id, seq, logs, byte_size, inserted_at)
+SELECT execution_id, 0, logs, byte_size, inserted_at
+FROM execution_process_logs;
+
+DROP INDEX IF EXISTS idx_execution_process_logs_inserted_at;
+DROP TABLE execution_process_logs;
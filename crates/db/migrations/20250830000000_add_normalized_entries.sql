@@ -0,0 +1,13 @@
+PRAGMA foreign_keys = ON;
+
+-- Normalized conversation entries of coding agent executions, persisted as they
+-- are produced so finished executions don't have to be re-normalized
+CREATE TABLE execution_process_normalized_entries (
+    execution_id      BLOB NOT NULL,
+    entry_index       INTEGER NOT NULL,   -- Index of the entry in the conversation
+    entry             TEXT NOT NULL,      -- NormalizedEntry as JSON
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (execution_id, entry_index),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
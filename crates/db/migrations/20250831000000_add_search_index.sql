@@ -0,0 +1,160 @@
+PRAGMA foreign_keys = ON;
+
+-- Everything that can be searched: tasks, executor session prompts and
+-- summaries, and normalized conversation entries. Kept in sync by triggers on
+-- the source tables and indexed by the search_index FTS5 table below.
+CREATE TABLE search_documents (
+    id                    INTEGER PRIMARY KEY,
+    kind                  TEXT NOT NULL CHECK (kind IN ('task', 'prompt', 'summary', 'entry')),
+    source_id             BLOB NOT NULL,  -- Task, executor session or execution process id
+    entry_index           INTEGER,        -- Set for entries only
+    project_id            BLOB NOT NULL,
+    task_id               BLOB NOT NULL,
+    task_attempt_id       BLOB,
+    execution_process_id  BLOB,
+    profile               TEXT,
+    entry_type            TEXT,           -- NormalizedEntryType, e.g. tool_use
+    action_type           TEXT,           -- ActionType of tool uses, e.g. command_run
+    title                 TEXT,
+    body                  TEXT,
+    paths                 TEXT,
+    commands              TEXT,
+    created_at            TEXT NOT NULL
+);
+
+CREATE INDEX idx_search_documents_source ON search_documents(kind, source_id, entry_index);
+CREATE INDEX idx_search_documents_task_id ON search_documents(task_id);
+CREATE INDEX idx_search_documents_project_id ON search_documents(project_id);
+
+CREATE VIRTUAL TABLE search_index USING fts5(
+    title,
+    body,
+    paths,
+    commands,
+    content = 'search_documents',
+    content_rowid = 'id',
+    tokenize = 'unicode61'
+);
+
+CREATE TRIGGER search_documents_after_insert AFTER INSERT ON search_documents BEGIN
+    INSERT INTO search_index (rowid, title, body, paths, commands)
+    VALUES (NEW.id, NEW.title, NEW.body, NEW.paths, NEW.commands);
+END;
+
+CREATE TRIGGER search_documents_after_delete AFTER DELETE ON search_documents BEGIN
+    INSERT INTO search_index (search_index, rowid, title, body, paths, commands)
+    VALUES ('delete', OLD.id, OLD.title, OLD.body, OLD.paths, OLD.commands);
+END;
+
+CREATE TRIGGER search_documents_after_update AFTER UPDATE ON search_documents BEGIN
+    INSERT INTO search_index (search_index, rowid, title, body, paths, commands)
+    VALUES ('delete', OLD.id, OLD.title, OLD.body, OLD.paths, OLD.commands);
+    INSERT INTO search_index (rowid, title, body, paths, commands)
+    VALUES (NEW.id, NEW.title, NEW.body, NEW.paths, NEW.commands);
+END;
+
+-- Tasks
+CREATE TRIGGER tasks_search_after_insert AFTER INSERT ON tasks BEGIN
+    INSERT INTO search_documents (kind, source_id, project_id, task_id, title, body, created_at)
+    VALUES ('task', NEW.id, NEW.project_id, NEW.id, NEW.title, NEW.description, NEW.created_at);
+END;
+
+CREATE TRIGGER tasks_search_after_update AFTER UPDATE OF title, description ON tasks BEGIN
+    UPDATE search_documents
+    SET title = NEW.title, body = NEW.description
+    WHERE kind = 'task' AND source_id = NEW.id;
+END;
+
+CREATE TRIGGER tasks_search_after_delete AFTER DELETE ON tasks BEGIN
+    DELETE FROM search_documents WHERE task_id = OLD.id;
+END;
+
+-- Executor session prompts and summaries
+CREATE TRIGGER executor_sessions_search_after_insert AFTER INSERT ON executor_sessions BEGIN
+    INSERT INTO search_documents (kind, source_id, project_id, task_id, task_attempt_id, execution_process_id, profile, body, created_at)
+    SELECT 'prompt', NEW.id, t.project_id, t.id, ta.id, NEW.execution_process_id, ta.profile, NEW.prompt, NEW.created_at
+    FROM task_attempts ta
+    JOIN tasks t ON t.id = ta.task_id
+    WHERE ta.id = NEW.task_attempt_id AND NEW.prompt IS NOT NULL;
+END;
+
+CREATE TRIGGER executor_sessions_search_after_update AFTER UPDATE OF prompt, summary ON executor_sessions BEGIN
+    DELETE FROM search_documents WHERE kind IN ('prompt', 'summary') AND source_id = NEW.id;
+    INSERT INTO search_documents (kind, source_id, project_id, task_id, task_attempt_id, execution_process_id, profile, body, created_at)
+    SELECT 'prompt', NEW.id, t.project_id, t.id, ta.id, NEW.execution_process_id, ta.profile, NEW.prompt, NEW.created_at
+    FROM task_attempts ta
+    JOIN tasks t ON t.id = ta.task_id
+    WHERE ta.id = NEW.task_attempt_id AND NEW.prompt IS NOT NULL;
+    INSERT INTO search_documents (kind, source_id, project_id, task_id, task_attempt_id, execution_process_id, profile, body, created_at)
+    SELECT 'summary', NEW.id, t.project_id, t.id, ta.id, NEW.execution_process_id, ta.profile, NEW.summary, NEW.updated_at
+    FROM task_attempts ta
+    JOIN tasks t ON t.id = ta.task_id
+    WHERE ta.id = NEW.task_attempt_id AND NEW.summary IS NOT NULL;
+END;
+
+CREATE TRIGGER executor_sessions_search_after_delete AFTER DELETE ON executor_sessions BEGIN
+    DELETE FROM search_documents WHERE kind IN ('prompt', 'summary') AND source_id = OLD.id;
+END;
+
+-- Normalized conversation entries, with the file path or command of tool uses
+CREATE TRIGGER normalized_entries_search_after_insert AFTER INSERT ON execution_process_normalized_entries BEGIN
+    INSERT INTO search_documents (kind, source_id, entry_index, project_id, task_id, task_attempt_id, execution_process_id, profile, entry_type, action_type, body, paths, commands, created_at)
+    SELECT 'entry', NEW.execution_id, NEW.entry_index, t.project_id, t.id, ta.id, NEW.execution_id, ta.profile,
+           json_extract(NEW.entry, '$.entry_type.type'),
+           json_extract(NEW.entry, '$.entry_type.action_type.action'),
+           json_extract(NEW.entry, '$.content'),
+           json_extract(NEW.entry, '$.entry_type.action_type.path'),
+           json_extract(NEW.entry, '$.entry_type.action_type.command'),
+           NEW.created_at
+    FROM execution_processes ep
+    JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+    JOIN tasks t ON t.id = ta.task_id
+    WHERE ep.id = NEW.execution_id;
+END;
+
+CREATE TRIGGER normalized_entries_search_after_update AFTER UPDATE OF entry ON execution_process_normalized_entries BEGIN
+    UPDATE search_documents
+    SET entry_type = json_extract(NEW.entry, '$.entry_type.type'),
+        action_type = json_extract(NEW.entry, '$.entry_type.action_type.action'),
+        body = json_extract(NEW.entry, '$.content'),
+        paths = json_extract(NEW.entry, '$.entry_type.action_type.path'),
+        commands = json_extract(NEW.entry, '$.entry_type.action_type.command')
+    WHERE kind = 'entry' AND source_id = NEW.execution_id AND entry_index = NEW.entry_index;
+END;
+
+CREATE TRIGGER normalized_entries_search_after_delete AFTER DELETE ON execution_process_normalized_entries BEGIN
+    DELETE FROM search_documents
+    WHERE kind = 'entry' AND source_id = OLD.execution_id AND entry_index = OLD.entry_index;
+END;
+
+-- Index what already exists
+INSERT INTO search_documents (kind, source_id, project_id, task_id, title, body, created_at)
+SELECT 'task', id, project_id, id, title, description, created_at
+FROM tasks;
+
+INSERT INTO search_documents (kind, source_id, project_id, task_id, task_attempt_id, execution_process_id, profile, body, created_at)
+SELECT 'prompt', es.id, t.project_id, t.id, ta.id, es.execution_process_id, ta.profile, es.prompt, es.created_at
+FROM executor_sessions es
+JOIN task_attempts ta ON ta.id = es.task_attempt_id
+JOIN tasks t ON t.id = ta.task_id
+WHERE es.prompt IS NOT NULL;
+
+INSERT INTO search_documents (kind, source_id, project_id, task_id, task_attempt_id, execution_process_id, profile, body, created_at)
+SELECT 'summary', es.id, t.project_id, t.id, ta.id, es.execution_process_id, ta.profile, es.summary, es.updated_at
+FROM executor_sessions es
+JOIN task_attempts ta ON ta.id = es.task_attempt_id
+JOIN tasks t ON t.id = ta.task_id
+WHERE es.summary IS NOT NULL;
+
+INSERT INTO search_documents (kind, source_id, entry_index, project_id, task_id, task_attempt_id, execution_process_id, profile, entry_type, action_type, body, paths, commands, created_at)
+SELECT 'entry', ne.execution_id, ne.entry_index, t.project_id, t.id, ta.id, ne.execution_id, ta.profile,
+       json_extract(ne.entry, '$.entry_type.type'),
+       json_extract(ne.entry, '$.entry_type.action_type.action'),
+       json_extract(ne.entry, '$.content'),
+       json_extract(ne.entry, '$.entry_type.action_type.path'),
+       json_extract(ne.entry, '$.entry_type.action_type.command'),
+       ne.created_at
+FROM execution_process_normalized_entries ne
+JOIN execution_processes ep ON ep.id = ne.execution_id
+JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+JOIN tasks t ON t.id = ta.task_id;
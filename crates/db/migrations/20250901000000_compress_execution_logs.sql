@@ -0,0 +1,7 @@
+PRAGMA foreign_keys = ON;
+
+-- Log chunks can be stored zstd compressed, in which case `logs` is empty and
+-- the compressed JSONL is in `data`
+ALTER TABLE execution_process_log_chunks
+    ADD COLUMN encoding TEXT NOT NULL DEFAULT 'plain' CHECK (encoding IN ('plain', 'zstd'));
+ALTER TABLE execution_process_log_chunks ADD COLUMN data BLOB;
@@ -0,0 +1,17 @@
+PRAGMA foreign_keys = ON;
+
+-- How secrets are masked in the output captured for a project's executions.
+-- Projects without a row use the defaults.
+CREATE TABLE project_redaction_rules (
+    project_id         BLOB PRIMARY KEY,
+    enabled            BOOLEAN NOT NULL DEFAULT TRUE,
+    -- GitHub, AWS, Anthropic and OpenAI keys and JWTs
+    builtin_patterns   BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Mask values from env files listed in the project's copy_files
+    copy_files_secrets BOOLEAN NOT NULL DEFAULT TRUE,
+    -- JSON array of extra regexes
+    custom_patterns    TEXT NOT NULL DEFAULT '[]',
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
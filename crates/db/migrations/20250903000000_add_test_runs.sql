@@ -0,0 +1,26 @@
+PRAGMA foreign_keys = ON;
+
+-- Test results recognized in the output of agent commands and scripts
+CREATE TABLE test_runs (
+    id                   BLOB PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    task_attempt_id      BLOB NOT NULL,
+    -- cargo, pytest, jest or vitest
+    runner               TEXT NOT NULL,
+    -- NULL for script runs
+    command              TEXT,
+    passed               INTEGER NOT NULL DEFAULT 0,
+    failed               INTEGER NOT NULL DEFAULT 0,
+    skipped              INTEGER NOT NULL DEFAULT 0,
+    -- JSON array of test names
+    failing_tests        TEXT NOT NULL DEFAULT '[]',
+    -- Order within the execution, its runs are recorded together so created_at
+    -- can't tell them apart
+    sequence             INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_test_runs_task_attempt_id ON test_runs(task_attempt_id, created_at);
+CREATE INDEX idx_test_runs_execution_process_id ON test_runs(execution_process_id);
@@ -0,0 +1,10 @@
+PRAGMA foreign_keys = ON;
+
+-- Strategy used for a direct merge: squash, merge_commit, rebase or fast_forward
+ALTER TABLE merges ADD COLUMN merge_strategy TEXT;
+
+-- Every direct merge so far was a squash merge
+UPDATE merges SET merge_strategy = 'squash' WHERE merge_type = 'direct';
+
+-- Strategy used when a merge request doesn't pick one, NULL means squash
+ALTER TABLE projects ADD COLUMN default_merge_strategy TEXT;
@@ -0,0 +1,24 @@
+PRAGMA foreign_keys = ON;
+
+-- Attempt whose branch this attempt's branch was based on, for stacked attempts
+ALTER TABLE task_attempts ADD COLUMN parent_attempt_id BLOB REFERENCES task_attempts(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_task_attempts_parent_attempt_id ON task_attempts(parent_attempt_id);
+
+-- Parent attempt's tip this attempt's branch was last based on. Commits after it
+-- are the attempt's own, even once the parent is rewritten or squash-merged.
+ALTER TABLE task_attempts ADD COLUMN stacked_on_commit TEXT;
+
+-- Link existing attempts that were started from another attempt's branch
+UPDATE task_attempts
+SET parent_attempt_id = (
+    SELECT parent.id
+    FROM task_attempts parent
+    JOIN tasks parent_task ON parent_task.id = parent.task_id
+    JOIN tasks child_task ON child_task.id = task_attempts.task_id
+    WHERE parent.branch = task_attempts.base_branch
+      AND parent_task.project_id = child_task.project_id
+      AND parent.id != task_attempts.id
+    ORDER BY parent.created_at DESC
+    LIMIT 1
+);
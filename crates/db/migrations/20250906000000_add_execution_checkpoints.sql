@@ -0,0 +1,8 @@
+PRAGMA foreign_keys = ON;
+
+-- HEAD of the attempt branch when the execution started, so the attempt can be
+-- rolled back to the state before it
+ALTER TABLE execution_processes ADD COLUMN checkpoint_commit TEXT;
+
+-- Set on executions whose changes were discarded by reverting to an earlier checkpoint
+ALTER TABLE execution_processes ADD COLUMN reverted BOOLEAN NOT NULL DEFAULT FALSE;
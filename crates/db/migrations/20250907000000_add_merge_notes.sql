@@ -0,0 +1,4 @@
+PRAGMA foreign_keys = ON;
+
+-- What a partial merge left out of the attempt's changes
+ALTER TABLE merges ADD COLUMN merge_note TEXT;
@@ -0,0 +1,12 @@
+PRAGMA foreign_keys = ON;
+
+-- Revert of a merged attempt: the revert commit, and for PR merges the PR
+-- opened for it
+ALTER TABLE merges ADD COLUMN revert_commit TEXT;
+ALTER TABLE merges ADD COLUMN revert_pr_number INTEGER;
+ALTER TABLE merges ADD COLUMN revert_pr_url TEXT;
+ALTER TABLE merges ADD COLUMN reverted_at TEXT;
+
+-- Target branch tip before a direct merge, so merges that added several commits
+-- can be reverted as a whole
+ALTER TABLE merges ADD COLUMN base_commit TEXT;
@@ -0,0 +1,5 @@
+PRAGMA foreign_keys = ON;
+
+-- Fetch origin before creating worktrees or rebasing, and base attempts on
+-- origin/<branch> instead of the local base branch
+ALTER TABLE projects ADD COLUMN sync_remote_base BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,23 @@
+PRAGMA foreign_keys = ON;
+
+-- How commits made for a project's task attempts are attributed and signed.
+-- Projects without a row commit with the git config identity, unsigned and
+-- without trailers.
+CREATE TABLE project_commit_settings (
+    project_id          BLOB PRIMARY KEY,
+    -- Author and committer of agent commits, both set or both NULL
+    author_name         TEXT,
+    author_email        TEXT,
+    -- Credit the git config identity with a Co-authored-by trailer
+    co_authored_by      BOOLEAN NOT NULL DEFAULT FALSE,
+    task_id_trailer     BOOLEAN NOT NULL DEFAULT FALSE,
+    attempt_id_trailer  BOOLEAN NOT NULL DEFAULT FALSE,
+    profile_trailer     BOOLEAN NOT NULL DEFAULT FALSE,
+    -- ssh or gpg, NULL for unsigned commits
+    signing_format      TEXT,
+    -- SSH key path or GPG key id, git config user.signingkey when NULL
+    signing_key         TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
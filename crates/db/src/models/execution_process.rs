@@ -12,6 +12,7 @@ use super::{task::Task, task_attempt::TaskAttempt};
 #[sqlx(type_name = "execution_process_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum ExecutionProcessStatus {
+    Queued,
     Running,
     Completed,
     Failed,
@@ -37,6 +38,12 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    /// The failed execution process this one is a retry of
+    pub retry_of: Option<Uuid>,
+    /// Attempt branch HEAD before this execution started
+    pub checkpoint_commit: Option<String>,
+    /// Whether this execution's changes were discarded by a checkpoint revert
+    pub reverted: bool,
     pub started_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -48,6 +55,8 @@ pub struct CreateExecutionProcess {
     pub task_attempt_id: Uuid,
     pub executor_action: ExecutorAction,
     pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub retry_of: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -84,6 +93,9 @@ impl ExecutionProcess {
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
+                retry_of as "retry_of?: Uuid",
+                checkpoint_commit,
+                reverted as "reverted!: bool",
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -107,6 +119,9 @@ impl ExecutionProcess {
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
+                retry_of as "retry_of?: Uuid",
+                checkpoint_commit,
+                reverted as "reverted!: bool",
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -133,6 +148,9 @@ impl ExecutionProcess {
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
+                retry_of as "retry_of?: Uuid",
+                checkpoint_commit,
+                reverted as "reverted!: bool",
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -157,6 +175,9 @@ impl ExecutionProcess {
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
+                retry_of as "retry_of?: Uuid",
+                checkpoint_commit,
+                reverted as "reverted!: bool",
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -169,6 +190,22 @@ impl ExecutionProcess {
         .await
     }
 
+    /// IDs of coding agent executions that have finished, oldest first
+    pub async fn find_finished_coding_agent_ids(
+        pool: &SqlitePool,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT id as "id!: Uuid"
+               FROM execution_processes
+               WHERE run_reason = 'codingagent'
+                 AND status NOT IN ('queued', 'running')
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
     /// Find running dev servers for a specific project
     pub async fn find_running_dev_servers_by_project(
         pool: &SqlitePool,
@@ -183,6 +220,9 @@ impl ExecutionProcess {
                 ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 ep.status as "status!: ExecutionProcessStatus",
                 ep.exit_code,
+                ep.retry_of as "retry_of?: Uuid",
+                ep.checkpoint_commit,
+                ep.reverted as "reverted!: bool",
                 ep.started_at as "started_at!: DateTime<Utc>",
                 ep.completed_at as "completed_at?: DateTime<Utc>",
                 ep.created_at as "created_at!: DateTime<Utc>", 
@@ -243,6 +283,9 @@ impl ExecutionProcess {
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
+                retry_of as "retry_of?: Uuid",
+                checkpoint_commit,
+                reverted as "reverted!: bool",
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -272,10 +315,10 @@ impl ExecutionProcess {
             ExecutionProcess,
             r#"INSERT INTO execution_processes (
                 id, task_attempt_id, run_reason, executor_action, status, 
-                exit_code, started_at, 
+                exit_code, retry_of, started_at, 
                 completed_at, created_at, updated_at
                ) 
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) 
                RETURNING 
                 id as "id!: Uuid", 
                 task_attempt_id as "task_attempt_id!: Uuid", 
@@ -283,6 +326,9 @@ impl ExecutionProcess {
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
+                retry_of as "retry_of?: Uuid",
+                checkpoint_commit,
+                reverted as "reverted!: bool",
                 started_at as "started_at!: DateTime<Utc>",
                 completed_at as "completed_at?: DateTime<Utc>",
                 created_at as "created_at!: DateTime<Utc>", 
@@ -291,8 +337,9 @@ impl ExecutionProcess {
             data.task_attempt_id,
             data.run_reason,
             executor_action_json,
-            ExecutionProcessStatus::Running,
+            data.status,
             None::<i64>,           // exit_code
+            data.retry_of,         // retry_of
             now,                   // started_at
             None::<DateTime<Utc>>, // completed_at
             now,                   // created_at
@@ -301,6 +348,223 @@ impl ExecutionProcess {
         .fetch_one(pool)
         .await
     }
+    /// Count running coding agents and setup scripts, optionally restricted to a single project
+    pub async fn count_running_limited(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+    ) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ep.task_attempt_id = ta.id
+               JOIN tasks t ON ta.task_id = t.id
+               WHERE ep.status = 'running'
+                 AND ep.run_reason IN ('codingagent', 'setupscript')
+                 AND ($1 IS NULL OR t.project_id = $1)"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.count)
+    }
+
+    /// Whether any attempt of the task has a queued or running (non dev server) process
+    pub async fn has_active_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               JOIN task_attempts ta ON ep.task_attempt_id = ta.id
+               WHERE ta.task_id = $1
+                 AND ep.status IN ('queued', 'running')
+                 AND ep.run_reason != 'devserver'"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.count > 0)
+    }
+
+    /// Move a queued execution process to running, resetting its start time
+    pub async fn mark_started(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'running', started_at = $1, updated_at = $1
+               WHERE id = $2"#,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Number of runs in the retry chain ending at this execution process,
+    /// including the original run
+    pub async fn count_attempts_in_retry_chain(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"WITH RECURSIVE chain(id, retry_of) AS (
+                   SELECT id, retry_of FROM execution_processes WHERE id = $1
+                   UNION ALL
+                   SELECT ep.id, ep.retry_of
+                   FROM execution_processes ep
+                   JOIN chain c ON ep.id = c.retry_of
+               )
+               SELECT COUNT(*) as "count!: i64" FROM chain"#,
+            id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.count)
+    }
+
+    /// Schedule a retry of a failed execution process, or cancel it with None
+    pub async fn update_retry_at(
+        pool: &SqlitePool,
+        id: Uuid,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET retry_at = $1 WHERE id = $2",
+            retry_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Execution processes whose scheduled retry is due, earliest first
+    pub async fn find_due_retry_ids(
+        pool: &SqlitePool,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT id as "id!: Uuid"
+               FROM execution_processes
+               WHERE retry_at IS NOT NULL
+                 AND datetime(retry_at) <= datetime($1)
+               ORDER BY retry_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    /// Take a scheduled retry so it starts only once. Returns false if it was
+    /// cancelled or taken already.
+    pub async fn claim_retry(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE execution_processes SET retry_at = NULL WHERE id = $1 AND retry_at IS NOT NULL",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel the scheduled retries of an attempt's execution processes,
+    /// returns how many were cancelled
+    pub async fn cancel_retries_for_attempt(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE execution_processes SET retry_at = NULL WHERE task_attempt_id = $1 AND retry_at IS NOT NULL",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Record the OS process id of the spawned executor
+    pub async fn update_pid(
+        pool: &SqlitePool,
+        id: Uuid,
+        pid: i64,
+        pid_start_time: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET pid = $1, pid_start_time = $2 WHERE id = $3",
+            pid,
+            pid_start_time,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the attempt branch HEAD an execution started from
+    pub async fn update_checkpoint(
+        pool: &SqlitePool,
+        id: Uuid,
+        checkpoint_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_processes SET checkpoint_commit = $1 WHERE id = $2",
+            checkpoint_commit,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark an execution and every later one of the attempt as reverted
+    pub async fn mark_reverted_since(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET reverted = TRUE, updated_at = $1
+               WHERE task_attempt_id = $2
+                 AND created_at >= $3
+                 AND run_reason != 'devserver'"#,
+            now,
+            task_attempt_id,
+            since
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Find the OS process id recorded for an execution process
+    /// The pid of the executor and the start time recorded with it
+    pub async fn find_pid(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<(i64, String)>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT pid, pid_start_time FROM execution_processes WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.and_then(|r| Some((r.pid?, r.pid_start_time?))))
+    }
+
     pub async fn was_killed(pool: &SqlitePool, id: Uuid) -> bool {
         if let Ok(exp_process) = Self::find_by_id(pool, id).await
             && exp_process.is_some_and(|ep| ep.status == ExecutionProcessStatus::Killed)
@@ -317,7 +581,10 @@ impl ExecutionProcess {
         status: ExecutionProcessStatus,
         exit_code: Option<i64>,
     ) -> Result<(), sqlx::Error> {
-        let completed_at = if matches!(status, ExecutionProcessStatus::Running) {
+        let completed_at = if matches!(
+            status,
+            ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+        ) {
             None
         } else {
             Some(Utc::now())
@@ -1,10 +1,17 @@
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqlitePool, Type};
 use ts_rs::TS;
 use utils::log_msg::LogMsg;
 use uuid::Uuid;
 
+// Chunks are compressed quickly as they are written, then again harder once the
+// execution has finished and its chunks are merged
+const WRITE_COMPRESSION_LEVEL: i32 = 3;
+const COMPACTION_COMPRESSION_LEVEL: i32 = 12;
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
@@ -13,61 +20,344 @@ pub struct ExecutionProcessLogs {
     pub inserted_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, TS)]
-pub struct CreateExecutionProcessLogs {
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq)]
+#[sqlx(type_name = "log_chunk_encoding", rename_all = "lowercase")]
+pub enum LogChunkEncoding {
+    Plain,
+    Zstd,
+}
+
+/// One append-only piece of an execution's logs
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessLogChunk {
     pub execution_id: Uuid,
-    pub logs: String,
-    pub byte_size: i64,
+    pub seq: i64,
+    pub logs: String, // JSONL format, empty if compressed
+    pub encoding: LogChunkEncoding,
+    pub data: Option<Vec<u8>>, // Compressed JSONL
+    pub byte_size: i64,        // Uncompressed size
+    pub inserted_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessLogChunk {
+    /// The JSONL content of the chunk, decompressing it if needed
+    pub fn decode(self) -> Result<String, sqlx::Error> {
+        match self.encoding {
+            LogChunkEncoding::Plain => Ok(self.logs),
+            LogChunkEncoding::Zstd => {
+                let data = self.data.unwrap_or_default();
+                let bytes = zstd::decode_all(data.as_slice())
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+                String::from_utf8(bytes).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+            }
+        }
+    }
+}
+
+/// Storage taken up by execution logs
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LogStorageUsage {
+    pub execution_count: i64,
+    /// Size of the logs before compression
+    pub log_bytes: i64,
+    /// Size of the logs as stored
+    pub stored_log_bytes: i64,
+    /// Size of the persisted normalized conversations, which are kept when raw
+    /// logs are purged
+    pub normalized_entry_bytes: i64,
+}
+
+/// Stored size of the logs of one finished execution
+#[derive(Debug, Clone)]
+pub struct ExecutionLogSize {
+    pub execution_id: Uuid,
+    pub stored_bytes: i64,
 }
 
 impl ExecutionProcessLogs {
-    /// Find logs by execution process ID
+    /// Find logs by execution process ID, joining its chunks in order
     pub async fn find_by_execution_id(
         pool: &SqlitePool,
         execution_id: Uuid,
     ) -> Result<Option<Self>, sqlx::Error> {
+        let chunks = Self::find_chunks(pool, execution_id).await?;
+
+        let Some(inserted_at) = chunks.last().map(|chunk| chunk.inserted_at) else {
+            return Ok(None);
+        };
+        let byte_size = chunks.iter().map(|chunk| chunk.byte_size).sum();
+        let mut logs = String::new();
+        for chunk in chunks {
+            logs.push_str(&chunk.decode()?);
+        }
+
+        Ok(Some(Self {
+            execution_id,
+            logs,
+            byte_size,
+            inserted_at,
+        }))
+    }
+
+    async fn find_chunks(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Vec<ExecutionProcessLogChunk>, sqlx::Error> {
         sqlx::query_as!(
-            ExecutionProcessLogs,
-            r#"SELECT 
+            ExecutionProcessLogChunk,
+            r#"SELECT
                 execution_id as "execution_id!: Uuid",
+                seq,
                 logs,
+                encoding as "encoding!: LogChunkEncoding",
+                data,
                 byte_size,
                 inserted_at as "inserted_at!: DateTime<Utc>"
-               FROM execution_process_logs 
-               WHERE execution_id = $1"#,
+               FROM execution_process_log_chunks
+               WHERE execution_id = $1
+               ORDER BY seq ASC"#,
             execution_id
         )
-        .fetch_optional(pool)
+        .fetch_all(pool)
         .await
     }
 
-    /// Create or update execution process logs
-    pub async fn upsert(
+    /// Append a chunk of JSONL lines after the existing chunks of an execution
+    pub async fn append_chunk(
         pool: &SqlitePool,
-        data: &CreateExecutionProcessLogs,
-    ) -> Result<Self, sqlx::Error> {
-        let now = Utc::now();
+        execution_id: Uuid,
+        logs: &str,
+    ) -> Result<(), sqlx::Error> {
+        let byte_size = logs.len() as i64;
+        let data = compress(logs, WRITE_COMPRESSION_LEVEL)?;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_log_chunks (execution_id, seq, logs, encoding, data, byte_size, inserted_at)
+               VALUES (
+                   $1,
+                   (SELECT COALESCE(MAX(seq) + 1, 0) FROM execution_process_log_chunks WHERE execution_id = $1),
+                   '',
+                   'zstd',
+                   $2,
+                   $3,
+                   datetime('now', 'subsec')
+               )"#,
+            execution_id,
+            data,
+            byte_size
+        )
+        .execute(pool)
+        .await?;
 
-        sqlx::query_as!(
-            ExecutionProcessLogs,
-            r#"INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
-               VALUES ($1, $2, $3, $4)
-               ON CONFLICT (execution_id) DO UPDATE
-               SET logs = EXCLUDED.logs, 
-                   byte_size = EXCLUDED.byte_size,
-                   inserted_at = EXCLUDED.inserted_at
-               RETURNING 
-                execution_id as "execution_id!: Uuid",
-                logs,
-                byte_size,
-                inserted_at as "inserted_at!: DateTime<Utc>""#,
-            data.execution_id,
-            data.logs,
-            data.byte_size,
-            now
+        Ok(())
+    }
+
+    /// Merge the chunks of a finished execution into a single compressed chunk.
+    /// Returns false if there was nothing to compact.
+    pub async fn compact(pool: &SqlitePool, execution_id: Uuid) -> Result<bool, sqlx::Error> {
+        let chunks = Self::find_chunks(pool, execution_id).await?;
+        if chunks.is_empty() || (chunks.len() == 1 && chunks[0].encoding == LogChunkEncoding::Zstd)
+        {
+            return Ok(false);
+        }
+
+        let inserted_at = chunks[chunks.len() - 1].inserted_at;
+        let byte_size: i64 = chunks.iter().map(|chunk| chunk.byte_size).sum();
+        // Compressing at a high level takes a while, keep it off the async workers
+        let data = tokio::task::spawn_blocking(move || {
+            let mut logs = String::new();
+            for chunk in chunks {
+                logs.push_str(&chunk.decode()?);
+            }
+            compress(&logs, COMPACTION_COMPRESSION_LEVEL)
+        })
+        .await
+        .map_err(|e| sqlx::Error::Io(std::io::Error::other(e)))??;
+
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_log_chunks WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"INSERT INTO execution_process_log_chunks (execution_id, seq, logs, encoding, data, byte_size, inserted_at)
+               VALUES ($1, 0, '', 'zstd', $2, $3, $4)"#,
+            execution_id,
+            data,
+            byte_size,
+            inserted_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Finished executions whose logs are split over several chunks or not compressed
+    pub async fn find_compactable_execution_ids(
+        pool: &SqlitePool,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT c.execution_id as "execution_id!: Uuid"
+               FROM execution_process_log_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_id
+               WHERE ep.status NOT IN ('queued', 'running')
+               GROUP BY c.execution_id
+               HAVING COUNT(*) > 1 OR SUM(c.encoding = 'plain') > 0"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.execution_id).collect())
+    }
+
+    /// Finished executions with logs that completed before `before`
+    pub async fn find_execution_ids_completed_before(
+        pool: &SqlitePool,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT DISTINCT c.execution_id as "execution_id!: Uuid"
+               FROM execution_process_log_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_id
+               WHERE ep.status NOT IN ('queued', 'running')
+                 AND datetime(COALESCE(ep.completed_at, ep.created_at)) < datetime($1)"#,
+            before
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.execution_id).collect())
+    }
+
+    /// Finished executions with logs that don't belong to the `keep` most recent
+    /// attempts of their task
+    pub async fn find_execution_ids_beyond_last_attempts(
+        pool: &SqlitePool,
+        keep: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"WITH ranked_attempts AS (
+                   SELECT id, ROW_NUMBER() OVER (PARTITION BY task_id ORDER BY created_at DESC) AS attempt_rank
+                   FROM task_attempts
+               )
+               SELECT DISTINCT c.execution_id as "execution_id!: Uuid"
+               FROM execution_process_log_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_id
+               JOIN ranked_attempts ra ON ra.id = ep.task_attempt_id
+               WHERE ep.status NOT IN ('queued', 'running')
+                 AND ra.attempt_rank > $1"#,
+            keep
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.execution_id).collect())
+    }
+
+    /// Finished executions with logs, oldest first, optionally limited to a
+    /// project and to executions that completed before `before`
+    pub async fn find_finished_sizes(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+        before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<ExecutionLogSize>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT
+                c.execution_id as "execution_id!: Uuid",
+                SUM(LENGTH(c.logs) + COALESCE(LENGTH(c.data), 0)) as "stored_bytes!: i64"
+               FROM execution_process_log_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE ep.status NOT IN ('queued', 'running')
+                 AND ($1 IS NULL OR t.project_id = $1)
+                 AND ($2 IS NULL OR datetime(COALESCE(ep.completed_at, ep.created_at)) < datetime($2))
+               GROUP BY c.execution_id
+               ORDER BY MIN(COALESCE(ep.completed_at, ep.created_at)) ASC"#,
+            project_id,
+            before
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| ExecutionLogSize {
+                execution_id: row.execution_id,
+                stored_bytes: row.stored_bytes,
+            })
+            .collect())
+    }
+
+    /// Total stored size of all execution logs, raw logs and normalized conversations
+    pub async fn total_stored_bytes(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT
+                (SELECT COALESCE(SUM(LENGTH(logs) + COALESCE(LENGTH(data), 0)), 0) FROM execution_process_log_chunks)
+                + (SELECT COALESCE(SUM(LENGTH(entry)), 0) FROM execution_process_normalized_entries) as "stored_bytes!: i64""#
         )
         .fetch_one(pool)
-        .await
+        .await?;
+        Ok(row.stored_bytes)
+    }
+
+    /// Hand the pages freed by deleted logs back to the file system. The first
+    /// call switches the database to incremental vacuuming, which takes a full
+    /// `VACUUM`, later calls only release the free pages.
+    pub async fn reclaim_space(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        // 2 is INCREMENTAL
+        let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+            .fetch_one(pool)
+            .await?;
+        if auto_vacuum == 2 {
+            sqlx::query("PRAGMA incremental_vacuum")
+                .execute(pool)
+                .await?;
+        } else {
+            let mut conn = pool.acquire().await?;
+            sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("VACUUM").execute(&mut *conn).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn storage_usage_by_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<LogStorageUsage, sqlx::Error> {
+        let logs = sqlx::query!(
+            r#"SELECT
+                COUNT(DISTINCT c.execution_id) as "execution_count!: i64",
+                COALESCE(SUM(c.byte_size), 0) as "log_bytes!: i64",
+                COALESCE(SUM(LENGTH(c.logs) + COALESCE(LENGTH(c.data), 0)), 0) as "stored_log_bytes!: i64"
+               FROM execution_process_log_chunks c
+               JOIN execution_processes ep ON ep.id = c.execution_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await?;
+        let entries = sqlx::query!(
+            r#"SELECT COALESCE(SUM(LENGTH(ne.entry)), 0) as "normalized_entry_bytes!: i64"
+               FROM execution_process_normalized_entries ne
+               JOIN execution_processes ep ON ep.id = ne.execution_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(LogStorageUsage {
+            execution_count: logs.execution_count,
+            log_bytes: logs.log_bytes,
+            stored_log_bytes: logs.stored_log_bytes,
+            normalized_entry_bytes: entries.normalized_entry_bytes,
+        })
     }
 
     /// Parse JSONL logs back into Vec<LogMsg>
@@ -93,23 +383,14 @@ impl ExecutionProcessLogs {
         Ok(jsonl)
     }
 
-    /// Append a JSONL line to the logs for an execution process
-    pub async fn append_log_line(
+    /// Delete the stored logs for an execution process
+    pub async fn delete_by_execution_id(
         pool: &SqlitePool,
         execution_id: Uuid,
-        jsonl_line: &str,
     ) -> Result<(), sqlx::Error> {
-        let byte_size = jsonl_line.len() as i64;
         sqlx::query!(
-            r#"INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
-               VALUES ($1, $2, $3, datetime('now', 'subsec'))
-               ON CONFLICT (execution_id) DO UPDATE
-               SET logs = logs || $2,
-                   byte_size = byte_size + $3,
-                   inserted_at = datetime('now', 'subsec')"#,
-            execution_id,
-            jsonl_line,
-            byte_size
+            "DELETE FROM execution_process_log_chunks WHERE execution_id = $1",
+            execution_id
         )
         .execute(pool)
         .await?;
@@ -117,3 +398,49 @@ impl ExecutionProcessLogs {
         Ok(())
     }
 }
+
+/// Buffers JSONL lines for one execution and writes them out as chunks, so a
+/// chatty process costs one insert per flush instead of one write per line
+pub struct ExecutionLogWriter {
+    pool: SqlitePool,
+    execution_id: Uuid,
+    buffer: String,
+}
+
+impl ExecutionLogWriter {
+    /// How often buffered lines are written out while a process is running
+    pub const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+    /// Flush early once the buffered chunk reaches this size
+    const MAX_CHUNK_BYTES: usize = 256 * 1024;
+
+    pub fn new(pool: SqlitePool, execution_id: Uuid) -> Self {
+        Self {
+            pool,
+            execution_id,
+            buffer: String::new(),
+        }
+    }
+
+    pub async fn push_line(&mut self, jsonl_line: &str) -> Result<(), sqlx::Error> {
+        self.buffer.push_str(jsonl_line);
+        if self.buffer.len() >= Self::MAX_CHUNK_BYTES {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Write the buffered lines as a new chunk. They stay buffered if the
+    /// insert fails, so the next flush retries them.
+    pub async fn flush(&mut self) -> Result<(), sqlx::Error> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        ExecutionProcessLogs::append_chunk(&self.pool, self.execution_id, &self.buffer).await?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+fn compress(logs: &str, level: i32) -> Result<Vec<u8>, sqlx::Error> {
+    zstd::encode_all(logs.as_bytes(), level).map_err(|e| sqlx::Error::Encode(Box::new(e)))
+}
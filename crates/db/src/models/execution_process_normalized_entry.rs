@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use executors::logs::NormalizedEntry;
+use sqlx::{FromRow, SqlitePool, types::Json};
+use uuid::Uuid;
+
+use super::execution_process_logs::ExecutionLogSize;
+
+/// One entry of the normalized conversation of an execution process
+#[derive(Debug, Clone, FromRow)]
+pub struct ExecutionProcessNormalizedEntry {
+    pub execution_id: Uuid,
+    pub entry_index: i64,
+    pub entry: Json<NormalizedEntry>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessNormalizedEntry {
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessNormalizedEntry,
+            r#"SELECT
+                execution_id as "execution_id!: Uuid",
+                entry_index,
+                entry as "entry!: Json<NormalizedEntry>",
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_normalized_entries
+               WHERE execution_id = $1
+               ORDER BY entry_index ASC"#,
+            execution_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Write the entries that changed since the last call, `None` removes an entry
+    pub async fn apply_changes(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        changes: &[(usize, Option<NormalizedEntry>)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for (index, entry) in changes {
+            let entry_index = *index as i64;
+            match entry {
+                Some(entry) => {
+                    let entry = Json(entry);
+                    sqlx::query!(
+                        r#"INSERT INTO execution_process_normalized_entries (execution_id, entry_index, entry)
+                           VALUES ($1, $2, $3)
+                           ON CONFLICT (execution_id, entry_index) DO UPDATE
+                           SET entry = EXCLUDED.entry,
+                               updated_at = datetime('now', 'subsec')"#,
+                        execution_id,
+                        entry_index,
+                        entry
+                    )
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                None => {
+                    sqlx::query!(
+                        "DELETE FROM execution_process_normalized_entries WHERE execution_id = $1 AND entry_index = $2",
+                        execution_id,
+                        entry_index
+                    )
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+        tx.commit().await
+    }
+
+    /// Replace the whole conversation of an execution, used when re-normalizing.
+    /// Entries keep the index the normalizer gave them, which search hits and
+    /// file activity refer to.
+    pub async fn replace_all(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        entries: &BTreeMap<usize, NormalizedEntry>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM execution_process_normalized_entries WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (index, entry) in entries {
+            let entry_index = *index as i64;
+            let entry = Json(entry);
+            sqlx::query!(
+                r#"INSERT INTO execution_process_normalized_entries (execution_id, entry_index, entry)
+                   VALUES ($1, $2, $3)"#,
+                execution_id,
+                entry_index,
+                entry
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Stored size of the conversations of finished executions, oldest first
+    pub async fn find_finished_sizes(
+        pool: &SqlitePool,
+    ) -> Result<Vec<ExecutionLogSize>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT
+                ne.execution_id as "execution_id!: Uuid",
+                SUM(LENGTH(ne.entry)) as "stored_bytes!: i64"
+               FROM execution_process_normalized_entries ne
+               JOIN execution_processes ep ON ep.id = ne.execution_id
+               WHERE ep.status NOT IN ('queued', 'running')
+               GROUP BY ne.execution_id
+               ORDER BY MIN(COALESCE(ep.completed_at, ep.created_at)) ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| ExecutionLogSize {
+                execution_id: row.execution_id,
+                stored_bytes: row.stored_bytes,
+            })
+            .collect())
+    }
+
+    pub async fn delete_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM execution_process_normalized_entries WHERE execution_id = $1",
+            execution_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An execution process waiting for a free coding agent slot
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ExecutionQueueEntry {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub project_id: Uuid,
+    pub priority: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateExecutionQueueEntry {
+    pub execution_process_id: Uuid,
+    pub project_id: Uuid,
+    pub priority: i64,
+}
+
+impl ExecutionQueueEntry {
+    /// Add an execution process to the back of the queue for its priority
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateExecutionQueueEntry,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let now = Utc::now();
+
+        sqlx::query_as!(
+            ExecutionQueueEntry,
+            r#"INSERT INTO execution_queue (id, execution_process_id, project_id, priority, created_at)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                project_id as "project_id!: Uuid",
+                priority,
+                created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.execution_process_id,
+            data.project_id,
+            data.priority,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// All queued entries in dispatch order (highest priority first, then FIFO)
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionQueueEntry,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                project_id as "project_id!: Uuid",
+                priority,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_queue
+               ORDER BY priority DESC, created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Queued entries for a single project in dispatch order
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionQueueEntry,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                project_id as "project_id!: Uuid",
+                priority,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_queue
+               WHERE project_id = $1
+               ORDER BY priority DESC, created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionQueueEntry,
+            r#"SELECT
+                id as "id!: Uuid",
+                execution_process_id as "execution_process_id!: Uuid",
+                project_id as "project_id!: Uuid",
+                priority,
+                created_at as "created_at!: DateTime<Utc>"
+               FROM execution_queue
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn update_priority(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        priority: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE execution_queue SET priority = $1 WHERE execution_process_id = $2",
+            priority,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove an execution process from the queue, returning whether it was queued
+    pub async fn delete_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM execution_queue WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -21,12 +21,36 @@ pub enum Merge {
     Pr(PrMerge),
 }
 
+/// How a task branch is brought into its base branch by a direct merge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// A single commit with all of the branch's changes
+    #[default]
+    Squash,
+    /// A merge commit with the base and the task branch as parents
+    MergeCommit,
+    /// The branch's commits replayed onto the base, then fast-forwarded
+    Rebase,
+    /// Only move the base to the task branch, fails if the base has moved on
+    FastForward,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct DirectMerge {
     pub id: Uuid,
     pub task_attempt_id: Uuid,
     pub merge_commit: String,
     pub target_branch_name: String,
+    pub strategy: MergeStrategy,
+    /// Set when only part of the attempt's changes were merged, describing what
+    /// was left out
+    pub note: Option<String>,
+    /// Target branch tip before the merge, unset for merges recorded before it
+    /// was kept
+    pub base_commit: Option<String>,
+    pub revert: Option<MergeRevert>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -38,6 +62,17 @@ pub struct PrMerge {
     pub created_at: DateTime<Utc>,
     pub target_branch_name: String,
     pub pr_info: PullRequestInfo,
+    pub revert: Option<MergeRevert>,
+}
+
+/// Revert of a merged attempt. For PR merges the revert commit is on its own
+/// branch with a PR opened for it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MergeRevert {
+    pub commit: String,
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+    pub reverted_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -62,6 +97,13 @@ struct MergeRow {
     task_attempt_id: Uuid,
     merge_type: MergeType,
     merge_commit: Option<String>,
+    merge_strategy: Option<MergeStrategy>,
+    merge_note: Option<String>,
+    base_commit: Option<String>,
+    revert_commit: Option<String>,
+    revert_pr_number: Option<i64>,
+    revert_pr_url: Option<String>,
+    reverted_at: Option<DateTime<Utc>>,
     target_branch_name: String,
     pr_number: Option<i64>,
     pr_url: Option<String>,
@@ -72,6 +114,13 @@ struct MergeRow {
 }
 
 impl Merge {
+    pub fn id(&self) -> Uuid {
+        match self {
+            Merge::Direct(direct) => direct.id,
+            Merge::Pr(pr) => pr.id,
+        }
+    }
+
     pub fn merge_commit(&self) -> Option<String> {
         match self {
             Merge::Direct(direct) => Some(direct.merge_commit.clone()),
@@ -79,12 +128,52 @@ impl Merge {
         }
     }
 
+    pub fn revert(&self) -> Option<&MergeRevert> {
+        match self {
+            Merge::Direct(direct) => direct.revert.as_ref(),
+            Merge::Pr(pr) => pr.revert.as_ref(),
+        }
+    }
+
+    /// Record the revert of a merge, with the PR opened for it when there is one
+    pub async fn record_revert(
+        pool: &SqlitePool,
+        merge_id: Uuid,
+        revert_commit: &str,
+        revert_pr_number: Option<i64>,
+        revert_pr_url: Option<&str>,
+    ) -> Result<MergeRevert, sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE merges
+               SET revert_commit = $1, revert_pr_number = $2, revert_pr_url = $3, reverted_at = $4
+               WHERE id = $5"#,
+            revert_commit,
+            revert_pr_number,
+            revert_pr_url,
+            now,
+            merge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(MergeRevert {
+            commit: revert_commit.to_string(),
+            pr_number: revert_pr_number,
+            pr_url: revert_pr_url.map(str::to_string),
+            reverted_at: now,
+        })
+    }
+
     /// Create a direct merge record
     pub async fn create_direct(
         pool: &SqlitePool,
         task_attempt_id: Uuid,
         target_branch_name: &str,
         merge_commit: &str,
+        strategy: MergeStrategy,
+        note: Option<&str>,
+        base_commit: Option<&str>,
     ) -> Result<DirectMerge, sqlx::Error> {
         let id = Uuid::new_v4();
         let now = Utc::now();
@@ -92,13 +181,20 @@ impl Merge {
         sqlx::query_as!(
             MergeRow,
             r#"INSERT INTO merges (
-                id, task_attempt_id, merge_type, merge_commit, created_at, target_branch_name
-            ) VALUES ($1, $2, 'direct', $3, $4, $5)
+                id, task_attempt_id, merge_type, merge_commit, merge_strategy, merge_note, base_commit, created_at, target_branch_name
+            ) VALUES ($1, $2, 'direct', $3, $4, $5, $6, $7, $8)
             RETURNING 
                 id as "id!: Uuid",
                 task_attempt_id as "task_attempt_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                merge_strategy as "merge_strategy?: MergeStrategy",
+                merge_note,
+                base_commit,
+                revert_commit,
+                revert_pr_number,
+                revert_pr_url,
+                reverted_at as "reverted_at?: DateTime<Utc>",
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -110,6 +206,9 @@ impl Merge {
             id,
             task_attempt_id,
             merge_commit,
+            strategy,
+            note,
+            base_commit,
             now,
             target_branch_name
         )
@@ -138,6 +237,13 @@ impl Merge {
                 task_attempt_id as "task_attempt_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                merge_strategy as "merge_strategy?: MergeStrategy",
+                merge_note,
+                base_commit,
+                revert_commit,
+                revert_pr_number,
+                revert_pr_url,
+                reverted_at as "reverted_at?: DateTime<Utc>",
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -167,6 +273,13 @@ impl Merge {
                 task_attempt_id as "task_attempt_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                merge_strategy as "merge_strategy?: MergeStrategy",
+                merge_note,
+                base_commit,
+                revert_commit,
+                revert_pr_number,
+                revert_pr_url,
+                reverted_at as "reverted_at?: DateTime<Utc>",
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -226,6 +339,13 @@ impl Merge {
                 task_attempt_id as "task_attempt_id!: Uuid",
                 merge_type as "merge_type!: MergeType",
                 merge_commit,
+                merge_strategy as "merge_strategy?: MergeStrategy",
+                merge_note,
+                base_commit,
+                revert_commit,
+                revert_pr_number,
+                revert_pr_url,
+                reverted_at as "reverted_at?: DateTime<Utc>",
                 pr_number,
                 pr_url,
                 pr_status as "pr_status?: MergeStatus",
@@ -257,8 +377,18 @@ impl Merge {
 }
 
 // Conversion implementations
+fn revert_from_row(row: &MergeRow) -> Option<MergeRevert> {
+    Some(MergeRevert {
+        commit: row.revert_commit.clone()?,
+        pr_number: row.revert_pr_number,
+        pr_url: row.revert_pr_url.clone(),
+        reverted_at: row.reverted_at?,
+    })
+}
+
 impl From<MergeRow> for DirectMerge {
     fn from(row: MergeRow) -> Self {
+        let revert = revert_from_row(&row);
         DirectMerge {
             id: row.id,
             task_attempt_id: row.task_attempt_id,
@@ -266,6 +396,10 @@ impl From<MergeRow> for DirectMerge {
                 .merge_commit
                 .expect("direct merge must have merge_commit"),
             target_branch_name: row.target_branch_name,
+            strategy: row.merge_strategy.unwrap_or_default(),
+            note: row.merge_note,
+            base_commit: row.base_commit,
+            revert,
             created_at: row.created_at,
         }
     }
@@ -273,6 +407,7 @@ impl From<MergeRow> for DirectMerge {
 
 impl From<MergeRow> for PrMerge {
     fn from(row: MergeRow) -> Self {
+        let revert = revert_from_row(&row);
         PrMerge {
             id: row.id,
             task_attempt_id: row.task_attempt_id,
@@ -284,6 +419,7 @@ impl From<MergeRow> for PrMerge {
                 merged_at: row.pr_merged_at,
                 merge_commit_sha: row.pr_merge_commit_sha,
             },
+            revert,
             created_at: row.created_at,
         }
     }
@@ -1,10 +1,18 @@
 pub mod execution_process;
 pub mod execution_process_logs;
+pub mod execution_process_normalized_entry;
+pub mod execution_queue;
 pub mod executor_session;
 pub mod image;
 pub mod jira_integration;
 pub mod merge;
 pub mod project;
+pub mod project_commit_settings;
+pub mod project_redaction_rules;
+pub mod project_retry_policy;
+pub mod search;
 pub mod task;
 pub mod task_attempt;
+pub mod task_schedule;
 pub mod task_template;
+pub mod test_run;
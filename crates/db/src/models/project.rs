@@ -7,6 +7,8 @@ use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
 
+use super::merge::MergeStrategy;
+
 #[derive(Debug, Error)]
 pub enum ProjectError {
     #[error(transparent)]
@@ -30,6 +32,13 @@ pub struct Project {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    /// Maximum number of coding agents allowed to run at once for this project
+    pub max_concurrent_agents: Option<i64>,
+    /// Strategy for direct merges that don't pick one, squash when unset
+    pub default_merge_strategy: Option<MergeStrategy>,
+    /// Fetch origin before creating worktrees or rebasing, and base attempts on
+    /// `origin/<branch>` rather than the local branch
+    pub sync_remote_base: bool,
 
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -46,6 +55,10 @@ pub struct CreateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub max_concurrent_agents: Option<i64>,
+    pub default_merge_strategy: Option<MergeStrategy>,
+    #[serde(default)]
+    pub sync_remote_base: bool,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -56,6 +69,9 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub max_concurrent_agents: Option<i64>,
+    pub default_merge_strategy: Option<MergeStrategy>,
+    pub sync_remote_base: Option<bool>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -67,6 +83,9 @@ pub struct ProjectWithBranch {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
+    pub max_concurrent_agents: Option<i64>,
+    pub default_merge_strategy: Option<MergeStrategy>,
+    pub sync_remote_base: bool,
     pub current_branch: Option<String>,
 
     #[ts(type = "Date")]
@@ -85,6 +104,9 @@ impl ProjectWithBranch {
             dev_script: project.dev_script,
             cleanup_script: project.cleanup_script,
             copy_files: project.copy_files,
+            max_concurrent_agents: project.max_concurrent_agents,
+            default_merge_strategy: project.default_merge_strategy,
+            sync_remote_base: project.sync_remote_base,
             current_branch,
             created_at: project.created_at,
             updated_at: project.updated_at,
@@ -110,7 +132,7 @@ impl Project {
     pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, max_concurrent_agents, default_merge_strategy as "default_merge_strategy: MergeStrategy", sync_remote_base as "sync_remote_base!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
         )
         .fetch_all(pool)
         .await
@@ -119,7 +141,7 @@ impl Project {
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, max_concurrent_agents, default_merge_strategy as "default_merge_strategy: MergeStrategy", sync_remote_base as "sync_remote_base!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
             id
         )
         .fetch_optional(pool)
@@ -132,7 +154,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, max_concurrent_agents, default_merge_strategy as "default_merge_strategy: MergeStrategy", sync_remote_base as "sync_remote_base!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
             git_repo_path
         )
         .fetch_optional(pool)
@@ -146,7 +168,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, max_concurrent_agents, default_merge_strategy as "default_merge_strategy: MergeStrategy", sync_remote_base as "sync_remote_base!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
             git_repo_path,
             exclude_id
         )
@@ -161,14 +183,17 @@ impl Project {
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, max_concurrent_agents, default_merge_strategy, sync_remote_base) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, max_concurrent_agents, default_merge_strategy as "default_merge_strategy: MergeStrategy", sync_remote_base as "sync_remote_base!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
             data.name,
             data.git_repo_path,
             data.setup_script,
             data.dev_script,
             data.cleanup_script,
-            data.copy_files
+            data.copy_files,
+            data.max_concurrent_agents,
+            data.default_merge_strategy,
+            data.sync_remote_base
         )
         .fetch_one(pool)
         .await
@@ -183,17 +208,23 @@ impl Project {
         dev_script: Option<String>,
         cleanup_script: Option<String>,
         copy_files: Option<String>,
+        max_concurrent_agents: Option<i64>,
+        default_merge_strategy: Option<MergeStrategy>,
+        sync_remote_base: bool,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7, max_concurrent_agents = $8, default_merge_strategy = $9, sync_remote_base = $10 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, max_concurrent_agents, default_merge_strategy as "default_merge_strategy: MergeStrategy", sync_remote_base as "sync_remote_base!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
             git_repo_path,
             setup_script,
             dev_script,
             cleanup_script,
-            copy_files
+            copy_files,
+            max_concurrent_agents,
+            default_merge_strategy,
+            sync_remote_base
         )
         .fetch_one(pool)
         .await
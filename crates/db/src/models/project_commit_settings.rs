@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How commits are signed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CommitSigningFormat {
+    /// `ssh-keygen -Y sign`, like git's `gpg.format = ssh`
+    Ssh,
+    Gpg,
+}
+
+/// How commits made for a project's task attempts are attributed and signed.
+/// Applies to agent commits, direct merges and rebases.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectCommitSettings {
+    pub project_id: Uuid,
+    /// Author and committer of agent commits, the git config identity when unset
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    /// Credit the git config identity with a `Co-authored-by` trailer when
+    /// commits use the identity above
+    pub co_authored_by: bool,
+    pub task_id_trailer: bool,
+    pub attempt_id_trailer: bool,
+    /// Name the attempt's agent profile in a trailer
+    pub profile_trailer: bool,
+    /// Unsigned when unset
+    pub signing_format: Option<CommitSigningFormat>,
+    /// SSH key path or GPG key id, git config `user.signingkey` when unset
+    pub signing_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectCommitSettings {
+    pub author_name: Option<String>,
+    pub author_email: Option<String>,
+    pub co_authored_by: bool,
+    pub task_id_trailer: bool,
+    pub attempt_id_trailer: bool,
+    pub profile_trailer: bool,
+    pub signing_format: Option<CommitSigningFormat>,
+    pub signing_key: Option<String>,
+}
+
+impl ProjectCommitSettings {
+    /// The settings of a project that has none saved: commits as before, with
+    /// the git config identity, unsigned and without trailers
+    pub fn default_for(project_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            project_id,
+            author_name: None,
+            author_email: None,
+            co_authored_by: false,
+            task_id_trailer: false,
+            attempt_id_trailer: false,
+            profile_trailer: false,
+            signing_format: None,
+            signing_key: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectCommitSettings,
+            r#"SELECT project_id as "project_id!: Uuid", author_name, author_email, co_authored_by as "co_authored_by!: bool", task_id_trailer as "task_id_trailer!: bool", attempt_id_trailer as "attempt_id_trailer!: bool", profile_trailer as "profile_trailer!: bool", signing_format as "signing_format: CommitSigningFormat", signing_key, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_commit_settings
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Saved settings of a project, or the defaults
+    pub async fn find_or_default(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .unwrap_or_else(|| Self::default_for(project_id)))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectCommitSettings,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectCommitSettings,
+            r#"INSERT INTO project_commit_settings (project_id, author_name, author_email, co_authored_by, task_id_trailer, attempt_id_trailer, profile_trailer, signing_format, signing_key)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               ON CONFLICT (project_id) DO UPDATE
+               SET author_name = EXCLUDED.author_name,
+                   author_email = EXCLUDED.author_email,
+                   co_authored_by = EXCLUDED.co_authored_by,
+                   task_id_trailer = EXCLUDED.task_id_trailer,
+                   attempt_id_trailer = EXCLUDED.attempt_id_trailer,
+                   profile_trailer = EXCLUDED.profile_trailer,
+                   signing_format = EXCLUDED.signing_format,
+                   signing_key = EXCLUDED.signing_key,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", author_name, author_email, co_authored_by as "co_authored_by!: bool", task_id_trailer as "task_id_trailer!: bool", attempt_id_trailer as "attempt_id_trailer!: bool", profile_trailer as "profile_trailer!: bool", signing_format as "signing_format: CommitSigningFormat", signing_key, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.author_name,
+            data.author_email,
+            data.co_authored_by,
+            data.task_id_trailer,
+            data.attempt_id_trailer,
+            data.profile_trailer,
+            data.signing_format,
+            data.signing_key
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How secrets are masked in the output captured for a project's executions
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectRedactionRules {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    /// Mask GitHub, AWS, Anthropic and OpenAI keys and JWTs
+    pub builtin_patterns: bool,
+    /// Mask values from env files listed in the project's copy files
+    pub copy_files_secrets: bool,
+    #[ts(type = "string[]")]
+    pub custom_patterns: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectRedactionRules {
+    pub enabled: bool,
+    pub builtin_patterns: bool,
+    pub copy_files_secrets: bool,
+    pub custom_patterns: Vec<String>,
+}
+
+impl ProjectRedactionRules {
+    /// The rules that apply to a project when none have been saved
+    pub fn default_for(project_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            project_id,
+            enabled: true,
+            builtin_patterns: true,
+            copy_files_secrets: true,
+            custom_patterns: Json(Vec::new()),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectRedactionRules,
+            r#"SELECT project_id as "project_id!: Uuid", enabled as "enabled!: bool", builtin_patterns as "builtin_patterns!: bool", copy_files_secrets as "copy_files_secrets!: bool", custom_patterns as "custom_patterns!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_redaction_rules
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Saved rules of a project, or the defaults
+    pub async fn find_or_default(pool: &SqlitePool, project_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .unwrap_or_else(|| Self::default_for(project_id)))
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectRedactionRules,
+    ) -> Result<Self, sqlx::Error> {
+        let custom_patterns = Json(&data.custom_patterns);
+        sqlx::query_as!(
+            ProjectRedactionRules,
+            r#"INSERT INTO project_redaction_rules (project_id, enabled, builtin_patterns, copy_files_secrets, custom_patterns)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (project_id) DO UPDATE
+               SET enabled = EXCLUDED.enabled,
+                   builtin_patterns = EXCLUDED.builtin_patterns,
+                   copy_files_secrets = EXCLUDED.copy_files_secrets,
+                   custom_patterns = EXCLUDED.custom_patterns,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", enabled as "enabled!: bool", builtin_patterns as "builtin_patterns!: bool", copy_files_secrets as "copy_files_secrets!: bool", custom_patterns as "custom_patterns!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            data.builtin_patterns,
+            data.copy_files_secrets,
+            custom_patterns
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
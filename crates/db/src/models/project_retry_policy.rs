@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessRunReason;
+
+/// How failed execution processes of one run reason are retried within a project
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectRetryPolicy {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub run_reason: ExecutionProcessRunReason,
+    /// Total number of runs, including the first one
+    pub max_attempts: i64,
+    pub backoff_seconds: i64,
+    pub backoff_multiplier: f64,
+    #[ts(type = "number[]")]
+    pub retryable_exit_codes: Json<Vec<i64>>,
+    #[ts(type = "string[]")]
+    pub retryable_stderr_patterns: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpsertProjectRetryPolicy {
+    pub run_reason: ExecutionProcessRunReason,
+    pub max_attempts: i64,
+    pub backoff_seconds: i64,
+    pub backoff_multiplier: Option<f64>,
+    pub retryable_exit_codes: Vec<i64>,
+    pub retryable_stderr_patterns: Vec<String>,
+}
+
+impl ProjectRetryPolicy {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectRetryPolicy,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", run_reason as "run_reason!: ExecutionProcessRunReason", max_attempts, backoff_seconds, backoff_multiplier, retryable_exit_codes as "retryable_exit_codes!: Json<Vec<i64>>", retryable_stderr_patterns as "retryable_stderr_patterns!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_retry_policies
+               WHERE project_id = $1
+               ORDER BY run_reason ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_for_run_reason(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectRetryPolicy,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", run_reason as "run_reason!: ExecutionProcessRunReason", max_attempts, backoff_seconds, backoff_multiplier, retryable_exit_codes as "retryable_exit_codes!: Json<Vec<i64>>", retryable_stderr_patterns as "retryable_stderr_patterns!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_retry_policies
+               WHERE project_id = $1 AND run_reason = $2"#,
+            project_id,
+            run_reason
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Create the policy for a run reason, or replace the existing one
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectRetryPolicy,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let backoff_multiplier = data.backoff_multiplier.unwrap_or(2.0);
+        let retryable_exit_codes = Json(&data.retryable_exit_codes);
+        let retryable_stderr_patterns = Json(&data.retryable_stderr_patterns);
+        sqlx::query_as!(
+            ProjectRetryPolicy,
+            r#"INSERT INTO project_retry_policies (id, project_id, run_reason, max_attempts, backoff_seconds, backoff_multiplier, retryable_exit_codes, retryable_stderr_patterns)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT (project_id, run_reason) DO UPDATE
+               SET max_attempts = EXCLUDED.max_attempts,
+                   backoff_seconds = EXCLUDED.backoff_seconds,
+                   backoff_multiplier = EXCLUDED.backoff_multiplier,
+                   retryable_exit_codes = EXCLUDED.retryable_exit_codes,
+                   retryable_stderr_patterns = EXCLUDED.retryable_stderr_patterns,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", run_reason as "run_reason!: ExecutionProcessRunReason", max_attempts, backoff_seconds, backoff_multiplier, retryable_exit_codes as "retryable_exit_codes!: Json<Vec<i64>>", retryable_stderr_patterns as "retryable_stderr_patterns!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.run_reason,
+            data.max_attempts,
+            data.backoff_seconds,
+            backoff_multiplier,
+            retryable_exit_codes,
+            retryable_stderr_patterns
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        run_reason: &ExecutionProcessRunReason,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_retry_policies WHERE project_id = $1 AND run_reason = $2",
+            project_id,
+            run_reason
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
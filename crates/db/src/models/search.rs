@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "search_result_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum SearchResultKind {
+    Task,
+    Prompt,
+    Summary,
+    Entry,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SearchHit {
+    pub kind: SearchResultKind,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub task_attempt_id: Option<Uuid>,
+    pub execution_process_id: Option<Uuid>,
+    /// Index of the matching entry in the execution's normalized conversation
+    pub entry_index: Option<i64>,
+    pub entry_type: Option<String>,
+    pub action_type: Option<String>,
+    /// Matching text with the matched terms wrapped in `**`
+    pub snippet: String,
+    /// Frontend path of the matching task or attempt
+    pub link: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchFilter {
+    pub project_id: Option<Uuid>,
+    pub profile: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Matches either the entry type (e.g. `tool_use`) or the action type of a
+    /// tool use (e.g. `command_run`)
+    pub entry_type: Option<String>,
+}
+
+impl SearchHit {
+    /// Search the full-text index. `match_query` must be a valid FTS5 query.
+    pub async fn search(
+        pool: &SqlitePool,
+        match_query: &str,
+        filter: &SearchFilter,
+        limit: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT
+                d.kind as "kind!: SearchResultKind",
+                d.project_id as "project_id!: Uuid",
+                d.task_id as "task_id!: Uuid",
+                d.task_attempt_id as "task_attempt_id?: Uuid",
+                d.execution_process_id as "execution_process_id?: Uuid",
+                d.entry_index,
+                d.entry_type,
+                d.action_type,
+                snippet(search_index, -1, '**', '**', '…', 16) as "snippet!: String",
+                d.created_at as "created_at!: DateTime<Utc>"
+               FROM search_index
+               JOIN search_documents d ON d.id = search_index.rowid
+               WHERE search_index MATCH $1
+                 AND ($2 IS NULL OR d.project_id = $2)
+                 AND ($3 IS NULL
+                      OR d.profile = $3
+                      OR (d.kind = 'task' AND EXISTS (
+                          SELECT 1 FROM task_attempts ta WHERE ta.task_id = d.task_id AND ta.profile = $3)))
+                 AND ($4 IS NULL OR datetime(d.created_at) >= datetime($4))
+                 AND ($5 IS NULL OR datetime(d.created_at) <= datetime($5))
+                 AND ($6 IS NULL OR d.entry_type = $6 OR d.action_type = $6)
+               ORDER BY search_index.rank
+               LIMIT $7"#,
+            match_query,
+            filter.project_id,
+            filter.profile,
+            filter.from,
+            filter.to,
+            filter.entry_type,
+            limit
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let mut link = format!("/projects/{}/tasks/{}", row.project_id, row.task_id);
+                if let Some(attempt_id) = row.task_attempt_id {
+                    link.push_str(&format!("/attempts/{attempt_id}"));
+                }
+                if let Some(execution_id) = row.execution_process_id {
+                    link.push_str(&format!("?execution={execution_id}"));
+                    if let Some(entry_index) = row.entry_index {
+                        link.push_str(&format!("&entry={entry_index}"));
+                    }
+                }
+
+                Self {
+                    kind: row.kind,
+                    project_id: row.project_id,
+                    task_id: row.task_id,
+                    task_attempt_id: row.task_attempt_id,
+                    execution_process_id: row.execution_process_id,
+                    entry_index: row.entry_index,
+                    entry_type: row.entry_type,
+                    action_type: row.action_type,
+                    snippet: row.snippet,
+                    link,
+                    created_at: row.created_at,
+                }
+            })
+            .collect())
+    }
+}
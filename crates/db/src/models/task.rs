@@ -52,9 +52,13 @@ pub struct TaskWithAttemptStatus {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub has_in_progress_attempt: bool,
+    pub has_queued_attempt: bool,
     pub has_merged_attempt: bool,
     pub last_attempt_failed: bool,
     pub profile: String,
+    /// Counts of the latest test run in the task's attempts
+    pub tests_passed: Option<i64>,
+    pub tests_failed: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -116,6 +120,16 @@ impl Task {
        AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
+
+  CASE WHEN EXISTS (
+    SELECT 1
+      FROM task_attempts ta
+      JOIN execution_processes ep
+        ON ep.task_attempt_id = ta.id
+     WHERE ta.task_id       = t.id
+       AND ep.status        = 'queued'
+     LIMIT 1
+  ) THEN 1 ELSE 0 END            AS "has_queued_attempt!: i64",
   
   CASE WHEN (
     SELECT ep.status
@@ -134,9 +148,24 @@ impl Task {
       WHERE ta.task_id = t.id
      ORDER BY ta.created_at DESC
       LIMIT 1
-    )                               AS "profile!: String"
+    )                               AS "profile!: String",
+
+  ltr.passed                        AS "tests_passed?: i64",
+  ltr.failed                        AS "tests_failed?: i64"
 
 FROM tasks t
+LEFT JOIN test_runs ltr
+  ON ltr.id = (
+    SELECT tr.id
+      FROM test_runs tr
+      JOIN task_attempts ta
+        ON tr.task_attempt_id = ta.id
+      JOIN execution_processes ep
+        ON tr.execution_process_id = ep.id
+     WHERE ta.task_id = t.id
+     ORDER BY ep.created_at DESC, tr.sequence DESC
+     LIMIT 1
+  )
 WHERE t.project_id = $1
 ORDER BY t.created_at DESC"#,
             project_id
@@ -157,9 +186,12 @@ ORDER BY t.created_at DESC"#,
                 created_at: rec.created_at,
                 updated_at: rec.updated_at,
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
+                has_queued_attempt: rec.has_queued_attempt != 0,
                 has_merged_attempt: false, // TODO use merges table
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 profile: rec.profile,
+                tests_passed: rec.tests_passed,
+                tests_failed: rec.tests_failed,
             })
             .collect();
 
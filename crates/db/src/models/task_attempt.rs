@@ -44,6 +44,11 @@ pub struct TaskAttempt {
     // "GEMINI", etc.)
     pub worktree_deleted: bool, // Flag indicating if worktree has been cleaned up
     pub setup_completed_at: Option<DateTime<Utc>>, // When setup script was last completed
+    /// Attempt whose branch this attempt's branch is based on, for stacked attempts
+    pub parent_attempt_id: Option<Uuid>,
+    /// Parent attempt's tip the branch was last based on, the upstream when
+    /// restacking it
+    pub stacked_on_commit: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -105,6 +110,8 @@ impl TaskAttempt {
                               profile AS "profile!",
                               worktree_deleted AS "worktree_deleted!: bool",
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                              parent_attempt_id AS "parent_attempt_id: Uuid",
+                              stacked_on_commit,
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
                        FROM task_attempts
@@ -125,6 +132,8 @@ impl TaskAttempt {
                               profile AS "profile!",
                               worktree_deleted AS "worktree_deleted!: bool",
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                              parent_attempt_id AS "parent_attempt_id: Uuid",
+                              stacked_on_commit,
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
                        FROM task_attempts
@@ -156,6 +165,8 @@ impl TaskAttempt {
                        ta.profile AS "profile!",
                        ta.worktree_deleted  AS "worktree_deleted!: bool",
                        ta.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       ta.parent_attempt_id AS "parent_attempt_id: Uuid",
+                       ta.stacked_on_commit,
                        ta.created_at        AS "created_at!: DateTime<Utc>",
                        ta.updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts ta
@@ -246,6 +257,8 @@ impl TaskAttempt {
                        profile AS "profile!",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       parent_attempt_id AS "parent_attempt_id: Uuid",
+                       stacked_on_commit,
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -267,6 +280,8 @@ impl TaskAttempt {
                        profile AS "profile!",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       parent_attempt_id AS "parent_attempt_id: Uuid",
+                       stacked_on_commit,
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -377,6 +392,8 @@ impl TaskAttempt {
             .collect())
     }
 
+    /// Create an attempt. An attempt based on the branch of another attempt in the
+    /// same project is stacked on that attempt.
     pub async fn create(
         pool: &SqlitePool,
         data: &CreateTaskAttempt,
@@ -387,9 +404,17 @@ impl TaskAttempt {
         // Insert the record into the database
         Ok(sqlx::query_as!(
             TaskAttempt,
-            r#"INSERT INTO task_attempts (id, task_id, container_ref, branch, base_branch, profile, worktree_deleted, setup_completed_at)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, base_branch, profile as "profile!",  worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO task_attempts (id, task_id, container_ref, branch, base_branch, profile, worktree_deleted, setup_completed_at, parent_attempt_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, (
+                   SELECT parent.id
+                   FROM task_attempts parent
+                   JOIN tasks parent_task ON parent_task.id = parent.task_id
+                   WHERE parent.branch = $5
+                     AND parent_task.project_id = (SELECT project_id FROM tasks WHERE id = $2)
+                   ORDER BY parent.created_at DESC
+                   LIMIT 1
+               ))
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, base_branch, profile as "profile!",  worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", parent_attempt_id as "parent_attempt_id: Uuid", stacked_on_commit, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             attempt_id,
             task_id,
             Option::<String>::None, // Container isn't known yet
@@ -403,6 +428,68 @@ impl TaskAttempt {
         .await?)
     }
 
+    /// Attempts stacked directly on this attempt, oldest first
+    pub async fn find_stacked_children(
+        pool: &SqlitePool,
+        parent_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       base_branch,
+                       profile AS "profile!",
+                       worktree_deleted  AS "worktree_deleted!: bool",
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       parent_attempt_id AS "parent_attempt_id: Uuid",
+                       stacked_on_commit,
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    task_attempts
+               WHERE   parent_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            parent_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Move a stacked attempt onto another parent, or off the stack with `None`
+    pub async fn update_parent_attempt(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        parent_attempt_id: Option<Uuid>,
+    ) -> Result<(), TaskAttemptError> {
+        sqlx::query!(
+            "UPDATE task_attempts SET parent_attempt_id = $1, updated_at = datetime('now') WHERE id = $2",
+            parent_attempt_id,
+            attempt_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record the parent attempt's tip the branch is now based on
+    pub async fn update_stacked_on_commit(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        stacked_on_commit: Option<&str>,
+    ) -> Result<(), TaskAttemptError> {
+        sqlx::query!(
+            "UPDATE task_attempts SET stacked_on_commit = $1, updated_at = datetime('now') WHERE id = $2",
+            stacked_on_commit,
+            attempt_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn update_base_branch(
         pool: &SqlitePool,
         attempt_id: Uuid,
@@ -419,6 +506,24 @@ impl TaskAttempt {
         Ok(())
     }
 
+    /// Switch the attempt to another profile, used when it is handed off to a
+    /// different coding agent
+    pub async fn update_profile(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        profile: &str,
+    ) -> Result<(), TaskAttemptError> {
+        sqlx::query!(
+            "UPDATE task_attempts SET profile = $1, updated_at = datetime('now') WHERE id = $2",
+            profile,
+            attempt_id,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn resolve_container_ref(
         pool: &SqlitePool,
         container_ref: &str,
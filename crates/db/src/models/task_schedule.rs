@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A cron schedule that periodically starts an attempt for a task, or for a
+/// new task created from a template
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub task_template_id: Option<Uuid>,
+    /// The task last created from the template, reused until it's done
+    pub template_task_id: Option<Uuid>,
+    pub cron_expression: String,
+    pub profile: String,
+    pub variant: Option<String>,
+    pub base_branch: String,
+    pub enabled: bool,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub project_id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub task_template_id: Option<Uuid>,
+    pub cron_expression: String,
+    pub profile: String,
+    pub variant: Option<String>,
+    pub base_branch: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub cron_expression: Option<String>,
+    pub profile: Option<String>,
+    /// Replaces the variant, `null` clears it
+    pub variant: Option<String>,
+    pub base_branch: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+impl TaskSchedule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id?: Uuid", task_template_id as "task_template_id?: Uuid", template_task_id as "template_task_id?: Uuid", cron_expression, profile, variant, base_branch, enabled as "enabled!: bool", last_run_at as "last_run_at?: DateTime<Utc>", next_run_at as "next_run_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id?: Uuid", task_template_id as "task_template_id?: Uuid", template_task_id as "template_task_id?: Uuid", cron_expression, profile, variant, base_branch, enabled as "enabled!: bool", last_run_at as "last_run_at?: DateTime<Utc>", next_run_at as "next_run_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id?: Uuid", task_template_id as "task_template_id?: Uuid", template_task_id as "template_task_id?: Uuid", cron_expression, profile, variant, base_branch, enabled as "enabled!: bool", last_run_at as "last_run_at?: DateTime<Utc>", next_run_at as "next_run_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next run is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id?: Uuid", task_template_id as "task_template_id?: Uuid", template_task_id as "template_task_id?: Uuid", cron_expression, profile, variant, base_branch, enabled as "enabled!: bool", last_run_at as "last_run_at?: DateTime<Utc>", next_run_at as "next_run_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = TRUE
+                 AND next_run_at IS NOT NULL
+                 AND datetime(next_run_at) <= datetime($1)
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+        id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"INSERT INTO task_schedules (id, project_id, task_id, task_template_id, cron_expression, profile, variant, base_branch, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id?: Uuid", task_template_id as "task_template_id?: Uuid", template_task_id as "template_task_id?: Uuid", cron_expression, profile, variant, base_branch, enabled as "enabled!: bool", last_run_at as "last_run_at?: DateTime<Utc>", next_run_at as "next_run_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.task_id,
+            data.task_template_id,
+            data.cron_expression,
+            data.profile,
+            data.variant,
+            data.base_branch,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        cron_expression: &str,
+        profile: &str,
+        variant: Option<&str>,
+        base_branch: &str,
+        enabled: bool,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskSchedule,
+            r#"UPDATE task_schedules
+               SET cron_expression = $2, profile = $3, variant = $4, base_branch = $5, enabled = $6, next_run_at = $7, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", task_id as "task_id?: Uuid", task_template_id as "task_template_id?: Uuid", template_task_id as "template_task_id?: Uuid", cron_expression, profile, variant, base_branch, enabled as "enabled!: bool", last_run_at as "last_run_at?: DateTime<Utc>", next_run_at as "next_run_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            cron_expression,
+            profile,
+            variant,
+            base_branch,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record a trigger and move the schedule on to its next occurrence
+    pub async fn mark_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        last_run_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_run_at = $2, next_run_at = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            last_run_at,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remember the task created from the schedule's template
+    pub async fn update_template_task(
+        pool: &SqlitePool,
+        id: Uuid,
+        template_task_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET template_task_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            template_task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use executors::logs::test_run::TestRunSummary;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Test results recognized in the output of an agent command or a script
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TestRun {
+    pub id: Uuid,
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub runner: String,
+    /// The agent's command, `None` for script runs
+    pub command: Option<String>,
+    pub passed: i64,
+    pub failed: i64,
+    pub skipped: i64,
+    #[ts(type = "string[]")]
+    pub failing_tests: Json<Vec<String>>,
+    /// Order of the run within its execution
+    pub sequence: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TestRun {
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TestRun,
+            r#"SELECT tr.id as "id!: Uuid", tr.execution_process_id as "execution_process_id!: Uuid", tr.task_attempt_id as "task_attempt_id!: Uuid", tr.runner, tr.command, tr.passed, tr.failed, tr.skipped, tr.failing_tests as "failing_tests!: Json<Vec<String>>", tr.sequence, tr.created_at as "created_at!: DateTime<Utc>"
+               FROM test_runs tr
+               JOIN execution_processes ep ON ep.id = tr.execution_process_id
+               WHERE tr.task_attempt_id = $1
+               ORDER BY ep.created_at ASC, tr.sequence ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the test runs recorded for an execution, so recording again
+    /// after a restart doesn't duplicate them. `runs` are in the order they ran.
+    pub async fn replace_for_execution(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        runs: &[(Option<String>, TestRunSummary)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM test_runs WHERE execution_process_id = $1",
+            execution_process_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (sequence, (command, summary)) in runs.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let sequence = sequence as i64;
+            let runner = summary.runner.as_str();
+            let passed = summary.passed as i64;
+            let failed = summary.failed as i64;
+            let skipped = summary.skipped as i64;
+            let failing_tests = Json(&summary.failing_tests);
+            sqlx::query!(
+                r#"INSERT INTO test_runs (id, execution_process_id, task_attempt_id, runner, command, passed, failed, skipped, failing_tests, sequence)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+                id,
+                execution_process_id,
+                task_attempt_id,
+                runner,
+                command,
+                passed,
+                failed,
+                skipped,
+                failing_tests,
+                sequence
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+}
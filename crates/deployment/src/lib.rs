@@ -8,7 +8,7 @@ use db::{
     models::{
         execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
         task::{Task, TaskStatus},
-        task_attempt::{TaskAttempt, TaskAttemptError},
+        task_attempt::TaskAttemptError,
     },
 };
 use executors::executors::ExecutorError;
@@ -27,6 +27,7 @@ use services::services::{
     image::{ImageError, ImageService},
     pr_monitor::PrMonitorService,
     sentry::SentryService,
+    task_scheduler::TaskSchedulerService,
     worktree_manager::WorktreeError,
 };
 use sqlx::{Error as SqlxError, types::Uuid};
@@ -115,6 +116,15 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, config).await
     }
 
+    async fn spawn_task_scheduler_service(&self) -> tokio::task::JoinHandle<()> {
+        let deployment = self.clone();
+        tokio::spawn(async move {
+            TaskSchedulerService::new(deployment.db().clone(), deployment.container())
+                .start()
+                .await;
+        })
+    }
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         if let Some(true) = self.config().read().await.analytics_enabled {
             // Does the user allow analytics?
@@ -126,11 +136,45 @@ pub trait Deployment: Clone + Send + Sync + 'static {
     }
 
     /// Cleanup executions marked as running in the db, call at startup
-    async fn cleanup_orphan_executions(&self) -> Result<(), DeploymentError> {
+    /// Recover executions that were running when the server last stopped.
+    /// Processes that are still alive are re-attached, interrupted coding agent
+    /// sessions are resumed and anything else is marked as failed.
+    async fn recover_running_executions(&self) -> Result<(), DeploymentError> {
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
         for process in running_processes {
+            let ctx = match ExecutionProcess::load_context(&self.db().pool, process.id).await {
+                Ok(ctx) => ctx,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to load context for execution process {}: {}",
+                        process.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            match self.container().reattach_execution(&ctx).await {
+                Ok(true) => {
+                    tracing::info!(
+                        "Re-attached to running execution process {} for task attempt {}",
+                        process.id,
+                        process.task_attempt_id
+                    );
+                    continue;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to re-attach to execution process {}: {}",
+                        process.id,
+                        e
+                    );
+                }
+            }
+
             tracing::info!(
-                "Found orphaned execution process {} for task attempt {}",
+                "Found interrupted execution process {} for task attempt {}",
                 process.id,
                 process.task_attempt_id
             );
@@ -139,33 +183,53 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 &self.db().pool,
                 process.id,
                 ExecutionProcessStatus::Failed,
-                None, // No exit code for orphaned processes
+                None, // No exit code for interrupted processes
             )
             .await
             {
                 tracing::error!(
-                    "Failed to update orphaned execution process {} status: {}",
+                    "Failed to update interrupted execution process {} status: {}",
                     process.id,
                     e
                 );
                 continue;
             }
-            // Process marked as failed
-            tracing::info!("Marked orphaned execution process {} as failed", process.id);
+            tracing::info!(
+                "Marked interrupted execution process {} as failed",
+                process.id
+            );
+
+            // Pick the agent session back up where it stopped
+            match self.container().resume_interrupted_execution(&ctx).await {
+                Ok(Some(resumed)) => {
+                    tracing::info!(
+                        "Resumed interrupted execution process {} as {}",
+                        process.id,
+                        resumed.id
+                    );
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to resume interrupted execution process {}: {}",
+                        process.id,
+                        e
+                    );
+                }
+            }
+
             // Update task status to InReview for coding agent and setup script failures
             if matches!(
                 process.run_reason,
                 ExecutionProcessRunReason::CodingAgent
                     | ExecutionProcessRunReason::SetupScript
                     | ExecutionProcessRunReason::CleanupScript
-            ) && let Ok(Some(task_attempt)) =
-                TaskAttempt::find_by_id(&self.db().pool, process.task_attempt_id).await
-                && let Ok(Some(task)) = task_attempt.parent_task(&self.db().pool).await
-                && let Err(e) =
-                    Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await
+            ) && let Err(e) =
+                Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await
             {
                 tracing::error!(
-                    "Failed to update task status to InReview for orphaned attempt: {}",
+                    "Failed to update task status to InReview for interrupted attempt: {}",
                     e
                 );
             }
@@ -173,6 +237,12 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         Ok(())
     }
 
+    /// Start executions left in the queue by a previous run, call at startup
+    async fn resume_execution_queue(&self) -> Result<(), DeploymentError> {
+        self.container().dispatch_queued_executions().await?;
+        Ok(())
+    }
+
     async fn stream_events(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<Event, std::io::Error>> {
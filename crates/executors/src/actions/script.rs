@@ -5,7 +5,7 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 use ts_rs::TS;
-use utils::shell::get_shell_command;
+use utils::shell::{get_shell_command, shell_command_line};
 
 use crate::{actions::Executable, executors::ExecutorError};
 
@@ -34,11 +34,10 @@ impl Executable for ScriptRequest {
         let (shell_cmd, shell_arg) = get_shell_command();
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .arg(shell_arg)
-            .arg(&self.script)
+            .arg(shell_command_line(&self.script))
             .current_dir(current_dir);
 
         let child = command.group_spawn()?;
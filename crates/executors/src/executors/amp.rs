@@ -5,18 +5,19 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::{io::AsyncWriteExt, process::Command, task::JoinHandle};
 use ts_rs::TS;
 use utils::{
     diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative,
-    shell::get_shell_command,
+    shell::{get_shell_command, shell_command_line},
 };
 
 use crate::{
     command::CommandBuilder,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem as LogsTodoItem,
+        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, SubagentStatus,
+        TodoItem as LogsTodoItem,
         stderr_processor::normalize_stderr_logs,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
@@ -43,13 +44,12 @@ impl StandardCodingAgentExecutor for Amp {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped()) // <-- open a pipe
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(amp_command);
+            .arg(shell_command_line(&amp_command));
 
         let mut child = command.group_spawn()?;
 
@@ -80,13 +80,12 @@ impl StandardCodingAgentExecutor for Amp {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&amp_command);
+            .arg(shell_command_line(&amp_command));
 
         let mut child = command.group_spawn()?;
 
@@ -99,18 +98,26 @@ impl StandardCodingAgentExecutor for Amp {
         Ok(child)
     }
 
-    fn normalize_logs(&self, raw_logs_msg_store: Arc<MsgStore>, current_dir: &PathBuf) {
+    fn normalize_logs(
+        &self,
+        raw_logs_msg_store: Arc<MsgStore>,
+        current_dir: &PathBuf,
+    ) -> Vec<JoinHandle<()>> {
         let entry_index_provider = EntryIndexProvider::start_from(&raw_logs_msg_store);
 
         // Process stderr logs using the standard stderr processor
-        normalize_stderr_logs(raw_logs_msg_store.clone(), entry_index_provider.clone());
+        let stderr =
+            normalize_stderr_logs(raw_logs_msg_store.clone(), entry_index_provider.clone());
 
         // Process stdout logs (Amp's JSON output)
         let current_dir = current_dir.clone();
-        tokio::spawn(async move {
+        let stdout = tokio::spawn(async move {
             let mut s = raw_logs_msg_store.stdout_lines_stream();
 
             let mut seen_amp_message_ids: HashMap<usize, Vec<usize>> = HashMap::new();
+            // Task tool uses by id, with their entry index, entry and start time
+            let mut subagent_tasks: HashMap<String, (usize, NormalizedEntry, Option<u64>)> =
+                HashMap::new();
             while let Some(Ok(line)) = s.next().await {
                 let trimmed = line.trim();
                 match serde_json::from_str(trimmed) {
@@ -128,6 +135,26 @@ impl StandardCodingAgentExecutor for Amp {
                                     let mut has_patch_ids =
                                         seen_amp_message_ids.get_mut(&amp_message_id);
 
+                                    if let AmpContentItem::ToolResult { tool_use_id, run } =
+                                        content_item
+                                        && let Some((patch_id, entry, started_at)) =
+                                            subagent_tasks.get(tool_use_id)
+                                        && let Some(status) = subagent_status(run)
+                                    {
+                                        let duration_ms = message
+                                            .meta
+                                            .as_ref()
+                                            .zip(*started_at)
+                                            .map(|(meta, started_at)| {
+                                                meta.sent_at.saturating_sub(started_at)
+                                            });
+                                        let mut entry = entry.clone();
+                                        entry.finish_subagent_task(status, duration_ms);
+                                        raw_logs_msg_store.push_patch(ConversationPatch::replace(
+                                            *patch_id, entry,
+                                        ));
+                                    }
+
                                     if let Some(entry) = content_item.to_normalized_entry(
                                         role,
                                         &message,
@@ -145,33 +172,50 @@ impl StandardCodingAgentExecutor for Amp {
                                                 );
                                             }
                                             entry_index_provider.reset();
+                                            subagent_tasks.clear();
                                         }
 
-                                        let patch: Patch = match &mut has_patch_ids {
+                                        let (patch_id, is_new) = match &mut has_patch_ids {
                                             None => {
                                                 let new_id = entry_index_provider.next();
                                                 seen_amp_message_ids
                                                     .entry(amp_message_id)
                                                     .or_default()
                                                     .push(new_id);
-                                                ConversationPatch::add_normalized_entry(
-                                                    new_id, entry,
-                                                )
+                                                (new_id, true)
                                             }
                                             Some(patch_ids) => match patch_ids.get(content_index) {
-                                                Some(patch_id) => {
-                                                    ConversationPatch::replace(*patch_id, entry)
-                                                }
+                                                Some(patch_id) => (*patch_id, false),
                                                 None => {
                                                     let new_id = entry_index_provider.next();
                                                     patch_ids.push(new_id);
-                                                    ConversationPatch::add_normalized_entry(
-                                                        new_id, entry,
-                                                    )
+                                                    (new_id, true)
                                                 }
                                             },
                                         };
 
+                                        if let NormalizedEntryType::ToolUse {
+                                            action_type:
+                                                ActionType::TaskCreate { id: Some(id), .. },
+                                            ..
+                                        } = &entry.entry_type
+                                        {
+                                            subagent_tasks.insert(
+                                                id.clone(),
+                                                (
+                                                    patch_id,
+                                                    entry.clone(),
+                                                    message.meta.as_ref().map(|meta| meta.sent_at),
+                                                ),
+                                            );
+                                        }
+
+                                        let patch: Patch = if is_new {
+                                            ConversationPatch::add_normalized_entry(patch_id, entry)
+                                        } else {
+                                            ConversationPatch::replace(patch_id, entry)
+                                        };
+
                                         raw_logs_msg_store.push_patch(patch);
                                     }
                                 }
@@ -192,6 +236,7 @@ impl StandardCodingAgentExecutor for Amp {
                                 entry_type: NormalizedEntryType::SystemMessage,
                                 content: format!("Raw output: {trimmed}"),
                                 metadata: None,
+                                parent_id: None,
                             };
 
                             let new_id = entry_index_provider.next();
@@ -202,6 +247,16 @@ impl StandardCodingAgentExecutor for Amp {
                 };
             }
         });
+        vec![stderr, stdout]
+    }
+}
+
+/// Outcome of a finished tool run, `None` while it is still going
+fn subagent_status(run: &serde_json::Value) -> Option<SubagentStatus> {
+    match run.get("status").and_then(|status| status.as_str())? {
+        "done" => Some(SubagentStatus::Completed),
+        "error" | "cancelled" | "rejected-by-user" => Some(SubagentStatus::Failed),
+        _ => None,
     }
 }
 
@@ -419,6 +474,7 @@ impl AmpContentItem {
                     entry_type,
                     content: text.clone(),
                     metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
+                    parent_id: None,
                 })
             }
             AmpContentItem::Thinking { thinking } => Some(NormalizedEntry {
@@ -426,11 +482,15 @@ impl AmpContentItem {
                 entry_type: NormalizedEntryType::Thinking,
                 content: thinking.clone(),
                 metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
+                parent_id: None,
             }),
-            AmpContentItem::ToolUse { tool_data, .. } => {
+            AmpContentItem::ToolUse { id, tool_data } => {
                 let name = tool_data.get_name();
                 let input = tool_data;
-                let action_type = Self::extract_action_type(name, input, worktree_path);
+                let mut action_type = Self::extract_action_type(name, input, worktree_path);
+                if let ActionType::TaskCreate { id: task_id, .. } = &mut action_type {
+                    *task_id = Some(id.clone());
+                }
                 let content =
                     Self::generate_concise_content(name, input, &action_type, worktree_path);
 
@@ -442,6 +502,7 @@ impl AmpContentItem {
                     },
                     content,
                     metadata: Some(serde_json::to_value(self).unwrap_or(Value::Null)),
+                    parent_id: None,
                 })
             }
             AmpContentItem::ToolResult { .. } => None,
@@ -503,6 +564,9 @@ impl AmpContentItem {
             AmpToolData::WebSearch { query, .. } => ActionType::WebFetch { url: query.clone() },
             AmpToolData::Task { description, .. } => ActionType::TaskCreate {
                 description: description.clone(),
+                id: None,
+                status: SubagentStatus::Running,
+                duration_ms: None,
             },
             AmpToolData::Glob { .. } => ActionType::Other {
                 description: "File pattern search".to_string(),
@@ -541,11 +605,13 @@ impl AmpContentItem {
         match action_type {
             ActionType::FileRead { path } => format!("`{path}`"),
             ActionType::FileEdit { path, .. } => format!("`{path}`"),
-            ActionType::CommandRun { command } => format!("`{command}`"),
+            ActionType::CommandRun { command } | ActionType::TestRun { command, .. } => {
+                format!("`{command}`")
+            }
             ActionType::Search { query } => format!("`{query}`"),
             ActionType::WebFetch { url } => format!("`{url}`"),
             ActionType::PlanPresentation { plan } => format!("Plan Presentation: `{plan}`"),
-            ActionType::TaskCreate { description } => description.clone(),
+            ActionType::TaskCreate { description, .. } => description.clone(),
             ActionType::TodoManagement { .. } => "TODO list updated".to_string(),
             ActionType::Other { description: _ } => {
                 // For other tools, try to extract key information or fall back to tool name
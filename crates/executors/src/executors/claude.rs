@@ -1,25 +1,26 @@
-use std::{fs, path::PathBuf, process::Stdio, sync::Arc};
+use std::{collections::HashMap, fs, path::PathBuf, process::Stdio, sync::Arc};
 
 use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::{io::AsyncWriteExt, process::Command, task::JoinHandle};
 use ts_rs::TS;
 use utils::{
     diff::{concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk},
     log_msg::LogMsg,
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
+    shell::{get_shell_command, shell_command_line},
 };
 
 use crate::{
     command::CommandBuilder,
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
+        ActionType, FileChange, NormalizedEntry, NormalizedEntryType, SubagentStatus, TodoItem,
         stderr_processor::normalize_stderr_logs,
+        test_run::enrich_command_entry,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
 };
@@ -51,13 +52,12 @@ impl StandardCodingAgentExecutor for ClaudeCode {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&claude_command);
+            .arg(shell_command_line(&claude_command));
 
         let mut child = command.group_spawn()?;
 
@@ -133,13 +133,12 @@ impl StandardCodingAgentExecutor for ClaudeCode {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&claude_command);
+            .arg(shell_command_line(&claude_command));
 
         let mut child = command.group_spawn()?;
 
@@ -152,11 +151,15 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         Ok(child)
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &PathBuf) {
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        current_dir: &PathBuf,
+    ) -> Vec<JoinHandle<()>> {
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
 
         // Process stdout logs (Claude's JSON output)
-        ClaudeLogProcessor::process_logs(
+        let stdout = ClaudeLogProcessor::process_logs(
             self,
             msg_store.clone(),
             current_dir,
@@ -164,7 +167,8 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         );
 
         // Process stderr logs using the standard stderr processor
-        normalize_stderr_logs(msg_store, entry_index_provider);
+        let stderr = normalize_stderr_logs(msg_store, entry_index_provider);
+        vec![stdout, stderr]
     }
 }
 
@@ -445,11 +449,16 @@ exit "$exit_code"
 /// Handles log processing and interpretation for Claude executor
 struct ClaudeLogProcessor {
     model_name: Option<String>,
+    /// Tool uses waiting for their result, with the index of their entry
+    pending_tool_uses: HashMap<String, (usize, NormalizedEntry)>,
 }
 
 impl ClaudeLogProcessor {
     fn new() -> Self {
-        Self { model_name: None }
+        Self {
+            model_name: None,
+            pending_tool_uses: HashMap::new(),
+        }
     }
 
     /// Process raw logs and convert them to normalized entries with patches
@@ -458,7 +467,7 @@ impl ClaudeLogProcessor {
         msg_store: Arc<MsgStore>,
         current_dir: &PathBuf,
         entry_index_provider: EntryIndexProvider,
-    ) {
+    ) -> JoinHandle<()> {
         let current_dir_clone = current_dir.clone();
         tokio::spawn(async move {
             let mut stream = msg_store.history_plus_stream();
@@ -470,7 +479,10 @@ impl ClaudeLogProcessor {
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
                     LogMsg::Stdout(x) => x,
-                    LogMsg::JsonPatch(_) | LogMsg::SessionId(_) | LogMsg::Stderr(_) => continue,
+                    LogMsg::JsonPatch(_)
+                    | LogMsg::SessionId(_)
+                    | LogMsg::Stderr(_)
+                    | LogMsg::Evicted(_) => continue,
                     LogMsg::Finished => break,
                 };
 
@@ -511,10 +523,16 @@ impl ClaudeLogProcessor {
                                 processor.to_normalized_entries(&claude_json, &worktree_path)
                             {
                                 let patch_id = entry_index_provider.next();
+                                processor.track_tool_use(patch_id, &entry);
                                 let patch =
                                     ConversationPatch::add_normalized_entry(patch_id, entry);
                                 msg_store.push_patch(patch);
                             }
+
+                            // Update entries whose tool use has finished
+                            for (patch_id, entry) in processor.finish_tool_uses(&claude_json) {
+                                msg_store.push_patch(ConversationPatch::replace(patch_id, entry));
+                            }
                         }
                         Err(_) => {
                             // Handle non-JSON output as raw system message
@@ -524,6 +542,7 @@ impl ClaudeLogProcessor {
                                     entry_type: NormalizedEntryType::SystemMessage,
                                     content: format!("Raw output: {trimmed}"),
                                     metadata: None,
+                                    parent_id: None,
                                 };
 
                                 let patch_id = entry_index_provider.next();
@@ -546,13 +565,14 @@ impl ClaudeLogProcessor {
                     entry_type: NormalizedEntryType::SystemMessage,
                     content: format!("Raw output: {}", buffer.trim()),
                     metadata: None,
+                    parent_id: None,
                 };
 
                 let patch_id = entry_index_provider.next();
                 let patch = ConversationPatch::add_normalized_entry(patch_id, entry);
                 msg_store.push_patch(patch);
             }
-        });
+        })
     }
 
     /// Extract session ID from Claude JSON
@@ -568,6 +588,84 @@ impl ClaudeLogProcessor {
         }
     }
 
+    /// Remember Task and command tool uses, their entries are updated when the
+    /// tool result arrives
+    fn track_tool_use(&mut self, patch_id: usize, entry: &NormalizedEntry) {
+        let id = match &entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::TaskCreate { id: Some(id), .. },
+                ..
+            } => id.clone(),
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { .. },
+                ..
+            } => match entry
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("id"))
+                .and_then(|id| id.as_str())
+            {
+                Some(id) => id.to_string(),
+                None => return,
+            },
+            _ => return,
+        };
+        self.pending_tool_uses.insert(id, (patch_id, entry.clone()));
+    }
+
+    /// Entries updated by the tool results in a user message: finished Task
+    /// entries get their status and duration, commands whose output is a test
+    /// run become test runs
+    fn finish_tool_uses(&mut self, claude_json: &ClaudeJson) -> Vec<(usize, NormalizedEntry)> {
+        let ClaudeJson::User {
+            message,
+            tool_use_result,
+            ..
+        } = claude_json
+        else {
+            return vec![];
+        };
+        // Claude reports the subagent's run time alongside the tool result
+        let duration_ms = tool_use_result
+            .as_ref()
+            .and_then(|result| result.get("totalDurationMs"))
+            .and_then(|ms| ms.as_u64());
+
+        let mut finished = Vec::new();
+        for content_item in &message.content {
+            let ClaudeContentItem::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } = content_item
+            else {
+                continue;
+            };
+            let Some((patch_id, mut entry)) = self.pending_tool_uses.remove(tool_use_id) else {
+                continue;
+            };
+            if let NormalizedEntryType::ToolUse {
+                action_type: ActionType::CommandRun { .. },
+                ..
+            } = &entry.entry_type
+            {
+                finished.extend(
+                    enrich_command_entry(&entry, &tool_result_text(content))
+                        .map(|test_run| (patch_id, test_run)),
+                );
+                continue;
+            }
+            let status = if is_error.unwrap_or(false) {
+                SubagentStatus::Failed
+            } else {
+                SubagentStatus::Completed
+            };
+            entry.finish_subagent_task(status, duration_ms);
+            finished.push((patch_id, entry));
+        }
+        finished
+    }
+
     /// Convert Claude JSON to normalized entries
     fn to_normalized_entries(
         &mut self,
@@ -593,9 +691,14 @@ impl ClaudeLogProcessor {
                     metadata: Some(
                         serde_json::to_value(claude_json).unwrap_or(serde_json::Value::Null),
                     ),
+                    parent_id: None,
                 }]
             }
-            ClaudeJson::Assistant { message, .. } => {
+            ClaudeJson::Assistant {
+                message,
+                parent_tool_use_id,
+                ..
+            } => {
                 let mut entries = Vec::new();
 
                 if self.model_name.is_none()
@@ -607,15 +710,18 @@ impl ClaudeLogProcessor {
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: format!("System initialized with model: {model}"),
                         metadata: None,
+                        parent_id: None,
                     });
                 }
 
                 for content_item in &message.content {
-                    if let Some(entry) = Self::content_item_to_normalized_entry(
+                    if let Some(mut entry) = Self::content_item_to_normalized_entry(
                         content_item,
                         "assistant",
                         worktree_path,
                     ) {
+                        // Messages from a subagent name the Task tool use that started it
+                        entry.parent_id = parent_tool_use_id.clone();
                         entries.push(entry);
                     }
                 }
@@ -640,6 +746,7 @@ impl ClaudeLogProcessor {
                     metadata: Some(
                         serde_json::to_value(claude_json).unwrap_or(serde_json::Value::Null),
                     ),
+                    parent_id: None,
                 }]
             }
             ClaudeJson::ToolResult { .. } => {
@@ -656,6 +763,7 @@ impl ClaudeLogProcessor {
                     entry_type: NormalizedEntryType::SystemMessage,
                     content: "Unrecognized JSON message from Claude".to_string(),
                     metadata: None,
+                    parent_id: None,
                 }]
             }
         }
@@ -680,6 +788,7 @@ impl ClaudeLogProcessor {
                     metadata: Some(
                         serde_json::to_value(content_item).unwrap_or(serde_json::Value::Null),
                     ),
+                    parent_id: None,
                 })
             }
             ClaudeContentItem::Thinking { thinking } => Some(NormalizedEntry {
@@ -689,10 +798,14 @@ impl ClaudeLogProcessor {
                 metadata: Some(
                     serde_json::to_value(content_item).unwrap_or(serde_json::Value::Null),
                 ),
+                parent_id: None,
             }),
-            ClaudeContentItem::ToolUse { tool_data, .. } => {
+            ClaudeContentItem::ToolUse { id, tool_data } => {
                 let name = tool_data.get_name();
-                let action_type = Self::extract_action_type(tool_data, worktree_path);
+                let mut action_type = Self::extract_action_type(tool_data, worktree_path);
+                if let ActionType::TaskCreate { id: task_id, .. } = &mut action_type {
+                    *task_id = Some(id.clone());
+                }
                 let content =
                     Self::generate_concise_content(tool_data, &action_type, worktree_path);
 
@@ -706,6 +819,7 @@ impl ClaudeLogProcessor {
                     metadata: Some(
                         serde_json::to_value(content_item).unwrap_or(serde_json::Value::Null),
                     ),
+                    parent_id: None,
                 })
             }
             ClaudeContentItem::ToolResult { .. } => {
@@ -794,6 +908,9 @@ impl ClaudeLogProcessor {
                 };
                 ActionType::TaskCreate {
                     description: task_description,
+                    id: None,
+                    status: SubagentStatus::Running,
+                    duration_ms: None,
                 }
             }
             ClaudeToolData::ExitPlanMode { plan } => {
@@ -835,10 +952,12 @@ impl ClaudeLogProcessor {
         match action_type {
             ActionType::FileRead { path } => format!("`{path}`"),
             ActionType::FileEdit { path, .. } => format!("`{path}`"),
-            ActionType::CommandRun { command } => format!("`{command}`"),
+            ActionType::CommandRun { command } | ActionType::TestRun { command, .. } => {
+                format!("`{command}`")
+            }
             ActionType::Search { query } => format!("`{query}`"),
             ActionType::WebFetch { url } => format!("`{url}`"),
-            ActionType::TaskCreate { description } => description.clone(),
+            ActionType::TaskCreate { description, .. } => description.clone(),
             ActionType::PlanPresentation { plan } => plan.clone(),
             ActionType::TodoManagement { .. } => "TODO list updated".to_string(),
             ActionType::Other { description: _ } => match tool_data {
@@ -867,6 +986,19 @@ impl ClaudeLogProcessor {
     }
 }
 
+/// Text of a tool result, which is either a string or a list of content blocks
+fn tool_result_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(|text| text.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
 // Data structures for parsing Claude's JSON output format
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type")]
@@ -883,11 +1015,20 @@ pub enum ClaudeJson {
     Assistant {
         message: ClaudeMessage,
         session_id: Option<String>,
+        /// Set on messages produced by a subagent
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        parent_tool_use_id: Option<String>,
     },
     #[serde(rename = "user")]
     User {
         message: ClaudeMessage,
         session_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        parent_tool_use_id: Option<String>,
+        /// Structured result of the tool, for Task this includes the
+        /// subagent's run time
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_use_result: Option<serde_json::Value>,
     },
     #[serde(rename = "tool_use")]
     ToolUse {
@@ -1364,4 +1505,73 @@ mod tests {
 
         // ToolResult entry is ignored - no third entry
     }
+
+    #[test]
+    fn test_subagent_entries_nest_under_task() {
+        let mut processor = ClaudeLogProcessor::new();
+
+        let task_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_task","name":"Task","input":{"subagent_type":"general-purpose","description":"Find usages","prompt":"Find all usages of foo"}}]}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(task_json).unwrap();
+        let entries = processor.to_normalized_entries(&parsed, "");
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(
+            &entries[0].entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::TaskCreate { id: Some(id), status: SubagentStatus::Running, .. },
+                ..
+            } if id == "toolu_task"
+        ));
+        processor.track_tool_use(3, &entries[0]);
+
+        let subagent_json = r#"{"type":"assistant","parent_tool_use_id":"toolu_task","message":{"role":"assistant","content":[{"type":"text","text":"Searching"}]}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(subagent_json).unwrap();
+        let entries = processor.to_normalized_entries(&parsed, "");
+        assert_eq!(entries[0].parent_id.as_deref(), Some("toolu_task"));
+
+        let result_json = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_task","content":"Done","is_error":false}]},"tool_use_result":{"totalDurationMs":4200}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        let finished = processor.finish_tool_uses(&parsed);
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].0, 3);
+        assert!(matches!(
+            finished[0].1.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::TaskCreate {
+                    status: SubagentStatus::Completed,
+                    duration_ms: Some(4200),
+                    ..
+                },
+                ..
+            }
+        ));
+
+        // Each task finishes once
+        assert!(processor.finish_tool_uses(&parsed).is_empty());
+    }
+
+    #[test]
+    fn test_bash_test_output_becomes_test_run() {
+        let mut processor = ClaudeLogProcessor::new();
+
+        let bash_json = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_bash","name":"Bash","input":{"command":"cargo test"}}]}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(bash_json).unwrap();
+        let entries = processor.to_normalized_entries(&parsed, "");
+        processor.track_tool_use(5, &entries[0]);
+
+        let result_json = r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_bash","content":[{"type":"text","text":"test result: FAILED. 4 passed; 1 failed; 0 ignored; 0 measured"}],"is_error":true}]}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+        let finished = processor.finish_tool_uses(&parsed);
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].0, 5);
+        match &finished[0].1.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::TestRun { command, summary },
+                ..
+            } => {
+                assert_eq!(command, "cargo test");
+                assert_eq!((summary.passed, summary.failed), (4, 1));
+            }
+            other => panic!("expected a test run, got {other:?}"),
+        }
+    }
 }
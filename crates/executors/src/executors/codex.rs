@@ -1,17 +1,17 @@
-use std::{path::PathBuf, process::Stdio, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, process::Stdio, sync::Arc};
 
 use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::{io::AsyncWriteExt, process::Command, task::JoinHandle};
 use ts_rs::TS;
 use utils::{
     diff::{concatenate_diff_hunks, extract_unified_diff_hunks},
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
+    shell::{get_shell_command, shell_command_line},
 };
 
 use crate::{
@@ -19,6 +19,7 @@ use crate::{
     executors::{ExecutorError, StandardCodingAgentExecutor},
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType,
+        test_run::enrich_command_entry,
         utils::{EntryIndexProvider, patch::ConversationPatch},
     },
 };
@@ -28,7 +29,7 @@ pub struct SessionHandler;
 
 impl SessionHandler {
     /// Start monitoring stderr lines for session ID extraction
-    pub fn start_session_id_extraction(msg_store: Arc<MsgStore>) {
+    pub fn start_session_id_extraction(msg_store: Arc<MsgStore>) -> JoinHandle<()> {
         tokio::spawn(async move {
             let mut stderr_lines_stream = msg_store.stderr_lines_stream();
 
@@ -37,7 +38,7 @@ impl SessionHandler {
                     msg_store.push_session_id(session_id);
                 }
             }
-        });
+        })
     }
 
     /// Extract session ID from codex stderr output
@@ -125,13 +126,12 @@ impl StandardCodingAgentExecutor for Codex {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&codex_command)
+            .arg(shell_command_line(&codex_command))
             .env("NODE_NO_WARNINGS", "1")
             .env("RUST_LOG", "info");
 
@@ -168,13 +168,12 @@ impl StandardCodingAgentExecutor for Codex {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&codex_command)
+            .arg(shell_command_line(&codex_command))
             .env("NODE_NO_WARNINGS", "1")
             .env("RUST_LOG", "info");
 
@@ -189,16 +188,22 @@ impl StandardCodingAgentExecutor for Codex {
         Ok(child)
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, current_dir: &PathBuf) {
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        current_dir: &PathBuf,
+    ) -> Vec<JoinHandle<()>> {
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
 
         // Process stderr logs for session extraction only (errors come through JSONL)
-        SessionHandler::start_session_id_extraction(msg_store.clone());
+        let stderr = SessionHandler::start_session_id_extraction(msg_store.clone());
 
         // Process stdout logs (Codex's JSONL output)
         let current_dir = current_dir.clone();
-        tokio::spawn(async move {
+        let stdout = tokio::spawn(async move {
             let mut stream = msg_store.stdout_lines_stream();
+            // Commands waiting for their output, by call id
+            let mut pending_commands: HashMap<String, (usize, NormalizedEntry)> = HashMap::new();
 
             while let Some(Ok(line)) = stream.next().await {
                 let trimmed = line.trim();
@@ -206,16 +211,49 @@ impl StandardCodingAgentExecutor for Codex {
                     continue;
                 }
 
-                if let Ok(entries) = serde_json::from_str::<CodexJson>(trimmed).map(|codex_json| {
-                    codex_json
+                if let Ok(codex_json) = serde_json::from_str::<CodexJson>(trimmed) {
+                    let entries = codex_json
                         .to_normalized_entries(&current_dir)
-                        .unwrap_or_default()
-                }) {
+                        .unwrap_or_default();
                     for entry in entries {
                         let new_id = entry_index_provider.next();
+                        if let CodexJson::StructuredMessage {
+                            msg:
+                                CodexMsgContent::ExecCommandBegin {
+                                    call_id: Some(call_id),
+                                    ..
+                                },
+                            ..
+                        } = &codex_json
+                        {
+                            pending_commands.insert(call_id.clone(), (new_id, entry.clone()));
+                        }
                         let patch = ConversationPatch::add_normalized_entry(new_id, entry);
                         msg_store.push_patch(patch);
                     }
+
+                    // Commands whose output is a test run become test runs
+                    if let CodexJson::StructuredMessage {
+                        msg:
+                            CodexMsgContent::ExecCommandEnd {
+                                call_id: Some(call_id),
+                                stdout,
+                                stderr,
+                                ..
+                            },
+                        ..
+                    } = &codex_json
+                        && let Some((patch_id, entry)) = pending_commands.remove(call_id)
+                    {
+                        let output = format!(
+                            "{}\n{}",
+                            stdout.as_deref().unwrap_or_default(),
+                            stderr.as_deref().unwrap_or_default()
+                        );
+                        if let Some(test_run) = enrich_command_entry(&entry, &output) {
+                            msg_store.push_patch(ConversationPatch::replace(patch_id, test_run));
+                        }
+                    }
                 } else {
                     // Handle malformed JSON as raw output
                     let entry = NormalizedEntry {
@@ -223,6 +261,7 @@ impl StandardCodingAgentExecutor for Codex {
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: format!("Raw output: {trimmed}"),
                         metadata: None,
+                        parent_id: None,
                     };
 
                     let new_id = entry_index_provider.next();
@@ -231,6 +270,7 @@ impl StandardCodingAgentExecutor for Codex {
                 }
             }
         });
+        vec![stderr, stdout]
     }
 }
 
@@ -420,6 +460,7 @@ impl CodexJson {
                     entry_type: NormalizedEntryType::SystemMessage,
                     content,
                     metadata: Some(serde_json::to_value(self).unwrap_or(serde_json::Value::Null)),
+                    parent_id: None,
                 }]
             }),
             CodexJson::Prompt { .. } => None, // Skip prompt messages
@@ -432,12 +473,14 @@ impl CodexJson {
                         entry_type: NormalizedEntryType::AssistantMessage,
                         content: message.clone(),
                         metadata: None,
+                        parent_id: None,
                     }]),
                     CodexMsgContent::AgentReasoning { text } => Some(vec![NormalizedEntry {
                         timestamp: None,
                         entry_type: NormalizedEntryType::Thinking,
                         content: text.clone(),
                         metadata: None,
+                        parent_id: None,
                     }]),
                     CodexMsgContent::Error { message } => {
                         let error_message = message
@@ -448,6 +491,7 @@ impl CodexJson {
                             entry_type: NormalizedEntryType::ErrorMessage,
                             content: error_message,
                             metadata: None,
+                            parent_id: None,
                         }])
                     }
                     CodexMsgContent::ExecCommandBegin { command, .. } => {
@@ -470,6 +514,7 @@ impl CodexJson {
                             },
                             content: format!("`{command_str}`"),
                             metadata: None,
+                            parent_id: None,
                         }])
                     }
                     CodexMsgContent::PatchApplyBegin { changes, .. } => {
@@ -528,6 +573,7 @@ impl CodexJson {
                                 },
                                 content: relative_path,
                                 metadata: None,
+                                parent_id: None,
                             });
                         }
 
@@ -550,6 +596,7 @@ impl CodexJson {
                             },
                             content,
                             metadata: None,
+                            parent_id: None,
                         }])
                     }
                     CodexMsgContent::ExecApprovalRequest {
@@ -573,6 +620,7 @@ impl CodexJson {
                             entry_type: NormalizedEntryType::SystemMessage,
                             content,
                             metadata: None,
+                            parent_id: None,
                         }])
                     }
                     CodexMsgContent::ApplyPatchApprovalRequest {
@@ -594,6 +642,7 @@ impl CodexJson {
                             entry_type: NormalizedEntryType::SystemMessage,
                             content,
                             metadata: None,
+                            parent_id: None,
                         }])
                     }
                     CodexMsgContent::PlanUpdate { value } => Some(vec![NormalizedEntry {
@@ -601,6 +650,7 @@ impl CodexJson {
                         entry_type: NormalizedEntryType::SystemMessage,
                         content: "Plan update".to_string(),
                         metadata: Some(value.clone()),
+                        parent_id: None,
                     }]),
 
                     // Ignored message types
@@ -689,6 +739,7 @@ mod tests {
                     entry_type: NormalizedEntryType::SystemMessage,
                     content: format!("Raw output: {trimmed}"),
                     metadata: None,
+                    parent_id: None,
                 });
             }
         }
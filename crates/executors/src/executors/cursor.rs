@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::{io::AsyncWriteExt, process::Command, task::JoinHandle};
 use ts_rs::TS;
 use utils::{
     diff::{
@@ -14,7 +14,7 @@ use utils::{
     },
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
+    shell::{get_shell_command, shell_command_line},
 };
 
 use crate::{
@@ -48,13 +48,12 @@ impl StandardCodingAgentExecutor for Cursor {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&agent_cmd);
+            .arg(shell_command_line(&agent_cmd));
 
         let mut child = command.group_spawn()?;
 
@@ -81,13 +80,12 @@ impl StandardCodingAgentExecutor for Cursor {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&agent_cmd);
+            .arg(shell_command_line(&agent_cmd));
 
         let mut child = command.group_spawn()?;
 
@@ -99,12 +97,16 @@ impl StandardCodingAgentExecutor for Cursor {
         Ok(child)
     }
 
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &PathBuf) {
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        worktree_path: &PathBuf,
+    ) -> Vec<JoinHandle<()>> {
         let entry_index_provider = EntryIndexProvider::start_from(&msg_store);
 
         // Process Cursor stdout JSONL with typed serde models
         let current_dir = worktree_path.clone();
-        tokio::spawn(async move {
+        let stdout = tokio::spawn(async move {
             let mut lines = msg_store.stdout_lines_stream();
 
             // Cursor agent doesn't use STDERR. Everything comes through STDOUT, both JSONL and raw error output.
@@ -114,6 +116,7 @@ impl StandardCodingAgentExecutor for Cursor {
                     entry_type: NormalizedEntryType::ErrorMessage,
                     content,
                     metadata: None,
+                    parent_id: None,
                 }))
                 .time_gap(Duration::from_secs(2)) // Break messages if they are 2 seconds apart
                 .index_provider(entry_index_provider.clone())
@@ -175,6 +178,7 @@ impl StandardCodingAgentExecutor for Cursor {
                                 entry_type: NormalizedEntryType::SystemMessage,
                                 content: format!("System initialized with model: {model}"),
                                 metadata: None,
+                                parent_id: None,
                             };
                             let id = entry_index_provider.next();
                             msg_store
@@ -193,6 +197,7 @@ impl StandardCodingAgentExecutor for Cursor {
                                 entry_type: NormalizedEntryType::AssistantMessage,
                                 content: current_assistant_message_buffer.clone(),
                                 metadata: None,
+                                parent_id: None,
                             };
                             if let Some(id) = current_assistant_message_index {
                                 msg_store.push_patch(ConversationPatch::replace(id, replace_entry))
@@ -228,6 +233,7 @@ impl StandardCodingAgentExecutor for Cursor {
                                 },
                                 content,
                                 metadata: None,
+                                parent_id: None,
                             };
                             let id = entry_index_provider.next();
                             msg_store
@@ -245,6 +251,7 @@ impl StandardCodingAgentExecutor for Cursor {
                             entry_type: NormalizedEntryType::SystemMessage,
                             content: format!("Raw output: `{line}`"),
                             metadata: None,
+                            parent_id: None,
                         };
                         let id = entry_index_provider.next();
                         msg_store.push_patch(ConversationPatch::add_normalized_entry(id, entry));
@@ -252,6 +259,7 @@ impl StandardCodingAgentExecutor for Cursor {
                 }
             }
         });
+        vec![stdout]
     }
 }
 
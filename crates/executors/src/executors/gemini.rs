@@ -8,9 +8,13 @@ use tokio::{
     fs::{self, OpenOptions},
     io::AsyncWriteExt,
     process::Command,
+    task::JoinHandle,
 };
 use ts_rs::TS;
-use utils::{msg_store::MsgStore, shell::get_shell_command};
+use utils::{
+    msg_store::MsgStore,
+    shell::{get_shell_command, shell_command_line},
+};
 
 use crate::{
     command::CommandBuilder,
@@ -43,13 +47,12 @@ impl StandardCodingAgentExecutor for Gemini {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(gemini_command)
+            .arg(shell_command_line(&gemini_command))
             .env("NODE_NO_WARNINGS", "1");
 
         let mut child = command.group_spawn()?;
@@ -87,13 +90,12 @@ impl StandardCodingAgentExecutor for Gemini {
         let mut command = Command::new(shell_cmd);
 
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(gemini_command)
+            .arg(shell_command_line(&gemini_command))
             .env("NODE_NO_WARNINGS", "1");
 
         let mut child = command.group_spawn()?;
@@ -134,9 +136,13 @@ impl StandardCodingAgentExecutor for Gemini {
     /// Sets up log normalization for the Gemini executor:
     /// - stderr via [`normalize_stderr_logs`]
     /// - stdout via [`PlainTextLogProcessor`] with Gemini-specific formatting and default heuristics
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &PathBuf) {
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        worktree_path: &PathBuf,
+    ) -> Vec<JoinHandle<()>> {
         let entry_index_counter = EntryIndexProvider::start_from(&msg_store);
-        normalize_stderr_logs(msg_store.clone(), entry_index_counter.clone());
+        let stderr = normalize_stderr_logs(msg_store.clone(), entry_index_counter.clone());
 
         // Send session ID to msg_store to enable follow-ups
         msg_store.push_session_id(
@@ -148,7 +154,7 @@ impl StandardCodingAgentExecutor for Gemini {
         );
 
         // Normalize Agent logs
-        tokio::spawn(async move {
+        let stdout = tokio::spawn(async move {
             let mut stdout = msg_store.stdout_chunked_stream();
 
             // Create a processor with Gemini-specific formatting
@@ -158,6 +164,7 @@ impl StandardCodingAgentExecutor for Gemini {
                     entry_type: NormalizedEntryType::AssistantMessage,
                     content,
                     metadata: None,
+                    parent_id: None,
                 }))
                 .format_chunk(Box::new(|partial_line: Option<&str>, chunk: String| {
                     Self::format_stdout_chunk(&chunk, partial_line.unwrap_or(""))
@@ -177,6 +184,7 @@ impl StandardCodingAgentExecutor for Gemini {
                 }
             }
         });
+        vec![stderr, stdout]
     }
 }
 
@@ -255,6 +263,7 @@ impl Gemini {
             entry_type: NormalizedEntryType::UserMessage,
             content: prompt,
             metadata: None,
+            parent_id: None,
         })
         .unwrap_or_default();
         user_message_json.push('\n');
@@ -273,6 +282,7 @@ impl Gemini {
             entry_type: NormalizedEntryType::AssistantMessage,
             content: stdout_content,
             metadata: None,
+            parent_id: None,
         })
         .unwrap_or_default();
         assistant_message_json.push('\n');
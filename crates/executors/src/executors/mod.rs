@@ -6,6 +6,7 @@ use enum_dispatch::enum_dispatch;
 use futures_io::Error as FuturesIoError;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::task::JoinHandle;
 use ts_rs::TS;
 use utils::msg_store::MsgStore;
 
@@ -182,5 +183,11 @@ pub trait StandardCodingAgentExecutor {
         prompt: &str,
         session_id: &str,
     ) -> Result<AsyncGroupChild, ExecutorError>;
-    fn normalize_logs(&self, _raw_logs_event_store: Arc<MsgStore>, _worktree_path: &PathBuf);
+    /// Start normalizing the raw logs in the store into conversation patches. The
+    /// returned tasks finish once the store is finished and its logs are normalized.
+    fn normalize_logs(
+        &self,
+        _raw_logs_event_store: Arc<MsgStore>,
+        _worktree_path: &PathBuf,
+    ) -> Vec<JoinHandle<()>>;
 }
@@ -7,11 +7,13 @@ use futures::{StreamExt, future::ready, stream::BoxStream};
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::{io::AsyncWriteExt, process::Command, task::JoinHandle};
 use ts_rs::TS;
 use utils::{
-    diff::create_unified_diff, msg_store::MsgStore, path::make_path_relative,
-    shell::get_shell_command,
+    diff::create_unified_diff,
+    msg_store::MsgStore,
+    path::make_path_relative,
+    shell::{get_shell_command, shell_command_line},
 };
 
 use crate::{
@@ -45,13 +47,12 @@ impl StandardCodingAgentExecutor for Opencode {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped()) // Keep stdout but we won't use it
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(opencode_command)
+            .arg(shell_command_line(&opencode_command))
             .env("NODE_NO_WARNINGS", "1");
 
         let mut child = command.group_spawn()?;
@@ -80,13 +81,12 @@ impl StandardCodingAgentExecutor for Opencode {
 
         let mut command = Command::new(shell_cmd);
         command
-            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped()) // Keep stdout but we won't use it
             .stderr(Stdio::piped())
             .current_dir(current_dir)
             .arg(shell_arg)
-            .arg(&opencode_command)
+            .arg(shell_command_line(&opencode_command))
             .env("NODE_NO_WARNINGS", "1");
 
         let mut child = command.group_spawn()?;
@@ -107,7 +107,11 @@ impl StandardCodingAgentExecutor for Opencode {
     /// 2. Error log recognition thread: read by line, identify error log lines, store them as error messages.
     /// 3. Main normalizer thread: read stderr by line, filter out log lines, send lines (with '\n' appended) to plain text normalizer,
     ///    then define predicate for split and create appropriate normalized entry (either assistant or tool call).
-    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &PathBuf) {
+    fn normalize_logs(
+        &self,
+        msg_store: Arc<MsgStore>,
+        worktree_path: &PathBuf,
+    ) -> Vec<JoinHandle<()>> {
         let entry_index_counter = EntryIndexProvider::start_from(&msg_store);
         let worktree_path = worktree_path.clone();
 
@@ -127,7 +131,7 @@ impl StandardCodingAgentExecutor for Opencode {
             .boxed();
 
         // Process log lines, which contain error messages and session ID
-        tokio::spawn(Self::process_opencode_log_lines(
+        let log_lines = tokio::spawn(Self::process_opencode_log_lines(
             log_lines,
             msg_store.clone(),
             entry_index_counter.clone(),
@@ -144,12 +148,13 @@ impl StandardCodingAgentExecutor for Opencode {
             .boxed();
 
         // Normalize agent logs
-        tokio::spawn(Self::process_agent_logs(
+        let agent_logs = tokio::spawn(Self::process_agent_logs(
             agent_logs,
             worktree_path,
             entry_index_counter,
             msg_store,
         ));
+        vec![log_lines, agent_logs]
     }
 }
 impl Opencode {
@@ -169,6 +174,7 @@ impl Opencode {
                     entry_type: NormalizedEntryType::ErrorMessage,
                     content: line.clone(),
                     metadata: None,
+                    parent_id: None,
                 };
 
                 // Create a patch for this single entry
@@ -229,6 +235,7 @@ impl Opencode {
                 },
                 content: tool_content,
                 metadata: None,
+                parent_id: None,
             };
         }
 
@@ -238,6 +245,7 @@ impl Opencode {
             entry_type: NormalizedEntryType::AssistantMessage,
             content,
             metadata: None,
+            parent_id: None,
         }
     }
 
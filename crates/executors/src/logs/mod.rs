@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use crate::logs::test_run::TestRunSummary;
+
 pub mod plain_text_processor;
 pub mod stderr_processor;
+pub mod test_run;
 pub mod utils;
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -35,6 +38,30 @@ pub struct NormalizedEntry {
     pub content: String,
     #[ts(skip)]
     pub metadata: Option<serde_json::Value>,
+    /// Tool use id of the subagent task this entry was produced by, see
+    /// [`ActionType::TaskCreate`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+}
+
+impl NormalizedEntry {
+    /// Record the outcome of a subagent on its Task tool use entry, other
+    /// entries are left alone
+    pub fn finish_subagent_task(&mut self, result: SubagentStatus, elapsed_ms: Option<u64>) {
+        if let NormalizedEntryType::ToolUse {
+            action_type:
+                ActionType::TaskCreate {
+                    status,
+                    duration_ms,
+                    ..
+                },
+            ..
+        } = &mut self.entry_type
+        {
+            *status = result;
+            *duration_ms = elapsed_ms;
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -61,6 +88,11 @@ pub enum ActionType {
     CommandRun {
         command: String,
     },
+    /// A command whose output was recognized as a test run
+    TestRun {
+        command: String,
+        summary: TestRunSummary,
+    },
     Search {
         query: String,
     },
@@ -69,6 +101,13 @@ pub enum ActionType {
     },
     TaskCreate {
         description: String,
+        /// Tool use id, referenced by the `parent_id` of the subagent's entries
+        #[serde(default)]
+        id: Option<String>,
+        #[serde(default)]
+        status: SubagentStatus,
+        #[serde(default)]
+        duration_ms: Option<u64>,
     },
     PlanPresentation {
         plan: String,
@@ -82,6 +121,16 @@ pub enum ActionType {
     },
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SubagentStatus {
+    #[default]
+    Running,
+    Completed,
+    Failed,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "action", rename_all = "snake_case")]
 pub enum FileChange {
@@ -415,6 +415,7 @@ mod tests {
                 entry_type: NormalizedEntryType::SystemMessage,
                 content: content.to_string(),
                 metadata: None,
+                parent_id: None,
             }
         };
 
@@ -443,6 +444,7 @@ mod tests {
                     },
                     content,
                     metadata: None,
+                    parent_id: None,
                 }
             } else {
                 NormalizedEntry {
@@ -450,6 +452,7 @@ mod tests {
                     entry_type: NormalizedEntryType::SystemMessage,
                     content: content.to_string(),
                     metadata: None,
+                    parent_id: None,
                 }
             }
         };
@@ -471,6 +474,7 @@ mod tests {
                 entry_type: NormalizedEntryType::SystemMessage,
                 content,
                 metadata: None,
+                parent_id: None,
             }
         };
 
@@ -0,0 +1,297 @@
+//! Recognition of test runner output, so test results can be shown without
+//! reading through the command's output.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TestRunner {
+    Cargo,
+    Pytest,
+    Jest,
+    Vitest,
+}
+
+impl TestRunner {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestRunner::Cargo => "cargo",
+            TestRunner::Pytest => "pytest",
+            TestRunner::Jest => "jest",
+            TestRunner::Vitest => "vitest",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TestRunSummary {
+    pub runner: TestRunner,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    pub failing_tests: Vec<String>,
+}
+
+impl TestRunSummary {
+    /// "140 passed, 2 failed, 1 skipped"
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            format!("{} passed", self.passed),
+            format!("{} failed", self.failed),
+        ];
+        if self.skipped > 0 {
+            parts.push(format!("{} skipped", self.skipped));
+        }
+        parts.join(", ")
+    }
+}
+
+lazy_static! {
+    // test result: ok. 5 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out
+    static ref CARGO_RESULT: Regex =
+        Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored").unwrap();
+    static ref CARGO_FAILED: Regex = Regex::new(r"(?m)^test (\S+) \.\.\. FAILED").unwrap();
+    // ===== 2 failed, 140 passed, 3 skipped in 1.23s =====
+    static ref PYTEST_SUMMARY: Regex =
+        Regex::new(r"(?m)^=+ (.*\d+ (?:passed|failed|skipped|errors?).*) in [\d.]+s.* =+$").unwrap();
+    static ref PYTEST_FAILED: Regex = Regex::new(r"(?m)^(?:FAILED|ERROR) (\S+)").unwrap();
+    // Tests:       2 failed, 1 skipped, 140 passed, 143 total
+    static ref JEST_SUMMARY: Regex = Regex::new(r"(?m)^Tests:\s+(.*\d+ total)").unwrap();
+    static ref JEST_FAILED: Regex = Regex::new(r"(?m)^\s+● (.+ › .+)$").unwrap();
+    //       Tests  2 failed | 140 passed (142)
+    static ref VITEST_SUMMARY: Regex = Regex::new(r"(?m)^\s*Tests\s+(.*\(\d+\))").unwrap();
+    static ref VITEST_FAILED: Regex = Regex::new(r"(?m)^\s*(?:FAIL|×)\s+(\S.* > .+?)(?:\s+\d+ms)?$").unwrap();
+    static ref COUNT: Regex = Regex::new(r"(\d+) (passed|failed|skipped|ignored|todo|errors?)").unwrap();
+}
+
+/// Summarize a test run from a command's output, `None` if the output isn't
+/// from a recognized test runner
+pub fn parse_test_output(output: &str) -> Option<TestRunSummary> {
+    let output = strip_ansi_escapes::strip_str(output);
+    parse_cargo(&output)
+        .or_else(|| parse_pytest(&output))
+        .or_else(|| parse_jest(&output))
+        .or_else(|| parse_vitest(&output))
+}
+
+/// The entry of a finished command turned into a test run, if the command is
+/// one and its output is a test runner's
+pub fn enrich_command_entry(entry: &NormalizedEntry, output: &str) -> Option<NormalizedEntry> {
+    let NormalizedEntryType::ToolUse {
+        tool_name,
+        action_type: ActionType::CommandRun { command },
+    } = &entry.entry_type
+    else {
+        return None;
+    };
+    let summary = parse_test_output(output)?;
+    Some(NormalizedEntry {
+        entry_type: NormalizedEntryType::ToolUse {
+            tool_name: tool_name.clone(),
+            action_type: ActionType::TestRun {
+                command: command.clone(),
+                summary,
+            },
+        },
+        ..entry.clone()
+    })
+}
+
+fn parse_cargo(output: &str) -> Option<TestRunSummary> {
+    let mut summary: Option<TestRunSummary> = None;
+    // One result line per test binary
+    for caps in CARGO_RESULT.captures_iter(output) {
+        let summary = summary.get_or_insert_with(|| empty(TestRunner::Cargo));
+        summary.passed += caps[1].parse::<u32>().unwrap_or(0);
+        summary.failed += caps[2].parse::<u32>().unwrap_or(0);
+        summary.skipped += caps[3].parse::<u32>().unwrap_or(0);
+    }
+    let mut summary = summary?;
+    summary.failing_tests = failing_tests(&CARGO_FAILED, output);
+    Some(summary)
+}
+
+fn parse_pytest(output: &str) -> Option<TestRunSummary> {
+    let counts = PYTEST_SUMMARY.captures_iter(output).last()?;
+    let mut summary = counted(TestRunner::Pytest, &counts[1]);
+    summary.failing_tests = failing_tests(&PYTEST_FAILED, output);
+    Some(summary)
+}
+
+fn parse_jest(output: &str) -> Option<TestRunSummary> {
+    let counts = JEST_SUMMARY.captures_iter(output).last()?;
+    let mut summary = counted(TestRunner::Jest, &counts[1]);
+    summary.failing_tests = failing_tests(&JEST_FAILED, output);
+    Some(summary)
+}
+
+fn parse_vitest(output: &str) -> Option<TestRunSummary> {
+    let counts = VITEST_SUMMARY.captures_iter(output).last()?;
+    let mut summary = counted(TestRunner::Vitest, &counts[1]);
+    summary.failing_tests = failing_tests(&VITEST_FAILED, output);
+    Some(summary)
+}
+
+fn empty(runner: TestRunner) -> TestRunSummary {
+    TestRunSummary {
+        runner,
+        passed: 0,
+        failed: 0,
+        skipped: 0,
+        failing_tests: Vec::new(),
+    }
+}
+
+/// Counts from a summary line such as "2 failed, 140 passed, 3 skipped"
+fn counted(runner: TestRunner, line: &str) -> TestRunSummary {
+    let mut summary = empty(runner);
+    for caps in COUNT.captures_iter(line) {
+        let count = caps[1].parse::<u32>().unwrap_or(0);
+        match &caps[2] {
+            "passed" => summary.passed += count,
+            // Errors are tests that couldn't run, count them as failures
+            "failed" | "error" | "errors" => summary.failed += count,
+            _ => summary.skipped += count,
+        }
+    }
+    summary
+}
+
+fn failing_tests(pattern: &Regex, output: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for caps in pattern.captures_iter(output) {
+        let name = caps[1].trim().to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cargo_output() {
+        let output = "\
+running 3 tests
+test a::works ... ok
+test a::breaks ... FAILED
+test a::slow ... ignored
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.01s
+
+running 2 tests
+test b::works ... ok
+test b::also_works ... ok
+
+test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+";
+        let summary = parse_test_output(output).unwrap();
+        assert_eq!(summary.runner, TestRunner::Cargo);
+        assert_eq!((summary.passed, summary.failed, summary.skipped), (3, 1, 1));
+        assert_eq!(summary.failing_tests, vec!["a::breaks"]);
+    }
+
+    #[test]
+    fn parses_pytest_output() {
+        let output = "\
+tests/test_api.py ..F.s
+=========================== short test summary info ============================
+FAILED tests/test_api.py::test_login - AssertionError: expected 200
+ERROR tests/test_db.py::test_connect - ConnectionError
+============ 1 failed, 3 passed, 1 skipped, 1 error in 0.52s =============
+";
+        let summary = parse_test_output(output).unwrap();
+        assert_eq!(summary.runner, TestRunner::Pytest);
+        assert_eq!((summary.passed, summary.failed, summary.skipped), (3, 2, 1));
+        assert_eq!(
+            summary.failing_tests,
+            vec![
+                "tests/test_api.py::test_login",
+                "tests/test_db.py::test_connect"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_jest_output() {
+        let output = "\
+FAIL src/math.test.js
+  ● math › adds numbers
+
+    expect(received).toBe(expected)
+
+Test Suites: 1 failed, 4 passed, 5 total
+Tests:       1 failed, 2 skipped, 140 passed, 143 total
+";
+        let summary = parse_test_output(output).unwrap();
+        assert_eq!(summary.runner, TestRunner::Jest);
+        assert_eq!(
+            (summary.passed, summary.failed, summary.skipped),
+            (140, 1, 2)
+        );
+        assert_eq!(summary.failing_tests, vec!["math › adds numbers"]);
+    }
+
+    #[test]
+    fn parses_vitest_output() {
+        let output = "\
+ \u{1b}[31m×\u{1b}[39m src/math.test.ts > math > adds numbers 3ms
+ Test Files  1 failed | 4 passed (5)
+      Tests  1 failed | 140 passed | 1 skipped (142)
+";
+        let summary = parse_test_output(output).unwrap();
+        assert_eq!(summary.runner, TestRunner::Vitest);
+        assert_eq!(
+            (summary.passed, summary.failed, summary.skipped),
+            (140, 1, 1)
+        );
+        assert_eq!(
+            summary.failing_tests,
+            vec!["src/math.test.ts > math > adds numbers"]
+        );
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        assert!(parse_test_output("Compiling utils v0.0.66\nFinished dev").is_none());
+    }
+
+    #[test]
+    fn enriches_command_entries() {
+        let entry = NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "Bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "cargo test".to_string(),
+                },
+            },
+            content: "`cargo test`".to_string(),
+            metadata: None,
+            parent_id: None,
+        };
+        let enriched = enrich_command_entry(
+            &entry,
+            "test result: ok. 2 passed; 0 failed; 0 ignored; 0 measured",
+        )
+        .unwrap();
+        assert!(matches!(
+            enriched.entry_type,
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::TestRun { .. },
+                ..
+            }
+        ));
+        assert!(enrich_command_entry(&entry, "no tests here").is_none());
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
+
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, json};
 use ts_rs::TS;
-use utils::diff::Diff;
+use utils::{diff::Diff, log_msg::LogMsg};
 
 use crate::logs::NormalizedEntry;
 
@@ -113,3 +115,61 @@ impl ConversationPatch {
         from_value(json!([patch_entry])).unwrap()
     }
 }
+
+#[derive(Deserialize)]
+struct RawPatchEntry {
+    op: PatchOperation,
+    path: String,
+    #[serde(default)]
+    value: Option<serde_json::Value>,
+}
+
+/// The normalized entries a patch adds, replaces or removes, by entry index.
+/// Entries replaced by something other than a normalized entry count as removed.
+pub fn normalized_entry_changes(patch: &Patch) -> Vec<(usize, Option<NormalizedEntry>)> {
+    let Ok(ops) = serde_json::to_value(patch).and_then(from_value::<Vec<RawPatchEntry>>) else {
+        return Vec::new();
+    };
+    ops.into_iter()
+        .filter_map(|op| {
+            let index = op
+                .path
+                .strip_prefix("/entries/")
+                .and_then(|i| i.parse::<usize>().ok())?;
+            let entry = match op.op {
+                PatchOperation::Remove => None,
+                PatchOperation::Add | PatchOperation::Replace => op
+                    .value
+                    .filter(|v| v.get("type") == Some(&json!("NORMALIZED_ENTRY")))
+                    .and_then(|mut v| v.get_mut("content").map(serde_json::Value::take))
+                    .and_then(|content| from_value::<NormalizedEntry>(content).ok()),
+            };
+            Some((index, entry))
+        })
+        .collect()
+}
+
+/// Rebuild the normalized conversation from the patches in a message history.
+/// Stdout, stderr and diff entries are skipped.
+pub fn extract_normalized_entries(messages: &[LogMsg]) -> Vec<NormalizedEntry> {
+    extract_indexed_normalized_entries(messages)
+        .into_values()
+        .collect()
+}
+
+/// Like `extract_normalized_entries`, keeping the index each entry has in the conversation
+pub fn extract_indexed_normalized_entries(messages: &[LogMsg]) -> BTreeMap<usize, NormalizedEntry> {
+    let mut entries = BTreeMap::new();
+    for msg in messages {
+        let LogMsg::JsonPatch(patch) = msg else {
+            continue;
+        };
+        for (index, entry) in normalized_entry_changes(patch) {
+            match entry {
+                Some(entry) => entries.insert(index, entry),
+                None => entries.remove(&index),
+            };
+        }
+    }
+    entries
+}
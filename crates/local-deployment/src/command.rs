@@ -1,7 +1,7 @@
 use command_group::AsyncGroupChild;
 #[cfg(unix)]
 use nix::{
-    sys::signal::{Signal, killpg},
+    sys::signal::{Signal, kill, killpg},
     unistd::{Pid, getpgid},
 };
 use services::services::container::ContainerError;
@@ -41,3 +41,86 @@ pub async fn kill_process_group(child: &mut AsyncGroupChild) -> Result<(), Conta
     let _ = child.wait().await;
     Ok(())
 }
+
+/// When a process started, in a form that is only compared for equality. Recorded
+/// next to the pid so a pid the OS has handed to another process since isn't
+/// re-attached to or killed.
+pub fn process_start_time(pid: u32) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        // Field 22 of /proc/<pid>/stat is the start time in clock ticks since boot,
+        // the boot id tells boots apart. The command name may contain spaces, so
+        // count fields from the closing parenthesis after it.
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        let start_ticks = stat.rsplit_once(')')?.1.split_whitespace().nth(19)?;
+        let boot_id = std::fs::read_to_string("/proc/sys/kernel/random/boot_id").ok()?;
+        Some(format!("{}:{start_ticks}", boot_id.trim()))
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = std::process::Command::new("ps")
+            .args(["-o", "lstart=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        let start = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !start.is_empty()).then_some(start)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// An executor started by a previous server run, known only by its pid and
+/// start time
+#[derive(Debug, Clone)]
+pub struct DetachedProcess {
+    pub pid: u32,
+    pub start_time: String,
+}
+
+impl DetachedProcess {
+    /// Whether the pid still belongs to the process that was started
+    pub fn is_alive(&self) -> bool {
+        #[cfg(unix)]
+        {
+            kill(Pid::from_raw(self.pid as i32), None).is_ok()
+                && process_start_time(self.pid).as_deref() == Some(self.start_time.as_str())
+        }
+        #[cfg(not(unix))]
+        {
+            false
+        }
+    }
+
+    /// Like `kill_process_group`, for processes re-attached after a restart
+    pub async fn kill_process_group(&self) -> Result<(), ContainerError> {
+        if !self.is_alive() {
+            return Ok(());
+        }
+
+        #[cfg(unix)]
+        {
+            let pgid = getpgid(Some(Pid::from_raw(self.pid as i32)))
+                .map_err(|e| ContainerError::KillFailed(std::io::Error::other(e)))?;
+
+            for sig in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGKILL] {
+                if let Err(e) = killpg(pgid, sig) {
+                    tracing::warn!(
+                        "Failed to send signal {:?} to process group {}: {}",
+                        sig,
+                        pgid,
+                        e
+                    );
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                if !self.is_alive() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ use anyhow::anyhow;
 use async_stream::try_stream;
 use async_trait::async_trait;
 use axum::response::sse::Event;
+use chrono::Utc;
 use command_group::AsyncGroupChild;
 use db::{
     DBService,
@@ -17,9 +18,12 @@ use db::{
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
+        execution_queue::ExecutionQueueEntry,
         executor_session::ExecutorSession,
         merge::Merge,
         project::Project,
+        project_commit_settings::ProjectCommitSettings,
+        project_redaction_rules::ProjectRedactionRules,
         task::{Task, TaskStatus},
         task_attempt::TaskAttempt,
     },
@@ -32,21 +36,23 @@ use executors::{
         utils::{ConversationPatch, patch::escape_json_pointer_segment},
     },
 };
-use futures::{StreamExt, TryStreamExt, stream::select};
+use futures::{StreamExt, stream::select};
 use notify_debouncer_full::DebouncedEvent;
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
+    commit_settings,
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
     filesystem_watcher,
     git::{DiffTarget, GitService},
     image::ImageService,
+    log_retention,
     notification::NotificationService,
+    redaction,
     worktree_manager::WorktreeManager,
 };
 use tokio::{sync::RwLock, task::JoinHandle};
-use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
@@ -54,12 +60,18 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::command;
+use crate::{
+    command::{self, DetachedProcess},
+    log_files::{ExecutionLogFiles, LogFollower},
+};
 
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
+    /// Processes from a previous server run that we only know the pid of
+    reattached_pids: Arc<RwLock<HashMap<Uuid, DetachedProcess>>>,
+    log_followers: Arc<RwLock<HashMap<Uuid, LogFollower>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
@@ -77,10 +89,14 @@ impl LocalContainerService {
         analytics: Option<AnalyticsContext>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
+        let reattached_pids = Arc::new(RwLock::new(HashMap::new()));
+        let log_followers = Arc::new(RwLock::new(HashMap::new()));
 
         LocalContainerService {
             db,
             child_store,
+            reattached_pids,
+            log_followers,
             msg_stores,
             config,
             git,
@@ -281,11 +297,89 @@ impl LocalContainerService {
         });
     }
 
+    /// Periodically compress finished execution logs and apply the configured
+    /// log retention limits
+    pub fn spawn_log_compaction(&self) {
+        let db = self.db.clone();
+        let config = self.config.clone();
+        let mut compaction_interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // 1 hour
+        tokio::spawn(async move {
+            loop {
+                compaction_interval.tick().await;
+                let retention = config.read().await.log_retention.clone();
+                match log_retention::run(&db, &retention).await {
+                    Ok(report) => tracing::info!(
+                        "Log compaction finished: {} compacted, {} purged, {} conversations purged, {} log file directories removed",
+                        report.compacted,
+                        report.purged,
+                        report.purged_conversations,
+                        report.removed_log_files
+                    ),
+                    Err(e) => tracing::error!("Failed to compact execution logs: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Periodically start the retries of failed executions that have fallen due.
+    /// Retries are persisted, so those scheduled before a restart are picked up.
+    pub fn spawn_retry_scheduler(&self) {
+        let container = self.clone();
+        let mut retry_interval = tokio::time::interval(Duration::from_secs(5));
+        tokio::spawn(async move {
+            loop {
+                retry_interval.tick().await;
+                if let Err(e) = container.start_due_retries().await {
+                    tracing::error!("Failed to start due retries: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Retry the failed executions whose retry is due, unless their attempt was
+    /// stopped, deleted or has moved on since
+    async fn start_due_retries(&self) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        for exec_id in ExecutionProcess::find_due_retry_ids(pool, Utc::now()).await? {
+            // Stopping the attempt clears the retry, so a claimed retry wasn't cancelled
+            if !ExecutionProcess::claim_retry(pool, exec_id).await? {
+                continue;
+            }
+            let Ok(ctx) = ExecutionProcess::load_context(pool, exec_id).await else {
+                continue;
+            };
+
+            let latest = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+                pool,
+                ctx.task_attempt.id,
+                &ctx.execution_process.run_reason,
+            )
+            .await?;
+            if latest.is_some_and(|latest| latest.id != exec_id)
+                || ExecutionProcess::has_active_for_task(pool, ctx.task.id).await?
+            {
+                tracing::info!(
+                    "Dropping retry of execution process {}, the attempt has moved on",
+                    exec_id
+                );
+                continue;
+            }
+
+            if let Err(e) = self.retry_execution(&ctx).await {
+                tracing::error!("Failed to retry execution process {}: {}", exec_id, e);
+                Self::finalize_task(&self.db, &self.config, &ctx).await;
+            }
+        }
+        Ok(())
+    }
+
     /// Spawn a background task that polls the child process for completion and
     /// cleans up the execution entry when it exits.
     pub fn spawn_exit_monitor(&self, exec_id: &Uuid) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
+        let reattached_pids = self.reattached_pids.clone();
+        let log_followers = self.log_followers.clone();
         let msg_stores = self.msg_stores.clone();
         let db = self.db.clone();
         let config = self.config.clone();
@@ -294,38 +388,61 @@ impl LocalContainerService {
 
         tokio::spawn(async move {
             loop {
-                let status_opt = {
-                    let child_lock = {
-                        let map = child_store.read().await;
-                        map.get(&exec_id)
-                            .cloned()
-                            .unwrap_or_else(|| panic!("Child handle missing for {exec_id}"))
-                    };
-
+                let child_lock = child_store.read().await.get(&exec_id).cloned();
+                let exit_opt = if let Some(child_lock) = child_lock {
                     let mut child_handler = child_lock.write().await;
                     match child_handler.try_wait() {
-                        Ok(Some(status)) => Some(Ok(status)),
-                        Ok(None) => None,
-                        Err(e) => Some(Err(e)),
-                    }
-                };
-
-                // Update execution process and cleanup if exit
-                if let Some(status_result) = status_opt {
-                    // Update execution process record with completion info
-                    let (exit_code, status) = match status_result {
-                        Ok(exit_status) => {
+                        Ok(Some(exit_status)) => {
                             let code = exit_status.code().unwrap_or(-1) as i64;
                             let status = if exit_status.success() {
                                 ExecutionProcessStatus::Completed
                             } else {
                                 ExecutionProcessStatus::Failed
                             };
-                            (Some(code), status)
+                            Some((Some(code), status))
                         }
-                        Err(_) => (None, ExecutionProcessStatus::Failed),
-                    };
+                        Ok(None) => None,
+                        Err(_) => Some((None, ExecutionProcessStatus::Failed)),
+                    }
+                } else {
+                    let process = reattached_pids
+                        .read()
+                        .await
+                        .get(&exec_id)
+                        .cloned()
+                        .unwrap_or_else(|| panic!("Child handle missing for {exec_id}"));
+                    if process.is_alive() {
+                        None
+                    } else {
+                        // Give the detached log relays a moment to flush
+                        tokio::time::sleep(Duration::from_millis(250)).await;
+                        // Not our child, so take the exit code its wrapper recorded. Without
+                        // one the run can't be trusted to have succeeded.
+                        match ExecutionLogFiles::for_execution(&exec_id)
+                            .read_exit_status()
+                            .await
+                        {
+                            Some(0) => Some((Some(0), ExecutionProcessStatus::Completed)),
+                            Some(code) => Some((Some(code), ExecutionProcessStatus::Failed)),
+                            None => {
+                                tracing::warn!(
+                                    "Re-attached execution process {} exited with an unknown status",
+                                    exec_id
+                                );
+                                Some((None, ExecutionProcessStatus::Failed))
+                            }
+                        }
+                    }
+                };
 
+                // Update execution process and cleanup if exit
+                if let Some((exit_code, status)) = exit_opt {
+                    // Make sure all output has reached the MsgStore before anything reads it
+                    if let Some(follower) = log_followers.write().await.remove(&exec_id) {
+                        follower.finish().await;
+                    }
+
+                    // Update execution process record with completion info
                     if !ExecutionProcess::was_killed(&db.pool, exec_id).await
                         && let Err(e) = ExecutionProcess::update_completion(
                             &db.pool,
@@ -344,10 +461,14 @@ impl LocalContainerService {
                             tracing::warn!("Failed to update executor session summary: {}", e);
                         }
 
+                        if let Err(e) = container.record_test_runs(&ctx).await {
+                            tracing::warn!("Failed to record test runs: {}", e);
+                        }
+
                         if matches!(
                             ctx.execution_process.status,
                             ExecutionProcessStatus::Completed
-                        ) && exit_code == Some(0)
+                        ) && exit_code.is_none_or(|code| code == 0)
                         {
                             // Commit changes (if any) and get feedback about whether changes were made
                             let changes_committed = match container.try_commit_changes(&ctx).await {
@@ -393,7 +514,16 @@ impl LocalContainerService {
                             }
                         }
 
-                        if Self::should_finalize(&ctx) {
+                        // Failures covered by the project's retry policy keep the task in progress
+                        let retry_delay = match container.retry_delay(&ctx).await {
+                            Ok(delay) => delay,
+                            Err(e) => {
+                                tracing::error!("Failed to evaluate retry policy: {}", e);
+                                None
+                            }
+                        };
+
+                        if retry_delay.is_none() && Self::should_finalize(&ctx) {
                             Self::finalize_task(&db, &config, &ctx).await;
                         }
 
@@ -413,6 +543,24 @@ impl LocalContainerService {
                                     "exit_code": ctx.execution_process.exit_code,
                                 })));
                         }
+
+                        // The retry is started by the retry scheduler, which survives restarts
+                        if let Some(delay) = retry_delay {
+                            let retry_at = Utc::now()
+                                + chrono::Duration::from_std(delay)
+                                    .unwrap_or_else(|_| chrono::Duration::zero());
+                            if let Err(e) =
+                                ExecutionProcess::update_retry_at(&db.pool, exec_id, Some(retry_at))
+                                    .await
+                            {
+                                tracing::error!(
+                                    "Failed to schedule retry of execution process {}: {}",
+                                    exec_id,
+                                    e
+                                );
+                                Self::finalize_task(&db, &config, &ctx).await;
+                            }
+                        }
                     }
 
                     // Cleanup msg store
@@ -420,7 +568,21 @@ impl LocalContainerService {
                         msg_arc.push_finished();
                         tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
                         match Arc::try_unwrap(msg_arc) {
-                            Ok(inner) => drop(inner),
+                            Ok(inner) => {
+                                drop(inner);
+                                // The log writers hold the store until their last flush, so the
+                                // logs are persisted and the files are no longer needed. Files
+                                // left behind are swept by the log retention pass.
+                                if let Err(e) =
+                                    ExecutionLogFiles::for_execution(&exec_id).remove().await
+                                {
+                                    tracing::warn!(
+                                        "Failed to remove log files of execution {}: {}",
+                                        exec_id,
+                                        e
+                                    );
+                                }
+                            }
                             Err(arc) => tracing::error!(
                                 "There are still {} strong Arcs to MsgStore for {}",
                                 Arc::strong_count(&arc),
@@ -431,6 +593,13 @@ impl LocalContainerService {
 
                     // Cleanup child handle
                     child_store.write().await.remove(&exec_id);
+                    reattached_pids.write().await.remove(&exec_id);
+
+                    // A concurrency slot may have been freed, start whatever is queued
+                    if let Err(e) = container.dispatch_queued_executions().await {
+                        tracing::error!("Failed to dispatch queued executions: {}", e);
+                    }
+
                     break;
                 }
 
@@ -460,28 +629,36 @@ impl LocalContainerService {
         )
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
-        let store = Arc::new(MsgStore::new());
+    /// A MsgStore for an execution that masks secrets according to the
+    /// project's redaction rules
+    async fn new_msg_store(&self, execution_id: Uuid) -> Result<Arc<MsgStore>, ContainerError> {
+        let ctx = ExecutionProcess::load_context(&self.db.pool, execution_id).await?;
+        let project = Project::find_by_id(&self.db.pool, ctx.task.project_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let rules = ProjectRedactionRules::find_or_default(&self.db.pool, project.id).await?;
+        let config = self.config.read().await.clone();
+        let redactor = redaction::redactor_for_project(&rules, &config, &project).await;
+        Ok(Arc::new(MsgStore::with_redactor(Arc::new(redactor))))
+    }
+
+    /// Relay the child's output into its log files and follow them into a new MsgStore
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+    ) -> Result<(), ContainerError> {
+        let store = self.new_msg_store(id).await?;
 
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
-        // Map stdout bytes -> LogMsg::Stdout
-        let out = ReaderStream::new(out)
-            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
-
-        // Map stderr bytes -> LogMsg::Stderr
-        let err = ReaderStream::new(err)
-            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
-
-        // If you have a JSON Patch source, map it to LogMsg::JsonPatch too, then select all three.
-
-        // Merge and forward into the store
-        let merged = select(out, err); // Stream<Item = Result<LogMsg, io::Error>>
-        store.clone().spawn_forwarder(merged);
+        let files = ExecutionLogFiles::for_execution(&id);
+        let follower = LogFollower::start(&files, out, err, store.clone()).await?;
 
-        let mut map = self.msg_stores().write().await;
-        map.insert(id, store);
+        self.log_followers.write().await.insert(id, follower);
+        self.msg_stores().write().await.insert(id, store);
+        Ok(())
     }
 
     /// Get the worktree path for a task attempt
@@ -708,6 +885,10 @@ impl ContainerService for LocalContainerService {
         PathBuf::from(task_attempt.container_ref.clone().unwrap_or_default())
     }
 
+    async fn global_agent_limit(&self) -> Option<u32> {
+        self.config.read().await.max_concurrent_agents
+    }
+
     /// Create a container
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError> {
         let task = task_attempt
@@ -727,12 +908,46 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
+        // Remote bases, and every base of a project synced with the remote, start
+        // from origin's tip of the branch, fetched first when there's a token
+        let remote_base = task_attempt.base_branch.strip_prefix("origin/").or(project
+            .sync_remote_base
+            .then_some(task_attempt.base_branch.as_str()));
+        if let Some(base_branch) = remote_base {
+            let github_token = self.config.read().await.github.token();
+            match github_token {
+                Some(token) => {
+                    if let Err(e) = self.git().fetch_origin(&project.git_repo_path, &token) {
+                        tracing::warn!(
+                            "Failed to fetch origin, using origin/{} as last fetched: {}",
+                            base_branch,
+                            e
+                        );
+                    }
+                }
+                None => tracing::warn!(
+                    "No GitHub token to fetch origin, using origin/{} as last fetched",
+                    base_branch
+                ),
+            }
+            self.git().create_branch_from_remote(
+                &project.git_repo_path,
+                &task_branch_name,
+                base_branch,
+            )?;
+            // The local branch name is stored, like after rebasing onto a remote branch
+            if base_branch != task_attempt.base_branch {
+                TaskAttempt::update_base_branch(&self.db.pool, task_attempt.id, base_branch)
+                    .await?;
+            }
+        }
+
         WorktreeManager::create_worktree(
             &project.git_repo_path,
             &task_branch_name,
             &worktree_path,
             Some(&task_attempt.base_branch),
-            true, // create new branch
+            remote_base.is_none(), // create new branch unless it was created from the remote
         )
         .await?;
 
@@ -766,6 +981,29 @@ impl ContainerService for LocalContainerService {
 
         TaskAttempt::update_branch(&self.db.pool, task_attempt.id, &task_branch_name).await?;
 
+        // A stacked attempt starts at its parent's tip, restacking replays the
+        // commits after it
+        if task_attempt.parent_attempt_id.is_some() && task_attempt.stacked_on_commit.is_none() {
+            match self
+                .git()
+                .get_branch_oid(&project.git_repo_path, &task_branch_name)
+            {
+                Ok(tip) => {
+                    TaskAttempt::update_stacked_on_commit(
+                        &self.db.pool,
+                        task_attempt.id,
+                        Some(&tip),
+                    )
+                    .await?
+                }
+                Err(e) => tracing::warn!(
+                    "Failed to record the parent tip of stacked attempt {}: {}",
+                    task_attempt.id,
+                    e
+                ),
+            }
+        }
+
         Ok(worktree_path.to_string_lossy().to_string())
     }
 
@@ -904,11 +1142,42 @@ impl ContainerService for LocalContainerService {
             )))?;
         let current_dir = PathBuf::from(container_ref);
 
-        // Create the child and stream, add to execution tracker
-        let mut child = executor_action.spawn(&current_dir).await?;
+        // Remember where the branch was so the attempt can be reverted to before this run
+        match self.git().get_head_info(&current_dir) {
+            Ok(head) => {
+                ExecutionProcess::update_checkpoint(&self.db.pool, execution_process.id, &head.oid)
+                    .await?
+            }
+            Err(e) => tracing::warn!(
+                "Failed to record checkpoint for execution process {}: {}",
+                execution_process.id,
+                e
+            ),
+        }
+
+        // Create the child and stream, add to execution tracker. The child records its
+        // exit status next to its logs so it is known even if it outlives this server.
+        let log_files = ExecutionLogFiles::for_execution(&execution_process.id);
+        log_files.create_dir().await?;
+        let mut child = utils::shell::with_exit_status_file(
+            log_files.exit_status.clone(),
+            executor_action.spawn(&current_dir),
+        )
+        .await?;
+
+        // Remember the pid so the process can be found again after a restart
+        if let Some(pid) = child.inner().id() {
+            ExecutionProcess::update_pid(
+                &self.db.pool,
+                execution_process.id,
+                pid as i64,
+                command::process_start_time(pid).as_deref(),
+            )
+            .await?;
+        }
 
         self.track_child_msgs_in_store(execution_process.id, &mut child)
-            .await;
+            .await?;
 
         self.add_child_to_store(execution_process.id, child).await;
 
@@ -918,16 +1187,88 @@ impl ContainerService for LocalContainerService {
         Ok(())
     }
 
+    async fn reattach_execution_inner(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<bool, ContainerError> {
+        // Without a start time the pid may belong to an unrelated process by now
+        let Some((pid, start_time)) =
+            ExecutionProcess::find_pid(&self.db.pool, execution_process.id).await?
+        else {
+            return Ok(false);
+        };
+        let process = DetachedProcess {
+            pid: pid as u32,
+            start_time,
+        };
+        if !process.is_alive() {
+            return Ok(false);
+        }
+
+        // Replay the output so far and keep following the files
+        let store = self.new_msg_store(execution_process.id).await?;
+        let files = ExecutionLogFiles::for_execution(&execution_process.id);
+        let follower = LogFollower::follow(&files, store.clone());
+
+        self.log_followers
+            .write()
+            .await
+            .insert(execution_process.id, follower);
+        self.msg_stores()
+            .write()
+            .await
+            .insert(execution_process.id, store);
+        self.reattached_pids
+            .write()
+            .await
+            .insert(execution_process.id, process);
+
+        let _hn = self.spawn_exit_monitor(&execution_process.id);
+
+        Ok(true)
+    }
+
     async fn stop_execution(
         &self,
         execution_process: &ExecutionProcess,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
+        // Queued executions have no process yet, just take them off the queue
+        if ExecutionQueueEntry::delete_by_execution_process_id(&self.db.pool, execution_process.id)
+            .await?
+        {
+            ExecutionProcess::update_completion(
+                &self.db.pool,
+                execution_process.id,
+                ExecutionProcessStatus::Killed,
+                None,
+            )
+            .await?;
+            if let Ok(ctx) =
+                ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
+                && let Err(e) =
+                    Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
+            {
+                tracing::error!("Failed to update task status to InReview: {e}");
+            }
+            tracing::debug!(
+                "Queued execution process {} cancelled",
+                execution_process.id
+            );
+            return Ok(());
+        }
+
+        let reattached_process = self
+            .reattached_pids
+            .read()
             .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+            .get(&execution_process.id)
+            .cloned();
+        let child = self.get_child_from_store(&execution_process.id).await;
+        if child.is_none() && reattached_process.is_none() {
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        }
         ExecutionProcess::update_completion(
             &self.db.pool,
             execution_process.id,
@@ -937,18 +1278,35 @@ impl ContainerService for LocalContainerService {
         .await?;
 
         // Kill the child process and remove from the store
-        {
-            let mut child_guard = child.write().await;
-            if let Err(e) = command::kill_process_group(&mut child_guard).await {
-                tracing::error!(
-                    "Failed to stop execution process {}: {}",
-                    execution_process.id,
-                    e
-                );
-                return Err(e);
+        let kill_result = match (child, reattached_process) {
+            (Some(child), _) => {
+                let mut child_guard = child.write().await;
+                command::kill_process_group(&mut child_guard).await
             }
+            (None, Some(process)) => process.kill_process_group().await,
+            (None, None) => Ok(()),
+        };
+        if let Err(e) = kill_result {
+            tracing::error!(
+                "Failed to stop execution process {}: {}",
+                execution_process.id,
+                e
+            );
+            return Err(e);
         }
         self.remove_child_from_store(&execution_process.id).await;
+        self.reattached_pids
+            .write()
+            .await
+            .remove(&execution_process.id);
+        if let Some(follower) = self
+            .log_followers
+            .write()
+            .await
+            .remove(&execution_process.id)
+        {
+            follower.finish().await;
+        }
 
         // Mark the process finished in the MsgStore
         if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
@@ -972,6 +1330,10 @@ impl ContainerService for LocalContainerService {
             execution_process.id
         );
 
+        if let Err(e) = self.dispatch_queued_executions().await {
+            tracing::error!("Failed to dispatch queued executions: {}", e);
+        }
+
         Ok(())
     }
 
@@ -1088,7 +1450,12 @@ impl ContainerService for LocalContainerService {
             message
         );
 
-        let changes_committed = self.git().commit(Path::new(container_ref), &message)?;
+        let settings =
+            ProjectCommitSettings::find_or_default(&self.db.pool, ctx.task.project_id).await?;
+        let commit_options = commit_settings::attempt_commit_options(&settings, &ctx.task_attempt);
+        let changes_committed =
+            self.git()
+                .commit(Path::new(container_ref), &message, &commit_options)?;
         Ok(changes_committed)
     }
 
@@ -22,6 +22,7 @@ use crate::container::LocalContainerService;
 
 mod command;
 pub mod container;
+mod log_files;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -95,6 +96,8 @@ impl Deployment for LocalDeployment {
             analytics_ctx,
         );
         container.spawn_worktree_cleanup().await;
+        container.spawn_log_compaction();
+        container.spawn_retry_scheduler();
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
 
@@ -0,0 +1,226 @@
+//! Per-execution log files for executor processes.
+//!
+//! Executor output is relayed into files on disk instead of being read straight
+//! from the child's pipes. On unix the relay is a `cat` in its own process
+//! group, so the agent keeps a reader on its pipes when the server goes away and
+//! the files can be followed again after a restart.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncRead, AsyncReadExt},
+    sync::watch,
+    task::JoinHandle,
+};
+use utils::{assets::execution_logs_dir, log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub struct ExecutionLogFiles {
+    pub stdout: PathBuf,
+    pub stderr: PathBuf,
+    /// Written by the shell wrapping the executor once it exits
+    pub exit_status: PathBuf,
+}
+
+impl ExecutionLogFiles {
+    pub fn for_execution(execution_id: &Uuid) -> Self {
+        let dir = execution_logs_dir().join(execution_id.to_string());
+        Self {
+            stdout: dir.join("stdout.log"),
+            stderr: dir.join("stderr.log"),
+            exit_status: dir.join("exit_status"),
+        }
+    }
+
+    pub async fn create_dir(&self) -> io::Result<()> {
+        if let Some(dir) = self.stdout.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        Ok(())
+    }
+
+    /// The exit code recorded for the execution, if its wrapper wrote one
+    pub async fn read_exit_status(&self) -> Option<i64> {
+        let contents = tokio::fs::read_to_string(&self.exit_status).await.ok()?;
+        contents.trim().parse().ok()
+    }
+
+    /// Delete the files of a finished execution
+    pub async fn remove(&self) -> io::Result<()> {
+        if let Some(dir) = self.stdout.parent() {
+            match tokio::fs::remove_dir_all(dir).await {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Relays an executor's output into its log files and follows those files
+/// into the execution's `MsgStore`
+pub struct LogFollower {
+    relays: Vec<JoinHandle<()>>,
+    tails: Vec<JoinHandle<()>>,
+    done: watch::Sender<bool>,
+}
+
+impl LogFollower {
+    /// Relay the child's pipes into `files` and follow them into `store`
+    pub async fn start<O, E>(
+        files: &ExecutionLogFiles,
+        stdout: O,
+        stderr: E,
+        store: Arc<MsgStore>,
+    ) -> io::Result<Self>
+    where
+        O: AsyncRead + TryInto<Stdio, Error = io::Error> + Unpin + Send + 'static,
+        E: AsyncRead + TryInto<Stdio, Error = io::Error> + Unpin + Send + 'static,
+    {
+        files.create_dir().await?;
+        let relays = vec![
+            relay_to_file(stdout, &files.stdout).await?,
+            relay_to_file(stderr, &files.stderr).await?,
+        ];
+
+        let mut follower = Self::follow(files, store);
+        follower.relays = relays;
+        Ok(follower)
+    }
+
+    /// Follow existing log files from the beginning, used when re-attaching to
+    /// a process started by a previous server run
+    pub fn follow(files: &ExecutionLogFiles, store: Arc<MsgStore>) -> Self {
+        let (done, done_rx) = watch::channel(false);
+        let tails = vec![
+            spawn_tail(
+                files.stdout.clone(),
+                store.clone(),
+                LogMsg::Stdout,
+                done_rx.clone(),
+            ),
+            spawn_tail(files.stderr.clone(), store, LogMsg::Stderr, done_rx),
+        ];
+
+        Self {
+            relays: Vec::new(),
+            tails,
+            done,
+        }
+    }
+
+    /// Wait for the relays to drain, then read the files to the end and stop
+    /// following them
+    pub async fn finish(self) {
+        for relay in self.relays {
+            let _ = relay.await;
+        }
+        let _ = self.done.send(true);
+        for tail in self.tails {
+            let _ = tail.await;
+        }
+    }
+}
+
+/// Copy everything read from `pipe` into the file at `path`. The returned
+/// handle completes once the writing end of the pipe has closed.
+async fn relay_to_file<R>(pipe: R, path: &Path) -> io::Result<JoinHandle<()>>
+where
+    R: AsyncRead + TryInto<Stdio, Error = io::Error> + Unpin + Send + 'static,
+{
+    #[cfg(unix)]
+    {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        let mut relay = tokio::process::Command::new("cat")
+            .stdin(pipe.try_into()?)
+            .stdout(file)
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()?;
+
+        Ok(tokio::spawn(async move {
+            if let Err(e) = relay.wait().await {
+                tracing::warn!("Log relay exited with error: {}", e);
+            }
+        }))
+    }
+
+    #[cfg(not(unix))]
+    {
+        // No detached relay available, copy in-process instead
+        let mut pipe = pipe;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+
+        Ok(tokio::spawn(async move {
+            if let Err(e) = tokio::io::copy(&mut pipe, &mut file).await {
+                tracing::warn!("Log relay failed: {}", e);
+            }
+        }))
+    }
+}
+
+/// Push everything written to `path` into `store` until `done` is set and the
+/// file has been read to the end
+fn spawn_tail(
+    path: PathBuf,
+    store: Arc<MsgStore>,
+    to_msg: fn(String) -> LogMsg,
+    done: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut file = match open_when_created(&path, &done).await {
+            Some(file) => file,
+            None => return,
+        };
+
+        let mut buf = vec![0u8; 8192];
+        loop {
+            match file.read(&mut buf).await {
+                Ok(0) => {
+                    if *done.borrow() {
+                        break;
+                    }
+                    tokio::time::sleep(TAIL_POLL_INTERVAL).await;
+                }
+                Ok(n) => store.push(to_msg(String::from_utf8_lossy(&buf[..n]).into_owned())),
+                Err(e) => {
+                    tracing::error!("Failed to read log file {}: {}", path.display(), e);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+async fn open_when_created(path: &Path, done: &watch::Receiver<bool>) -> Option<File> {
+    loop {
+        match OpenOptions::new().read(true).open(path).await {
+            Ok(file) => return Some(file),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !*done.borrow() => {
+                tokio::time::sleep(TAIL_POLL_INTERVAL).await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to open log file {}: {}", path.display(), e);
+                return None;
+            }
+        }
+    }
+}
@@ -17,6 +17,13 @@ fn generate_types_content() -> String {
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
+        db::models::project_retry_policy::ProjectRetryPolicy::decl(),
+        db::models::project_retry_policy::UpsertProjectRetryPolicy::decl(),
+        db::models::project_redaction_rules::ProjectRedactionRules::decl(),
+        db::models::project_redaction_rules::UpdateProjectRedactionRules::decl(),
+        db::models::project_commit_settings::CommitSigningFormat::decl(),
+        db::models::project_commit_settings::ProjectCommitSettings::decl(),
+        db::models::project_commit_settings::UpdateProjectCommitSettings::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -26,6 +33,13 @@ fn generate_types_content() -> String {
         db::models::task_template::TaskTemplate::decl(),
         db::models::task_template::CreateTaskTemplate::decl(),
         db::models::task_template::UpdateTaskTemplate::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
+        db::models::search::SearchResultKind::decl(),
+        db::models::search::SearchHit::decl(),
+        db::models::execution_process_logs::LogStorageUsage::decl(),
+        db::models::test_run::TestRun::decl(),
         db::models::task::TaskStatus::decl(),
         db::models::task::TaskType::decl(),
         db::models::task::Task::decl(),
@@ -42,6 +56,11 @@ fn generate_types_content() -> String {
         server::routes::config::GetMcpServerResponse::decl(),
         server::routes::task_attempts::CreateFollowUpAttempt::decl(),
         server::routes::task_attempts::CreateGitHubPrRequest::decl(),
+        services::services::transcript::TranscriptFormat::decl(),
+        services::services::transcript::TranscriptProcess::decl(),
+        services::services::transcript::TranscriptBundle::decl(),
+        services::services::file_activity::FileActivityEntry::decl(),
+        services::services::file_activity::FileActivity::decl(),
         server::routes::images::ImageResponse::decl(),
         services::services::github_service::GitHubServiceError::decl(),
         services::services::config::Config::decl(),
@@ -51,10 +70,20 @@ fn generate_types_content() -> String {
         services::services::config::EditorType::decl(),
         services::services::config::GitHubConfig::decl(),
         services::services::config::SoundFile::decl(),
+        services::services::config::LogRetentionConfig::decl(),
+        services::services::config::JiraConfig::decl(),
         services::services::auth::DeviceFlowStartResponse::decl(),
         server::routes::auth::DevicePollStatus::decl(),
         server::routes::auth::CheckTokenResponse::decl(),
         services::services::git::GitBranch::decl(),
+        services::services::git::MergePreview::decl(),
+        services::services::git::ConflictedFile::decl(),
+        services::services::git::RebaseConflicts::decl(),
+        services::services::git::ConflictResolution::decl(),
+        services::services::git::FileSelection::decl(),
+        services::services::git::FileHunks::decl(),
+        services::services::git::HunkInfo::decl(),
+        services::services::git::SelectionCommit::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
         utils::diff::FileDiffDetails::decl(),
@@ -73,17 +102,34 @@ fn generate_types_content() -> String {
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
         server::routes::task_attempts::CreateTaskAttemptBody::decl(),
+        server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
+        server::routes::task_attempts::MergeTaskAttemptResponse::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
+        server::routes::task_attempts::ResolveConflictRequest::decl(),
+        server::routes::task_attempts::RevertToCheckpointRequest::decl(),
+        server::routes::task_attempts::PartialMergeRequest::decl(),
+        server::routes::task_attempts::RevertMergeRequest::decl(),
+        server::routes::task_attempts::RevertMergeResponse::decl(),
+        server::routes::task_attempts::StackedAttempt::decl(),
+        server::routes::task_attempts::RestackResult::decl(),
         server::routes::task_attempts::BranchStatus::decl(),
         db::models::task_attempt::TaskAttempt::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_queue::ExecutionQueueEntry::decl(),
+        server::routes::execution_processes::UpdateQueuePriority::decl(),
+        server::routes::execution_processes::RenormalizeRequest::decl(),
+        server::routes::execution_processes::RenormalizeResponse::decl(),
+        server::routes::projects::PurgeProjectLogsRequest::decl(),
+        server::routes::projects::PurgeProjectLogsResponse::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
+        db::models::merge::MergeStrategy::decl(),
         db::models::merge::PullRequestInfo::decl(),
+        db::models::merge::MergeRevert::decl(),
         services::services::events::EventPatch::decl(),
         services::services::events::EventPatchInner::decl(),
         services::services::events::RecordTypes::decl(),
@@ -92,6 +138,9 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
+        executors::logs::SubagentStatus::decl(),
+        executors::logs::test_run::TestRunner::decl(),
+        executors::logs::test_run::TestRunSummary::decl(),
         executors::logs::TodoItem::decl(),
         executors::logs::utils::patch::PatchType::decl(),
         serde_json::Value::decl(),
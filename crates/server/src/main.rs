@@ -41,8 +41,10 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     let deployment = DeploymentImpl::new().await?;
     deployment.update_sentry_scope().await?;
-    deployment.cleanup_orphan_executions().await?;
+    deployment.recover_running_executions().await?;
+    deployment.resume_execution_queue().await?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_task_scheduler_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -6,7 +6,7 @@ use axum::{
 };
 use db::models::{
     execution_process::ExecutionProcess, project::Project, task::Task, task_attempt::TaskAttempt,
-    task_template::TaskTemplate,
+    task_schedule::TaskSchedule, task_template::TaskTemplate,
 };
 use deployment::Deployment;
 use uuid::Uuid;
@@ -203,3 +203,27 @@ pub async fn load_task_template_middleware(
     // Continue with the next middleware/handler
     Ok(next.run(request).await)
 }
+
+// Middleware that loads and injects TaskSchedule based on the schedule_id path parameter
+pub async fn load_task_schedule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let schedule = match TaskSchedule::find_by_id(&deployment.db().pool, schedule_id).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => {
+            tracing::warn!("TaskSchedule {} not found", schedule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task schedule {}: {}", schedule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    request.extensions_mut().insert(schedule);
+
+    Ok(next.run(request).await)
+}
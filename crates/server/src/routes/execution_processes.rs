@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use axum::{
     extract::{Path, Query, State},
     middleware::from_fn_with_state,
@@ -6,13 +8,17 @@ use axum::{
         Json as ResponseJson, Sse,
     },
     routing::{get, post},
-    BoxError, Extension, Router,
+    BoxError, Extension, Json, Router,
+};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_queue::ExecutionQueueEntry,
 };
-use db::models::execution_process::ExecutionProcess;
 use deployment::Deployment;
 use futures_util::TryStreamExt;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use services::services::container::ContainerService;
+use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -83,10 +89,103 @@ pub async fn stop_execution_process(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateQueuePriority {
+    pub priority: i64,
+}
+
+pub async fn get_execution_queue(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ExecutionQueueEntry>>>, ApiError> {
+    let entries = ExecutionQueueEntry::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+pub async fn update_queue_priority(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateQueuePriority>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if execution_process.status != ExecutionProcessStatus::Queued {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Execution process is not queued",
+        )));
+    }
+
+    ExecutionQueueEntry::update_priority(
+        &deployment.db().pool,
+        execution_process.id,
+        payload.priority,
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RenormalizeRequest {
+    /// Execution processes to re-normalize, all finished coding agent runs if omitted
+    pub execution_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RenormalizeResponse {
+    /// Number of executions queued for re-normalization
+    pub queued: usize,
+}
+
+// Only one bulk re-normalization runs at a time
+static RENORMALIZE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Rebuild the persisted conversations of finished executions from their raw
+/// logs, e.g. after a log parser has been upgraded. Runs in the background, the
+/// outcome is logged.
+pub async fn renormalize_execution_processes(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RenormalizeRequest>,
+) -> Result<ResponseJson<ApiResponse<RenormalizeResponse>>, ApiError> {
+    let execution_ids = match payload.execution_ids {
+        Some(ids) => ids,
+        None => ExecutionProcess::find_finished_coding_agent_ids(&deployment.db().pool).await?,
+    };
+
+    if RENORMALIZE_RUNNING.swap(true, Ordering::SeqCst) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "A re-normalization is already running",
+        )));
+    }
+
+    let queued = execution_ids.len();
+    tokio::spawn(async move {
+        let mut renormalized = 0;
+        let mut failed = 0;
+        for id in execution_ids {
+            match deployment.container().renormalize_execution(&id).await {
+                Ok(_) => renormalized += 1,
+                Err(e) => {
+                    tracing::warn!("Failed to re-normalize execution process {}: {}", id, e);
+                    failed += 1;
+                }
+            }
+        }
+        tracing::info!(
+            "Re-normalization finished: {} execution processes re-normalized, {} failed",
+            renormalized,
+            failed
+        );
+        RENORMALIZE_RUNNING.store(false, Ordering::SeqCst);
+    });
+
+    Ok(ResponseJson(ApiResponse::success(RenormalizeResponse {
+        queued,
+    })))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
+        .route("/priority", post(update_queue_priority))
         .route("/raw-logs", get(stream_raw_logs))
         .route("/normalized-logs", get(stream_normalized_logs))
         .layer(from_fn_with_state(
@@ -96,6 +195,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_attempts_router = Router::new()
         .route("/", get(get_execution_processes))
+        .route("/queue", get(get_execution_queue))
+        .route("/renormalize", post(renormalize_execution_processes))
         .nest("/{id}", task_attempt_id_router);
 
     Router::new().nest("/execution-processes", task_attempts_router)
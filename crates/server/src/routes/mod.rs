@@ -17,7 +17,9 @@ pub mod health;
 pub mod images;
 pub mod jira;
 pub mod projects;
+pub mod search;
 pub mod task_attempts;
+pub mod task_schedules;
 pub mod task_templates;
 pub mod tasks;
 
@@ -32,6 +34,8 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(task_templates::router(&deployment))
+        .merge(task_schedules::router(&deployment))
+        .merge(search::router(&deployment))
         .merge(auth::router(&deployment))
         .merge(filesystem::router())
         .merge(events::router(&deployment))
@@ -8,12 +8,23 @@ use axum::{
     routing::{get, post},
     Extension, Json, Router,
 };
-use db::models::project::{
-    CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject,
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::ExecutionProcessRunReason,
+    execution_process_logs::{ExecutionProcessLogs, LogStorageUsage},
+    project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
+    project_commit_settings::{ProjectCommitSettings, UpdateProjectCommitSettings},
+    project_redaction_rules::{ProjectRedactionRules, UpdateProjectRedactionRules},
+    project_retry_policy::{ProjectRetryPolicy, UpsertProjectRetryPolicy},
 };
 use deployment::Deployment;
 use ignore::WalkBuilder;
-use services::services::{file_ranker::FileRanker, git::GitBranch};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    commit_settings, file_ranker::FileRanker, git::GitBranch, log_retention, redaction,
+    retry_policy,
+};
+use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
@@ -40,6 +51,9 @@ pub async fn get_project_branches(
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
+/// A limit below one would keep the project's agents queued forever
+const INVALID_AGENT_LIMIT: &str = "Max concurrent agents must be at least 1";
+
 pub async fn create_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateProject>,
@@ -48,6 +62,10 @@ pub async fn create_project(
 
     tracing::debug!("Creating project '{}'", payload.name);
 
+    if payload.max_concurrent_agents.is_some_and(|limit| limit < 1) {
+        return Ok(ResponseJson(ApiResponse::error(INVALID_AGENT_LIMIT)));
+    }
+
     // Check if git repo path is already used by another project
     match Project::find_by_git_repo_path(&deployment.db().pool, &payload.git_repo_path).await {
         Ok(Some(_)) => {
@@ -146,6 +164,10 @@ pub async fn update_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    if payload.max_concurrent_agents.is_some_and(|limit| limit < 1) {
+        return Ok(ResponseJson(ApiResponse::error(INVALID_AGENT_LIMIT)));
+    }
+
     // If git_repo_path is being changed, check if the new path is already used by another project
     if let Some(new_git_repo_path) = &payload.git_repo_path {
         if new_git_repo_path != &existing_project.git_repo_path.to_string_lossy() {
@@ -182,11 +204,15 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
+        max_concurrent_agents,
+        default_merge_strategy,
+        sync_remote_base,
     } = payload;
 
     let name = name.unwrap_or(existing_project.name);
     let git_repo_path =
         git_repo_path.unwrap_or(existing_project.git_repo_path.to_string_lossy().to_string());
+    let sync_remote_base = sync_remote_base.unwrap_or(existing_project.sync_remote_base);
 
     match Project::update(
         &deployment.db().pool,
@@ -197,6 +223,9 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
+        max_concurrent_agents,
+        default_merge_strategy,
+        sync_remote_base,
     )
     .await
     {
@@ -391,6 +420,129 @@ async fn search_files_in_repo(
     Ok(results)
 }
 
+pub async fn get_project_retry_policies(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectRetryPolicy>>>, ApiError> {
+    let policies =
+        ProjectRetryPolicy::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(policies)))
+}
+
+pub async fn upsert_project_retry_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectRetryPolicy>,
+) -> Result<ResponseJson<ApiResponse<ProjectRetryPolicy>>, ApiError> {
+    if let Err(e) = retry_policy::validate(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+    }
+
+    let policy = ProjectRetryPolicy::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RetryPolicyQuery {
+    run_reason: ExecutionProcessRunReason,
+}
+
+pub async fn delete_project_retry_policy(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<RetryPolicyQuery>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected =
+        ProjectRetryPolicy::delete(&deployment.db().pool, project.id, &query.run_reason).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub async fn get_project_redaction_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectRedactionRules>>, ApiError> {
+    let rules = ProjectRedactionRules::find_or_default(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn update_project_redaction_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectRedactionRules>,
+) -> Result<ResponseJson<ApiResponse<ProjectRedactionRules>>, ApiError> {
+    if let Err(e) = redaction::validate(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+    }
+
+    let rules = ProjectRedactionRules::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn get_project_commit_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectCommitSettings>>, ApiError> {
+    let settings =
+        ProjectCommitSettings::find_or_default(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn update_project_commit_settings(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectCommitSettings>,
+) -> Result<ResponseJson<ApiResponse<ProjectCommitSettings>>, ApiError> {
+    if let Err(e) = commit_settings::validate(&payload) {
+        return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+    }
+
+    let settings =
+        ProjectCommitSettings::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+pub async fn get_project_storage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<LogStorageUsage>>, ApiError> {
+    let usage =
+        ExecutionProcessLogs::storage_usage_by_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PurgeProjectLogsRequest {
+    /// Only purge logs of executions that completed before this time
+    pub before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PurgeProjectLogsResponse {
+    pub purged: usize,
+}
+
+/// Delete the raw logs of the project's finished executions, keeping their
+/// normalized conversations
+pub async fn purge_project_logs(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PurgeProjectLogsRequest>,
+) -> Result<ResponseJson<ApiResponse<PurgeProjectLogsResponse>>, ApiError> {
+    let purged = log_retention::purge_project(deployment.db(), project.id, payload.before).await?;
+    tracing::info!(
+        "Purged logs of {} executions for project {}",
+        purged,
+        project.id
+    );
+    Ok(ResponseJson(ApiResponse::success(
+        PurgeProjectLogsResponse { purged },
+    )))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -400,6 +552,22 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/branches", get(get_project_branches))
         .route("/search", get(search_project_files))
         .route("/open-editor", post(open_project_in_editor))
+        .route(
+            "/retry-policies",
+            get(get_project_retry_policies)
+                .put(upsert_project_retry_policy)
+                .delete(delete_project_retry_policy),
+        )
+        .route(
+            "/redaction-rules",
+            get(get_project_redaction_rules).put(update_project_redaction_rules),
+        )
+        .route(
+            "/commit-settings",
+            get(get_project_commit_settings).put(update_project_commit_settings),
+        )
+        .route("/storage", get(get_project_storage))
+        .route("/storage/purge", post(purge_project_logs))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
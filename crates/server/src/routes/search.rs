@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use db::models::search::{SearchFilter, SearchHit};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::search::to_match_query;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, DeploymentImpl};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    q: String,
+    project_id: Option<Uuid>,
+    profile: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    entry_type: Option<String>,
+    limit: Option<i64>,
+}
+
+pub async fn search(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchHit>>>, ApiError> {
+    let Some(match_query) = to_match_query(&query.q) else {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    };
+
+    let filter = SearchFilter {
+        project_id: query.project_id,
+        profile: query.profile,
+        from: query.from,
+        to: query.to,
+        entry_type: query.entry_type,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let results = SearchHit::search(&deployment.db().pool, &match_query, &filter, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+pub fn router(_deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    Router::new().route("/search", get(search))
+}
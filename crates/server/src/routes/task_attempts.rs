@@ -1,21 +1,23 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware::from_fn_with_state,
     response::{
         sse::{Event, KeepAlive},
-        Json as ResponseJson, Sse,
+        IntoResponse, Json as ResponseJson, Response, Sse,
     },
     routing::{get, post},
     BoxError, Extension, Json, Router,
 };
 use db::models::{
-    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     image::TaskImage,
-    merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    merge::{Merge, MergeRevert, MergeStatus, MergeStrategy, PrMerge, PullRequestInfo},
     project::{Project, ProjectError},
+    project_commit_settings::ProjectCommitSettings,
     task::{Task, TaskStatus},
     task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+    test_run::TestRun,
 };
 use deployment::Deployment;
 use executors::{
@@ -24,14 +26,22 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
         ExecutorAction, ExecutorActionType,
     },
+    executors::CodingAgent,
     profile::{ProfileConfigs, ProfileVariantLabel},
 };
 use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use services::services::{
+    commit_settings,
     container::ContainerService,
+    file_activity::FileActivity,
+    git::{
+        CommitOptions, ConflictResolution, DiffTarget, DirectMergeOptions, FileHunks,
+        FileSelection, GitServiceError, MergePreview, RebaseConflicts, SelectionCommit,
+    },
     github_service::{CreatePrRequest, GitHubService, GitHubServiceError},
     image::ImageService,
+    transcript::{self, TranscriptFormat},
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -40,6 +50,28 @@ use uuid::Uuid;
 
 use crate::{error::ApiError, middleware::load_task_attempt_middleware, DeploymentImpl};
 
+/// Prepended to the first follow-up after reverting to a checkpoint, the agent's
+/// session still remembers the discarded turns
+const CHECKPOINT_REVERT_NOTE: &str = "Note: the changes you made in the most recent turns of this conversation were discarded, the worktree was reset to the state before them. Don't assume those edits exist, check the current state of the files before continuing.";
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct MergeTaskAttemptRequest {
+    /// Falls back to the project's default merge strategy
+    pub strategy: Option<MergeStrategy>,
+    /// Also update the files of the main repository if it has the base branch
+    /// checked out
+    #[serde(default)]
+    pub update_checkout: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct MergeTaskAttemptResponse {
+    pub merge_commit: String,
+    /// Set when the base branch is checked out in the main repository and its
+    /// files are now behind the branch
+    pub checkout_warning: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct RebaseTaskAttemptRequest {
     pub new_base_branch: Option<String>,
@@ -50,6 +82,9 @@ pub struct CreateGitHubPrRequest {
     pub title: String,
     pub body: Option<String>,
     pub base_branch: Option<String>,
+    /// Append the attempt's transcript to the PR body
+    #[serde(default)]
+    pub include_transcript: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -143,6 +178,9 @@ pub struct CreateFollowUpAttempt {
     pub prompt: String,
     pub variant: Option<String>,
     pub image_ids: Option<Vec<Uuid>>,
+    /// Continue with a different profile. If it uses a different agent, the
+    /// attempt is handed off to a fresh session of that agent.
+    pub profile: Option<String>,
 }
 
 pub async fn follow_up(
@@ -158,16 +196,6 @@ pub async fn follow_up(
         .ensure_container_exists(&task_attempt)
         .await?;
 
-    // Get session_id with simple query
-    let session_id = ExecutionProcess::find_latest_session_id_by_task_attempt(
-        &deployment.db().pool,
-        task_attempt.id,
-    )
-    .await?
-    .ok_or(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
-        "Couldn't find a prior CodingAgent execution that already has a session_id".to_string(),
-    )))?;
-
     // Get ExecutionProcess for profile data
     let latest_execution_process = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
         &deployment.db().pool,
@@ -194,16 +222,43 @@ pub async fn follow_up(
         ))),
     }?;
 
+    // Let the agent know its later turns were rolled back
+    let mut prompt = payload.prompt;
+    if latest_execution_process.reverted {
+        prompt = format!("{CHECKPOINT_REVERT_NOTE}\n\n{prompt}");
+    }
+
+    let requested_profile = payload
+        .profile
+        .clone()
+        .filter(|profile| profile != &initial_profile_variant_label.profile);
+    let switching_profile = requested_profile.is_some();
     let mut profile_variant_label = ProfileVariantLabel {
-        profile: initial_profile_variant_label.profile.clone(),
+        profile: requested_profile.unwrap_or(initial_profile_variant_label.profile.clone()),
         variant: payload.variant.clone(),
     };
 
+    // Switching profiles hands the attempt off when the new profile runs a
+    // different agent, since it can't resume the previous agent's session
+    let mut handoff = false;
+    if switching_profile {
+        let Some(candidate_agent) = resolve_agent(&profile_variant_label) else {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Profile not found: {}",
+                profile_variant_label.profile
+            ))));
+        };
+        handoff = resolve_agent(&initial_profile_variant_label).is_none_or(|initial_agent| {
+            std::mem::discriminant(&candidate_agent) != std::mem::discriminant(&initial_agent)
+        });
+    }
+
     // Pin follow-up to the original profile's agent. If the requested variant would
     // switch the underlying agent (e.g., Claude -> Gemini), override it to the
     // initial variant (or default) to keep the same agent family.
-    if let Some(profile_cfg) =
-        ProfileConfigs::get_cached().get_profile(&initial_profile_variant_label.profile)
+    if let Some(profile_cfg) = ProfileConfigs::get_cached()
+        .get_profile(&initial_profile_variant_label.profile)
+        .filter(|_| !switching_profile)
     {
         let initial_agent = initial_profile_variant_label
             .variant
@@ -240,7 +295,6 @@ pub async fn follow_up(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    let mut prompt = payload.prompt;
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many(&deployment.db().pool, task.id, image_ids).await?;
 
@@ -268,6 +322,30 @@ pub async fn follow_up(
         ))
     });
 
+    if handoff {
+        let execution_process = deployment
+            .container()
+            .start_handoff(
+                &task_attempt,
+                &initial_profile_variant_label,
+                profile_variant_label,
+                &prompt,
+                cleanup_action,
+            )
+            .await?;
+        return Ok(ResponseJson(ApiResponse::success(execution_process)));
+    }
+
+    // Get session_id with simple query
+    let session_id = ExecutionProcess::find_latest_session_id_by_task_attempt(
+        &deployment.db().pool,
+        task_attempt.id,
+    )
+    .await?
+    .ok_or(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+        "Couldn't find a prior CodingAgent execution that already has a session_id".to_string(),
+    )))?;
+
     let follow_up_request = CodingAgentFollowUpRequest {
         prompt,
         session_id,
@@ -291,6 +369,20 @@ pub async fn follow_up(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+/// The agent a profile variant runs, falling back to the profile's default
+fn resolve_agent(label: &ProfileVariantLabel) -> Option<CodingAgent> {
+    let profiles = ProfileConfigs::get_cached();
+    let profile_cfg = profiles.get_profile(&label.profile)?;
+    Some(
+        label
+            .variant
+            .as_ref()
+            .and_then(|v| profile_cfg.get_variant(v))
+            .map(|v| v.agent.clone())
+            .unwrap_or(profile_cfg.default.agent.clone()),
+    )
+}
+
 pub async fn get_task_attempt_diff(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
@@ -310,7 +402,8 @@ pub async fn get_task_attempt_diff(
 pub async fn merge_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
-) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    request_body: Option<Json<MergeTaskAttemptRequest>>,
+) -> Result<ResponseJson<ApiResponse<MergeTaskAttemptResponse>>, ApiError> {
     let pool = &deployment.db().pool;
 
     let task = task_attempt
@@ -325,56 +418,295 @@ pub async fn merge_task_attempt(
         .await?;
     let worktree_path = std::path::Path::new(&container_ref);
 
+    let commit_message = merge_commit_message(&ctx.task);
+
+    // Get branch name from task attempt
+    let branch_name = ctx.task_attempt.branch.as_ref().ok_or_else(|| {
+        ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No branch found for task attempt".to_string(),
+        ))
+    })?;
+
+    let request = request_body.map(|Json(body)| body);
+    let strategy = request
+        .as_ref()
+        .and_then(|body| body.strategy)
+        .or(ctx.project.default_merge_strategy)
+        .unwrap_or_default();
+    let update_checkout = request.is_some_and(|body| body.update_checkout);
+
+    // The branch is reset to the merged commit, remember its tip for the
+    // attempts stacked on it
+    let old_branch_tip = deployment
+        .git()
+        .get_branch_oid(&ctx.project.git_repo_path, branch_name)
+        .ok();
+
+    let settings = ProjectCommitSettings::find_or_default(pool, ctx.project.id).await?;
+    let merge = || {
+        deployment.git().merge_changes(
+            &ctx.project.git_repo_path,
+            worktree_path,
+            branch_name,
+            &ctx.task_attempt.base_branch,
+            &commit_message,
+            DirectMergeOptions {
+                strategy,
+                update_checkout,
+            },
+            &commit_settings::attempt_commit_options(&settings, &ctx.task_attempt),
+        )
+    };
+    let outcome = match merge() {
+        // Replay the task's commits onto the base in the worktree. The rebase stops
+        // on the conflicts so they're resolved like a rebase's, and if it goes
+        // through cleanly the merge is tried again.
+        Err(GitServiceError::MergeConflicts(_)) => {
+            deployment.git().rebase_onto(
+                worktree_path,
+                &ctx.task_attempt.base_branch,
+                ctx.task_attempt.stacked_on_commit.as_deref(),
+                &commit_settings::commit_options(&settings),
+            )?;
+            merge()?
+        }
+        result => result?,
+    };
+    if let Some(warning) = &outcome.checkout_warning {
+        tracing::warn!("Merged task attempt {}: {}", task_attempt.id, warning);
+    }
+
+    Merge::create_direct(
+        pool,
+        task_attempt.id,
+        &ctx.task_attempt.base_branch,
+        &outcome.merge_commit,
+        strategy,
+        None,
+        Some(&outcome.base_commit),
+    )
+    .await?;
+    Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+
+    let retargeted = retarget_stacked_children(
+        &deployment,
+        &ctx.project.git_repo_path,
+        &ctx.task_attempt,
+        old_branch_tip,
+        &commit_settings::commit_options(&settings),
+    )
+    .await?;
+    for result in retargeted {
+        if let Some(error) = result.error {
+            tracing::warn!(
+                "Failed to retarget stacked attempt {} after merging {}: {}",
+                result.attempt_id,
+                task_attempt.id,
+                error
+            );
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_merged",
+            serde_json::json!({
+                "task_id": ctx.task.id.to_string(),
+                "project_id": ctx.project.id.to_string(),
+                "attempt_id": task_attempt.id.to_string(),
+                "merge_strategy": strategy,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        MergeTaskAttemptResponse {
+            merge_commit: outcome.merge_commit,
+            checkout_warning: outcome.checkout_warning,
+        },
+    )))
+}
+
+/// Commit message for merging a task: its title and short id, then its description
+fn merge_commit_message(task: &Task) -> String {
     let task_uuid_str = task.id.to_string();
     let first_uuid_section = task_uuid_str.split('-').next().unwrap_or(&task_uuid_str);
 
     // Create commit message with task title and description
-    let mut commit_message = format!("{} (vibe-kanban {})", ctx.task.title, first_uuid_section);
+    let mut commit_message = format!("{} (vibe-kanban {})", task.title, first_uuid_section);
 
     // Add description on next line if it exists
-    if let Some(description) = &ctx.task.description {
+    if let Some(description) = &task.description {
         if !description.trim().is_empty() {
             commit_message.push_str("\n\n");
             commit_message.push_str(description);
         }
     }
+    commit_message
+}
 
-    // Get branch name from task attempt
-    let branch_name = ctx.task_attempt.branch.as_ref().ok_or_else(|| {
+#[derive(Debug, Deserialize)]
+pub struct SelectableHunksQuery {
+    #[serde(default)]
+    pub include_uncommitted: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct PartialMergeRequest {
+    pub files: Vec<FileSelection>,
+    /// Also take uncommitted changes from the worktree
+    #[serde(default)]
+    pub include_uncommitted: bool,
+    /// Commit onto a new branch created from the base branch instead of the base
+    /// branch itself
+    pub new_branch: Option<String>,
+}
+
+/// Hunks of the attempt's changes that can be picked for a partial merge
+pub async fn get_task_attempt_selectable_hunks(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SelectableHunksQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<FileHunks>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+    let branch_name = task_attempt.branch.as_ref().ok_or_else(|| {
         ApiError::TaskAttempt(TaskAttemptError::ValidationError(
             "No branch found for task attempt".to_string(),
         ))
     })?;
 
-    let merge_commit_id = deployment.git().merge_changes(
-        &ctx.project.git_repo_path,
-        worktree_path,
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let source = selection_source(
+        query.include_uncommitted,
+        &project.git_repo_path,
+        std::path::Path::new(&container_ref),
         branch_name,
-        &ctx.task_attempt.base_branch,
-        &commit_message,
+        &task_attempt.base_branch,
+    );
+    let hunks = deployment.git().get_selectable_hunks(source)?;
+    Ok(ResponseJson(ApiResponse::success(hunks)))
+}
+
+/// Commit only the selected files and hunks of the attempt onto its base branch
+/// or a new branch, recorded as a merge noting what was left out
+pub async fn partial_merge_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PartialMergeRequest>,
+) -> Result<ResponseJson<ApiResponse<SelectionCommit>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+    let branch_name = task_attempt.branch.as_ref().ok_or_else(|| {
+        ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "No branch found for task attempt".to_string(),
+        ))
+    })?;
+
+    let new_branch = payload
+        .new_branch
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+    if let Some(name) = new_branch {
+        if !git2::Branch::name_is_valid(name)? {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Invalid branch name: {name}"
+            ))));
+        }
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let source = selection_source(
+        payload.include_uncommitted,
+        &project.git_repo_path,
+        std::path::Path::new(&container_ref),
+        branch_name,
+        &task_attempt.base_branch,
+    );
+
+    let settings = ProjectCommitSettings::find_or_default(pool, project.id).await?;
+    let result = deployment.git().commit_selection(
+        &project.git_repo_path,
+        source,
+        &payload.files,
+        new_branch,
+        &merge_commit_message(&task),
+        &commit_settings::attempt_commit_options(&settings, &task_attempt),
     )?;
 
+    let note =
+        (!result.excluded.is_empty()).then(|| format!("Left out: {}", result.excluded.join(", ")));
+    let target_branch = new_branch.unwrap_or(&task_attempt.base_branch);
     Merge::create_direct(
         pool,
         task_attempt.id,
-        &ctx.task_attempt.base_branch,
-        &merge_commit_id,
+        target_branch,
+        &result.commit,
+        MergeStrategy::Squash,
+        note.as_deref(),
+        None,
     )
     .await?;
-    Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+    if new_branch.is_none() {
+        Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    }
 
     deployment
         .track_if_analytics_allowed(
-            "task_attempt_merged",
+            "task_attempt_partially_merged",
             serde_json::json!({
-                "task_id": ctx.task.id.to_string(),
-                "project_id": ctx.project.id.to_string(),
+                "task_id": task.id.to_string(),
+                "project_id": project.id.to_string(),
                 "attempt_id": task_attempt.id.to_string(),
+                "new_branch": new_branch.is_some(),
+                "excluded_count": result.excluded.len(),
             }),
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(())))
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+/// Where the changes of a partial merge are picked from
+fn selection_source<'p>(
+    include_uncommitted: bool,
+    repo_path: &'p std::path::Path,
+    worktree_path: &'p std::path::Path,
+    branch_name: &'p str,
+    base_branch: &'p str,
+) -> DiffTarget<'p> {
+    if include_uncommitted {
+        DiffTarget::Worktree {
+            worktree_path,
+            branch_name,
+            base_branch,
+        }
+    } else {
+        DiffTarget::Branch {
+            repo_path,
+            branch_name,
+            base_branch,
+        }
+    }
 }
 
 pub async fn push_task_attempt_branch(
@@ -481,10 +813,23 @@ pub async fn create_github_pr(
             )));
         }
     }
+    let body = if request.include_transcript {
+        let bundle = deployment
+            .container()
+            .build_transcript(&task_attempt)
+            .await?;
+        Some(transcript::append_to_pr_body(
+            request.body.as_deref(),
+            &transcript::render_markdown_blocks(&bundle),
+        ))
+    } else {
+        request.body.clone()
+    };
+
     // Create the PR using GitHub service
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: branch_name.clone(),
         base_branch: base_branch.clone(),
     };
@@ -534,6 +879,234 @@ pub async fn create_github_pr(
     }
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct RevertMergeRequest {
+    pub merge_id: Uuid,
+    /// Move the task back to `todo` or `inprogress` once reverted
+    pub task_status: Option<TaskStatus>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RevertMergeResponse {
+    pub revert: MergeRevert,
+    /// Set when the target branch is checked out in the main repository and its
+    /// files are now behind the branch
+    pub checkout_warning: Option<String>,
+}
+
+/// Revert a merged attempt: a revert commit on the target branch for direct
+/// merges, a revert branch with a PR for merged PRs
+pub async fn revert_task_attempt_merge(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RevertMergeRequest>,
+) -> Result<ResponseJson<ApiResponse<RevertMergeResponse>>, ApiError> {
+    if let Some(status) = &payload.task_status {
+        if !matches!(status, TaskStatus::Todo | TaskStatus::InProgress) {
+            return Ok(ResponseJson(ApiResponse::error(
+                "A reverted task can only be moved back to To Do or In Progress",
+            )));
+        }
+    }
+
+    let pool = &deployment.db().pool;
+    let Some(merge) = Merge::find_by_task_attempt_id(pool, task_attempt.id)
+        .await?
+        .into_iter()
+        .find(|merge| merge.id() == payload.merge_id)
+    else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Merge not found for this attempt",
+        )));
+    };
+    if merge.revert().is_some() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This merge was already reverted",
+        )));
+    }
+
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let project = Project::find_by_id(pool, task.project_id)
+        .await?
+        .ok_or(ApiError::Project(ProjectError::ProjectNotFound))?;
+
+    let settings = ProjectCommitSettings::find_or_default(pool, project.id).await?;
+    let commit_options = commit_settings::attempt_commit_options(&settings, &task_attempt);
+    let mut checkout_warning = None;
+    let revert = match &merge {
+        Merge::Direct(direct) => {
+            // Rebase and fast-forward merges may have added several commits, the
+            // merge commit alone is only the last of them
+            let base_commit = match (direct.strategy, &direct.base_commit) {
+                (MergeStrategy::Rebase | MergeStrategy::FastForward, None) => {
+                    return Ok(ResponseJson(ApiResponse::error(
+                        "This merge was made before the base branch tip was recorded and may have added several commits, revert it manually",
+                    )));
+                }
+                (_, base_commit) => base_commit.as_deref(),
+            };
+            let message = match base_commit {
+                Some(base_commit) => format!(
+                    "Revert \"{}\"\n\nThis reverts commits {}..{}.",
+                    task.title, base_commit, direct.merge_commit
+                ),
+                None => format!(
+                    "Revert \"{}\"\n\nThis reverts commit {}.",
+                    task.title, direct.merge_commit
+                ),
+            };
+            let outcome = deployment.git().revert_commit(
+                &project.git_repo_path,
+                &direct.target_branch_name,
+                &direct.merge_commit,
+                base_commit,
+                &message,
+                &commit_options,
+            )?;
+            if let Some(warning) = &outcome.checkout_warning {
+                tracing::warn!(
+                    "Reverted merge of task attempt {}: {}",
+                    task_attempt.id,
+                    warning
+                );
+            }
+            checkout_warning = outcome.checkout_warning;
+            Merge::record_revert(pool, direct.id, &outcome.revert_commit, None, None).await?
+        }
+        Merge::Pr(pr) => {
+            let (MergeStatus::Merged, Some(merge_commit_sha)) =
+                (&pr.pr_info.status, &pr.pr_info.merge_commit_sha)
+            else {
+                return Ok(ResponseJson(ApiResponse::error(
+                    "Only merged pull requests can be reverted",
+                )));
+            };
+
+            let github_config = deployment.config().read().await.github.clone();
+            let Some(github_token) = github_config.token() else {
+                return Err(GitHubServiceError::TokenInvalid.into());
+            };
+            let github_service = GitHubService::new(&github_token)?;
+            github_service.check_token().await?;
+
+            let message = format!(
+                "Revert \"{}\"\n\nThis reverts commit {}, merged in #{}.",
+                task.title, merge_commit_sha, pr.pr_info.number
+            );
+            let revert_branch = format!("revert-{}", &merge_commit_sha[..7]);
+            let revert_commit = deployment.git().create_revert_branch(
+                &project.git_repo_path,
+                &pr.target_branch_name,
+                merge_commit_sha,
+                &revert_branch,
+                &message,
+                &github_token,
+                &commit_options,
+            )?;
+            deployment.git().push_to_github(
+                &project.git_repo_path,
+                &revert_branch,
+                &github_token,
+            )?;
+
+            let repo_info = deployment
+                .git()
+                .get_github_repo_info(&project.git_repo_path)?;
+            let pr_info = github_service
+                .create_pr(
+                    &repo_info,
+                    &CreatePrRequest {
+                        title: format!("Revert \"{}\"", task.title),
+                        body: Some(format!("Reverts #{}", pr.pr_info.number)),
+                        head_branch: revert_branch,
+                        base_branch: pr.target_branch_name.clone(),
+                    },
+                )
+                .await?;
+            Merge::record_revert(
+                pool,
+                pr.id,
+                &revert_commit,
+                Some(pr_info.number),
+                Some(&pr_info.url),
+            )
+            .await?
+        }
+    };
+
+    if let Some(status) = payload.task_status {
+        Task::update_status(pool, task.id, status).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_merge_reverted",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": project.id.to_string(),
+                "attempt_id": task_attempt.id.to_string(),
+                "via_pr": revert.pr_number.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(RevertMergeResponse {
+        revert,
+        checkout_warning,
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranscriptQuery {
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+/// Download the attempt's transcript as Markdown, standalone HTML or a JSON bundle
+pub async fn export_task_attempt_transcript(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TranscriptQuery>,
+) -> Result<Response, ApiError> {
+    let bundle = deployment
+        .container()
+        .build_transcript(&task_attempt)
+        .await?;
+    let (content_type, extension, body) = match query.format {
+        TranscriptFormat::Markdown => (
+            "text/markdown; charset=utf-8",
+            "md",
+            transcript::render_markdown(&bundle),
+        ),
+        TranscriptFormat::Html => (
+            "text/html; charset=utf-8",
+            "html",
+            transcript::render_html(&bundle),
+        ),
+        TranscriptFormat::Json => (
+            "application/json",
+            "json",
+            serde_json::to_string_pretty(&bundle).map_err(std::io::Error::from)?,
+        ),
+    };
+    let disposition = format!(
+        "attachment; filename=\"transcript-{}.{}\"",
+        task_attempt.id, extension
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
 #[derive(serde::Deserialize)]
 pub struct OpenEditorRequest {
     editor_type: Option<String>,
@@ -601,6 +1174,15 @@ pub struct BranchStatus {
     pub remote_commits_behind: Option<usize>,
     pub remote_commits_ahead: Option<usize>,
     pub merges: Vec<Merge>,
+    /// A rebase stopped on conflicts and waits to be continued or aborted
+    pub rebase_in_progress: bool,
+    /// Whether the branch would merge cleanly into the base branch as it is now
+    pub merge_preview: Option<MergePreview>,
+    /// Commits the local base branch has that `origin/<base>` doesn't, as of
+    /// the last fetch. `None` when the base has no remote branch.
+    pub base_remote_commits_ahead: Option<usize>,
+    /// Commits on `origin/<base>` the local base branch doesn't have yet
+    pub base_remote_commits_behind: Option<usize>,
 }
 
 pub async fn get_task_attempt_branch_status(
@@ -620,6 +1202,15 @@ pub async fn get_task_attempt_branch_status(
         .await
         .ok()
         .map(|is_clean| !is_clean);
+    let rebase_in_progress = task_attempt
+        .container_ref
+        .as_ref()
+        .is_some_and(|container_ref| {
+            deployment
+                .git()
+                .is_rebase_in_progress(std::path::Path::new(container_ref))
+                .unwrap_or(false)
+        });
 
     let task_branch =
         task_attempt
@@ -633,6 +1224,19 @@ pub async fn get_task_attempt_branch_status(
         &task_branch,
         &task_attempt.base_branch,
     )?;
+    let merge_preview = deployment
+        .git()
+        .preview_merge(
+            &ctx.project.git_repo_path,
+            &task_branch,
+            &task_attempt.base_branch,
+        )
+        .ok();
+    let base_drift = deployment
+        .git()
+        .get_base_remote_drift(&ctx.project.git_repo_path, &task_attempt.base_branch)
+        .ok()
+        .flatten();
     // Fetch merges for this task attempt and add to branch status
     let merges = Merge::find_by_task_attempt_id(pool, task_attempt.id).await?;
     let mut branch_status = BranchStatus {
@@ -643,6 +1247,10 @@ pub async fn get_task_attempt_branch_status(
         remote_commits_behind: None,
         merges,
         base_branch_name: task_attempt.base_branch.clone(),
+        rebase_in_progress,
+        merge_preview,
+        base_remote_commits_ahead: base_drift.map(|(ahead, _)| ahead),
+        base_remote_commits_behind: base_drift.map(|(_, behind)| behind),
     };
 
     // check remote status if the attempt has an open PR
@@ -671,6 +1279,15 @@ pub async fn get_task_attempt_branch_status(
     Ok(ResponseJson(ApiResponse::success(branch_status)))
 }
 
+/// `origin/<branch>` for a local branch, remote branches are returned as is
+fn remote_branch_name(branch: &str) -> String {
+    if branch.starts_with("origin/") {
+        branch.to_string()
+    } else {
+        format!("origin/{branch}")
+    }
+}
+
 #[axum::debug_handler]
 pub async fn rebase_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
@@ -694,32 +1311,514 @@ pub async fn rebase_task_attempt(
     let effective_base_branch =
         new_base_branch.or_else(|| Some(ctx.task_attempt.base_branch.clone()));
 
+    // Projects synced with the remote fetch and rebase onto origin's tip of the
+    // base, counting the task's commits from where origin's base was before
+    let (effective_base_branch, old_base_branch) = if ctx.project.sync_remote_base {
+        (
+            effective_base_branch.map(|branch| remote_branch_name(&branch)),
+            remote_branch_name(&ctx.task_attempt.base_branch),
+        )
+    } else {
+        (effective_base_branch, ctx.task_attempt.base_branch.clone())
+    };
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&task_attempt)
         .await?;
     let worktree_path = std::path::Path::new(&container_ref);
 
-    let _new_base_commit = deployment.git().rebase_branch(
+    let settings = ProjectCommitSettings::find_or_default(pool, ctx.project.id).await?;
+    // Stacked attempts, and attempts retargeted after their parent was merged,
+    // replay only the commits after the parent tip they were last based on
+    let rebase_result = deployment.git().rebase_branch(
         &ctx.project.git_repo_path,
         worktree_path,
         effective_base_branch.clone().as_deref(),
-        &ctx.task_attempt.base_branch.clone(),
+        &old_base_branch,
+        ctx.task_attempt.stacked_on_commit.as_deref(),
         github_config.token(),
-    )?;
+        &commit_settings::commit_options(&settings),
+    );
 
-    if let Some(new_base_branch) = &effective_base_branch {
-        if new_base_branch != &ctx.task_attempt.base_branch {
+    // A rebase that stopped on conflicts is already onto the new base, so the
+    // new base is kept for when it's continued
+    if matches!(
+        rebase_result,
+        Ok(_) | Err(GitServiceError::RebaseConflicts(_))
+    ) {
+        if let Some(new_base_branch) = &effective_base_branch {
             // for remote branches, store the local branch name in the database
-            let db_branch_name = if new_base_branch.starts_with("origin/") {
-                new_base_branch.strip_prefix("origin/").unwrap()
-            } else {
-                new_base_branch
-            };
-            TaskAttempt::update_base_branch(&deployment.db().pool, task_attempt.id, db_branch_name)
+            let db_branch_name = new_base_branch
+                .strip_prefix("origin/")
+                .unwrap_or(new_base_branch);
+            if db_branch_name != ctx.task_attempt.base_branch {
+                TaskAttempt::update_base_branch(
+                    &deployment.db().pool,
+                    task_attempt.id,
+                    db_branch_name,
+                )
+                .await?;
+            }
+        }
+
+        // The tip is kept while the attempt is rebased onto its parent's branch,
+        // any other base has all of the parent's commits it still needs
+        let mut stacked_on_commit = None;
+        if let (Some(parent_id), Some(new_base_branch)) =
+            (ctx.task_attempt.parent_attempt_id, &effective_base_branch)
+        {
+            if let Some(parent_branch) = TaskAttempt::find_by_id(pool, parent_id)
+                .await?
+                .and_then(|parent| parent.branch)
+            {
+                if &parent_branch == new_base_branch {
+                    stacked_on_commit = deployment
+                        .git()
+                        .get_branch_oid(&ctx.project.git_repo_path, &parent_branch)
+                        .ok();
+                }
+            }
+        }
+        if stacked_on_commit != ctx.task_attempt.stacked_on_commit {
+            TaskAttempt::update_stacked_on_commit(
+                pool,
+                task_attempt.id,
+                stacked_on_commit.as_deref(),
+            )
+            .await?;
+        }
+    }
+    rebase_result?;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct StackedAttempt {
+    pub attempt: TaskAttempt,
+    pub task_title: String,
+    /// 0 for the attempt at the bottom of the stack
+    pub depth: usize,
+    /// Commits on the parent attempt's branch this attempt's branch doesn't have
+    pub commits_behind_parent: Option<usize>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackResult {
+    pub attempt_id: Uuid,
+    /// Why the attempt couldn't be restacked, the attempts on top of it are skipped
+    pub error: Option<String>,
+}
+
+/// The whole stack the attempt belongs to, bottom first, each attempt followed by
+/// the attempts stacked on it
+pub async fn get_task_attempt_stack(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<StackedAttempt>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let project = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?
+        .parent_project(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::ProjectNotFound))?;
+
+    let mut bottom = task_attempt;
+    let mut seen = vec![bottom.id];
+    while let Some(parent_id) = bottom.parent_attempt_id {
+        if seen.contains(&parent_id) {
+            break;
+        }
+        let Some(parent) = TaskAttempt::find_by_id(pool, parent_id).await? else {
+            break;
+        };
+        seen.push(parent.id);
+        bottom = parent;
+    }
+
+    let mut stack: Vec<StackedAttempt> = Vec::new();
+    let mut pending = vec![(bottom, 0)];
+    while let Some((attempt, depth)) = pending.pop() {
+        if stack.iter().any(|entry| entry.attempt.id == attempt.id) {
+            continue;
+        }
+        let task_title = attempt
+            .parent_task(pool)
+            .await?
+            .map(|task| task.title)
+            .unwrap_or_default();
+        let commits_behind_parent = match (&attempt.parent_attempt_id, &attempt.branch) {
+            (Some(_), Some(branch)) => deployment
+                .git()
+                .get_local_branch_status(&project.git_repo_path, branch, &attempt.base_branch)
+                .ok()
+                .map(|(_, behind)| behind),
+            _ => None,
+        };
+
+        let children = TaskAttempt::find_stacked_children(pool, attempt.id).await?;
+        // Pushed in reverse so the oldest child is listed first
+        pending.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        stack.push(StackedAttempt {
+            attempt,
+            task_title,
+            depth,
+            commits_behind_parent,
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(stack)))
+}
+
+/// Rebase every attempt stacked on this one onto its updated parent, in order
+pub async fn restack_task_attempt(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RestackResult>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let project = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?
+        .parent_project(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::ProjectNotFound))?;
+
+    let settings = ProjectCommitSettings::find_or_default(pool, project.id).await?;
+    let mut results = Vec::new();
+    restack_descendants(
+        &deployment,
+        &project.git_repo_path,
+        &task_attempt,
+        None,
+        &commit_settings::commit_options(&settings),
+        &mut results,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+/// Rebase a stacked attempt's branch onto `onto_branch`, replaying the commits
+/// after the parent tip it was last based on, and record the tip it's now based
+/// on. `fallback_upstream` is used for attempts without a recorded tip. Returns
+/// the attempt's tip from before the rebase, the fallback for the attempts on
+/// top of it.
+async fn restack_attempt(
+    deployment: &DeploymentImpl,
+    repo_path: &std::path::Path,
+    attempt: &TaskAttempt,
+    onto_branch: &str,
+    fallback_upstream: Option<&str>,
+    commit_options: &CommitOptions,
+) -> Result<Option<String>, ApiError> {
+    let old_tip = attempt
+        .branch
+        .as_ref()
+        .and_then(|branch| deployment.git().get_branch_oid(repo_path, branch).ok());
+    let onto_tip = deployment.git().get_branch_oid(repo_path, onto_branch)?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(attempt)
+        .await?;
+    let result = deployment.git().rebase_onto(
+        std::path::Path::new(&container_ref),
+        onto_branch,
+        attempt.stacked_on_commit.as_deref().or(fallback_upstream),
+        commit_options,
+    );
+    // A rebase that stopped on conflicts is already onto the new tip
+    if matches!(result, Ok(_) | Err(GitServiceError::RebaseConflicts(_))) {
+        TaskAttempt::update_stacked_on_commit(&deployment.db().pool, attempt.id, Some(&onto_tip))
+            .await?;
+    }
+    result?;
+    Ok(old_tip)
+}
+
+/// Restack the attempts above `parent`, depth first. `parent_old_tip` is the
+/// parent's tip before it was rewritten, `None` when it only gained commits; it
+/// is only used for attempts without a recorded parent tip.
+async fn restack_descendants(
+    deployment: &DeploymentImpl,
+    repo_path: &std::path::Path,
+    parent: &TaskAttempt,
+    parent_old_tip: Option<String>,
+    commit_options: &CommitOptions,
+    results: &mut Vec<RestackResult>,
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let mut pending = vec![(parent.clone(), parent_old_tip)];
+    while let Some((parent, upstream)) = pending.pop() {
+        let Some(parent_branch) = parent.branch.clone() else {
+            continue;
+        };
+        for child in TaskAttempt::find_stacked_children(pool, parent.id).await? {
+            match restack_attempt(
+                deployment,
+                repo_path,
+                &child,
+                &parent_branch,
+                upstream.as_deref(),
+                commit_options,
+            )
+            .await
+            {
+                Ok(old_tip) => {
+                    results.push(RestackResult {
+                        attempt_id: child.id,
+                        error: None,
+                    });
+                    pending.push((child, old_tip));
+                }
+                Err(e) => results.push(RestackResult {
+                    attempt_id: child.id,
+                    error: Some(e.to_string()),
+                }),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Once an attempt is merged, move the attempts stacked on it onto its base
+/// branch, replaying only their own commits, and restack the rest of the stack.
+/// Their recorded parent tip stays the upstream, the merge may have rewritten
+/// the parent's commits.
+async fn retarget_stacked_children(
+    deployment: &DeploymentImpl,
+    repo_path: &std::path::Path,
+    merged: &TaskAttempt,
+    merged_old_tip: Option<String>,
+    commit_options: &CommitOptions,
+) -> Result<Vec<RestackResult>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut results = Vec::new();
+    for child in TaskAttempt::find_stacked_children(pool, merged.id).await? {
+        TaskAttempt::update_base_branch(pool, child.id, &merged.base_branch).await?;
+        TaskAttempt::update_parent_attempt(pool, child.id, merged.parent_attempt_id).await?;
+
+        match restack_attempt(
+            deployment,
+            repo_path,
+            &child,
+            &merged.base_branch,
+            merged_old_tip.as_deref(),
+            commit_options,
+        )
+        .await
+        {
+            Ok(old_tip) => {
+                results.push(RestackResult {
+                    attempt_id: child.id,
+                    error: None,
+                });
+                restack_descendants(
+                    deployment,
+                    repo_path,
+                    &child,
+                    old_tip,
+                    commit_options,
+                    &mut results,
+                )
                 .await?;
+            }
+            Err(e) => results.push(RestackResult {
+                attempt_id: child.id,
+                error: Some(e.to_string()),
+            }),
         }
     }
+    Ok(results)
+}
+
+pub async fn get_task_attempt_conflicts(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<RebaseConflicts>>>, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let conflicts = deployment
+        .git()
+        .get_rebase_conflicts(std::path::Path::new(&container_ref))?;
+    Ok(ResponseJson(ApiResponse::success(conflicts)))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ResolveConflictRequest {
+    pub file_path: String,
+    pub resolution: ConflictResolution,
+}
+
+pub async fn resolve_task_attempt_conflict(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    deployment.git().resolve_conflict(
+        std::path::Path::new(&container_ref),
+        &payload.file_path,
+        payload.resolution,
+    )?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Start a follow-up asking the agent to resolve the conflicts in the worktree.
+/// The rebase is left stopped for the user to continue once the agent is done.
+pub async fn ask_agent_to_resolve_conflicts(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    let conflicts = deployment
+        .git()
+        .get_rebase_conflicts(std::path::Path::new(&container_ref))?
+        .ok_or(ApiError::GitService(GitServiceError::NoRebaseInProgress))?;
+    if conflicts.files.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "All conflicts are resolved, continue the rebase",
+        )));
+    }
+
+    let prompt = conflict_resolution_prompt(&task_attempt.base_branch, &conflicts);
+    follow_up(
+        Extension(task_attempt),
+        State(deployment),
+        Json(CreateFollowUpAttempt {
+            prompt,
+            variant: None,
+            image_ids: None,
+            profile: None,
+        }),
+    )
+    .await
+}
+
+fn conflict_resolution_prompt(base_branch: &str, conflicts: &RebaseConflicts) -> String {
+    let commit = match (&conflicts.commit, &conflicts.commit_summary) {
+        (Some(sha), Some(summary)) => format!(" while applying {} ({summary})", &sha[..7]),
+        (Some(sha), None) => format!(" while applying {}", &sha[..7]),
+        _ => String::new(),
+    };
+    let mut prompt = format!(
+        "Rebasing this branch onto `{base_branch}` stopped on conflicts{commit}. Resolve the conflicts in these files:\n"
+    );
+    for file in &conflicts.files {
+        prompt.push_str(&format!("- {}\n", file.path));
+    }
+    prompt.push_str(
+        "\nEdit each file so it keeps the intent of both sides, remove the conflict markers and stage it with `git add`. Don't commit and don't run `git rebase --continue`, the user continues the rebase after reviewing your resolution.",
+    );
+    prompt
+}
+
+pub async fn continue_task_attempt_rebase(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let settings = ProjectCommitSettings::find_or_default(pool, task.project_id).await?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    deployment.git().continue_rebase(
+        std::path::Path::new(&container_ref),
+        &commit_settings::commit_options(&settings),
+    )?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn abort_task_attempt_rebase(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    deployment
+        .git()
+        .abort_rebase(std::path::Path::new(&container_ref))?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RevertToCheckpointRequest {
+    /// Execution to undo, along with every execution after it
+    pub execution_process_id: Uuid,
+}
+
+/// Reset the attempt branch and worktree to the checkpoint recorded before an
+/// execution started, discarding the changes of that execution and later ones
+pub async fn revert_task_attempt_to_checkpoint(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RevertToCheckpointRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await?;
+    let Some(execution_process) = processes
+        .iter()
+        .find(|process| process.id == payload.execution_process_id)
+    else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Execution process not found for this attempt",
+        )));
+    };
+    if execution_process.reverted {
+        return Ok(ResponseJson(ApiResponse::error(
+            "This execution was already reverted",
+        )));
+    }
+    let Some(checkpoint) = &execution_process.checkpoint_commit else {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No checkpoint was recorded for this execution",
+        )));
+    };
+    if processes.iter().any(|process| {
+        process.run_reason != ExecutionProcessRunReason::DevServer
+            && matches!(
+                process.status,
+                ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+            )
+    }) {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Stop the running execution before reverting to a checkpoint",
+        )));
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&task_attempt)
+        .await?;
+    deployment
+        .git()
+        .reset_to_checkpoint(std::path::Path::new(&container_ref), checkpoint)?;
+    let reverted =
+        ExecutionProcess::mark_reverted_since(pool, task_attempt.id, execution_process.created_at)
+            .await?;
+    tracing::info!(
+        "Reverted task attempt {} to checkpoint {}, discarding {} executions",
+        task_attempt.id,
+        checkpoint,
+        reverted
+    );
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
@@ -850,6 +1949,23 @@ pub async fn get_task_attempt_children(
     }
 }
 
+pub async fn get_task_attempt_file_activity(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<FileActivity>>>, ApiError> {
+    let files = deployment.container().file_activity(&task_attempt).await?;
+    Ok(ResponseJson(ApiResponse::success(files)))
+}
+
+pub async fn get_task_attempt_test_runs(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TestRun>>>, ApiError> {
+    let test_runs =
+        TestRun::find_by_task_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(test_runs)))
+}
+
 pub async fn stop_task_attempt_execution(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
@@ -866,12 +1982,32 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/branch-status", get(get_task_attempt_branch_status))
         .route("/diff", get(get_task_attempt_diff))
         .route("/merge", post(merge_task_attempt))
+        .route("/partial-merge", post(partial_merge_task_attempt))
+        .route(
+            "/partial-merge/hunks",
+            get(get_task_attempt_selectable_hunks),
+        )
         .route("/push", post(push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
+        .route("/rebase/continue", post(continue_task_attempt_rebase))
+        .route("/rebase/abort", post(abort_task_attempt_rebase))
+        .route(
+            "/revert-to-checkpoint",
+            post(revert_task_attempt_to_checkpoint),
+        )
+        .route("/stack", get(get_task_attempt_stack))
+        .route("/restack", post(restack_task_attempt))
+        .route("/conflicts", get(get_task_attempt_conflicts))
+        .route("/conflicts/resolve", post(resolve_task_attempt_conflict))
+        .route("/conflicts/ask-agent", post(ask_agent_to_resolve_conflicts))
         .route("/pr", post(create_github_pr))
+        .route("/revert-merge", post(revert_task_attempt_merge))
+        .route("/transcript", get(export_task_attempt_transcript))
         .route("/open-editor", post(open_task_attempt_in_editor))
         .route("/delete-file", post(delete_task_attempt_file))
         .route("/children", get(get_task_attempt_children))
+        .route("/test-runs", get(get_task_attempt_test_runs))
+        .route("/file-activity", get(get_task_attempt_file_activity))
         .route("/stop", post(stop_task_attempt_execution))
         .layer(from_fn_with_state(
             deployment.clone(),
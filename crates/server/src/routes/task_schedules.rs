@@ -0,0 +1,193 @@
+use axum::{
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use db::models::{
+    project::Project,
+    task::Task,
+    task_schedule::{CreateTaskSchedule, TaskSchedule, UpdateTaskSchedule},
+    task_template::TaskTemplate,
+};
+use deployment::Deployment;
+use executors::profile::ProfileConfigs;
+use serde::Deserialize;
+use services::services::task_scheduler::next_run_after;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{error::ApiError, middleware::load_task_schedule_middleware, DeploymentImpl};
+
+#[derive(Debug, Deserialize)]
+pub struct TaskScheduleQuery {
+    project_id: Option<Uuid>,
+}
+
+pub async fn get_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let schedules = match query.project_id {
+        Some(project_id) => {
+            TaskSchedule::find_by_project_id(&deployment.db().pool, project_id).await?
+        }
+        None => TaskSchedule::find_all(&deployment.db().pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn get_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn create_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    if payload.task_id.is_some() == payload.task_template_id.is_some() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "A schedule needs exactly one of task_id or task_template_id",
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Project not found: {}",
+            payload.project_id
+        ))));
+    }
+    if let Some(task_id) = payload.task_id {
+        let task = Task::find_by_id(pool, task_id).await?;
+        if task.is_none_or(|task| task.project_id != payload.project_id) {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Task {} not found in project {}",
+                task_id, payload.project_id
+            ))));
+        }
+    }
+    if let Some(template_id) = payload.task_template_id {
+        // Global templates have no project and can be scheduled in any project
+        let template = TaskTemplate::find_by_id(pool, template_id).await?;
+        if template.is_none_or(|template| {
+            template
+                .project_id
+                .is_some_and(|project_id| project_id != payload.project_id)
+        }) {
+            return Ok(ResponseJson(ApiResponse::error(&format!(
+                "Task template {} not found in project {}",
+                template_id, payload.project_id
+            ))));
+        }
+    }
+
+    if ProfileConfigs::get_cached()
+        .get_profile(&payload.profile)
+        .is_none()
+    {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Profile not found: {}",
+            payload.profile
+        ))));
+    }
+
+    let next_run_at = match next_run_after(&payload.cron_expression, Utc::now()) {
+        Ok(next_run_at) => next_run_at,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    };
+
+    let schedule = TaskSchedule::create(pool, &payload, Uuid::new_v4(), next_run_at).await?;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    // Destructure payload to handle field updates.
+    // `None` for the variant is treated as an explicit `null` to clear it, like the
+    // optional fields of a project update.
+    let UpdateTaskSchedule {
+        cron_expression,
+        profile,
+        variant,
+        base_branch,
+        enabled,
+    } = payload;
+
+    let cron_expression = cron_expression.unwrap_or(schedule.cron_expression.clone());
+    let profile = profile.unwrap_or(schedule.profile.clone());
+    let base_branch = base_branch.unwrap_or(schedule.base_branch.clone());
+    let enabled = enabled.unwrap_or(schedule.enabled);
+
+    if ProfileConfigs::get_cached().get_profile(&profile).is_none() {
+        return Ok(ResponseJson(ApiResponse::error(&format!(
+            "Profile not found: {}",
+            profile
+        ))));
+    }
+
+    // Recompute the next run so edits and re-enabling take effect from now
+    let next_run_at = match next_run_after(&cron_expression, Utc::now()) {
+        Ok(next_run_at) if enabled => next_run_at,
+        Ok(_) => None,
+        Err(e) => return Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    };
+
+    let schedule = TaskSchedule::update(
+        &deployment.db().pool,
+        schedule.id,
+        &cron_expression,
+        &profile,
+        variant.as_deref(),
+        &base_branch,
+        enabled,
+        next_run_at,
+    )
+    .await?;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn delete_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskSchedule::delete(&deployment.db().pool, schedule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let task_schedule_router = Router::new()
+        .route(
+            "/",
+            get(get_schedule)
+                .put(update_schedule)
+                .delete(delete_schedule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_schedule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_schedules).post(create_schedule))
+        .nest("/{schedule_id}", task_schedule_router);
+
+    Router::new().nest("/schedules", inner)
+}
@@ -6,6 +6,7 @@ use axum::{
     Extension, Json, Router,
 };
 use db::models::{
+    execution_process::ExecutionProcessStatus,
     image::TaskImage,
     project::Project,
     task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
@@ -147,6 +148,7 @@ pub async fn create_task_and_start(
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
 
     tracing::info!("Started execution process {}", execution_process.id);
+    let is_queued = execution_process.status == ExecutionProcessStatus::Queued;
     Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
         id: task.id,
         title: task.title,
@@ -157,10 +159,13 @@ pub async fn create_task_and_start(
         parent_task_attempt: task.parent_task_attempt,
         created_at: task.created_at,
         updated_at: task.updated_at,
-        has_in_progress_attempt: true,
+        has_in_progress_attempt: !is_queued,
+        has_queued_attempt: is_queued,
         has_merged_attempt: false,
         last_attempt_failed: false,
         profile: task_attempt.profile,
+        tests_passed: None,
+        tests_failed: None,
     })))
 }
 
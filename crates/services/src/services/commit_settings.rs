@@ -0,0 +1,163 @@
+use db::models::{
+    project_commit_settings::{ProjectCommitSettings, UpdateProjectCommitSettings},
+    task_attempt::TaskAttempt,
+};
+use thiserror::Error;
+
+use crate::services::git::{CommitOptions, CommitSigner};
+
+#[derive(Debug, Error)]
+pub enum CommitSettingsError {
+    #[error("Set both a name and an email for the commit identity")]
+    IncompleteIdentity,
+    #[error("Co-authored-by needs a commit identity to credit the git user next to")]
+    CoAuthorWithoutIdentity,
+}
+
+/// Check settings before they are stored
+pub fn validate(data: &UpdateProjectCommitSettings) -> Result<(), CommitSettingsError> {
+    let name = data.author_name.as_deref().map(str::trim).unwrap_or("");
+    let email = data.author_email.as_deref().map(str::trim).unwrap_or("");
+    if name.is_empty() != email.is_empty() {
+        return Err(CommitSettingsError::IncompleteIdentity);
+    }
+    if data.co_authored_by && name.is_empty() {
+        return Err(CommitSettingsError::CoAuthorWithoutIdentity);
+    }
+    Ok(())
+}
+
+/// Identity and signing of a project's commits, without trailers. Used when
+/// rewriting commits, which keep their messages.
+pub fn commit_options(settings: &ProjectCommitSettings) -> CommitOptions {
+    let identity = match (&settings.author_name, &settings.author_email) {
+        (Some(name), Some(email)) if !name.trim().is_empty() && !email.trim().is_empty() => {
+            Some((name.trim().to_string(), email.trim().to_string()))
+        }
+        _ => None,
+    };
+    CommitOptions {
+        identity,
+        co_authored_by: settings.co_authored_by,
+        trailers: Vec::new(),
+        signing: settings.signing_format.map(|format| CommitSigner {
+            format,
+            key: settings
+                .signing_key
+                .as_deref()
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string),
+        }),
+    }
+}
+
+/// Commit options for new commits made for an attempt: agent commits and
+/// direct merges, with the trailers naming its task, the attempt and its profile
+pub fn attempt_commit_options(
+    settings: &ProjectCommitSettings,
+    task_attempt: &TaskAttempt,
+) -> CommitOptions {
+    let mut options = commit_options(settings);
+    if settings.task_id_trailer {
+        options
+            .trailers
+            .push(("Task-Id".to_string(), task_attempt.task_id.to_string()));
+    }
+    if settings.attempt_id_trailer {
+        options
+            .trailers
+            .push(("Attempt-Id".to_string(), task_attempt.id.to_string()));
+    }
+    if settings.profile_trailer {
+        options
+            .trailers
+            .push(("Agent-Profile".to_string(), task_attempt.profile.clone()));
+    }
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::project_commit_settings::CommitSigningFormat;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn update(name: Option<&str>, email: Option<&str>) -> UpdateProjectCommitSettings {
+        UpdateProjectCommitSettings {
+            author_name: name.map(str::to_string),
+            author_email: email.map(str::to_string),
+            co_authored_by: false,
+            task_id_trailer: false,
+            attempt_id_trailer: false,
+            profile_trailer: false,
+            signing_format: None,
+            signing_key: None,
+        }
+    }
+
+    #[test]
+    fn requires_a_complete_identity() {
+        assert!(validate(&update(None, None)).is_ok());
+        assert!(validate(&update(Some("Agent"), Some("agent@example.com"))).is_ok());
+        assert!(matches!(
+            validate(&update(Some("Agent"), Some("  "))),
+            Err(CommitSettingsError::IncompleteIdentity)
+        ));
+
+        let mut data = update(None, None);
+        data.co_authored_by = true;
+        assert!(matches!(
+            validate(&data),
+            Err(CommitSettingsError::CoAuthorWithoutIdentity)
+        ));
+    }
+
+    #[test]
+    fn builds_attempt_trailers_and_signer() {
+        let project_id = Uuid::new_v4();
+        let mut settings = ProjectCommitSettings::default_for(project_id);
+        settings.author_name = Some("Agent".to_string());
+        settings.author_email = Some("agent@example.com".to_string());
+        settings.task_id_trailer = true;
+        settings.profile_trailer = true;
+        settings.signing_format = Some(CommitSigningFormat::Ssh);
+        settings.signing_key = Some(" ".to_string());
+
+        let now = Utc::now();
+        let attempt = TaskAttempt {
+            id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            container_ref: None,
+            branch: None,
+            base_branch: "main".to_string(),
+            profile: "CLAUDE_CODE".to_string(),
+            worktree_deleted: false,
+            setup_completed_at: None,
+            parent_attempt_id: None,
+            stacked_on_commit: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let options = attempt_commit_options(&settings, &attempt);
+        assert_eq!(
+            options.identity,
+            Some(("Agent".to_string(), "agent@example.com".to_string()))
+        );
+        assert_eq!(
+            options.trailers,
+            vec![
+                ("Task-Id".to_string(), attempt.task_id.to_string()),
+                ("Agent-Profile".to_string(), "CLAUDE_CODE".to_string()),
+            ]
+        );
+        let signer = options.signing.expect("signing is set");
+        assert_eq!(signer.format, CommitSigningFormat::Ssh);
+        assert_eq!(signer.key, None);
+
+        assert!(commit_options(&settings).trailers.is_empty());
+    }
+}
@@ -14,14 +14,15 @@ pub enum ConfigError {
     ValidationError(String),
 }
 
-pub type Config = versions::v5::Config;
-pub type NotificationConfig = versions::v5::NotificationConfig;
-pub type EditorConfig = versions::v5::EditorConfig;
-pub type ThemeMode = versions::v5::ThemeMode;
-pub type SoundFile = versions::v5::SoundFile;
-pub type EditorType = versions::v5::EditorType;
-pub type GitHubConfig = versions::v5::GitHubConfig;
-pub type JiraConfig = versions::v5::JiraConfig;
+pub type Config = versions::v7::Config;
+pub type NotificationConfig = versions::v7::NotificationConfig;
+pub type EditorConfig = versions::v7::EditorConfig;
+pub type ThemeMode = versions::v7::ThemeMode;
+pub type SoundFile = versions::v7::SoundFile;
+pub type EditorType = versions::v7::EditorType;
+pub type GitHubConfig = versions::v7::GitHubConfig;
+pub type JiraConfig = versions::v7::JiraConfig;
+pub type LogRetentionConfig = versions::v7::LogRetentionConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
@@ -1,15 +1,17 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     path::PathBuf,
     sync::{
         Arc,
         atomic::{AtomicUsize, Ordering},
     },
+    time::Duration,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
 use async_trait::async_trait;
 use axum::response::sse::Event;
+use chrono::Utc;
 use db::{
     DBService,
     models::{
@@ -17,36 +19,61 @@ use db::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
             ExecutionProcessStatus,
         },
-        execution_process_logs::ExecutionProcessLogs,
+        execution_process_logs::{ExecutionLogWriter, ExecutionProcessLogs},
+        execution_process_normalized_entry::ExecutionProcessNormalizedEntry,
+        execution_queue::{CreateExecutionQueueEntry, ExecutionQueueEntry},
         executor_session::{CreateExecutorSession, ExecutorSession},
+        merge::Merge,
+        project::Project,
+        project_retry_policy::ProjectRetryPolicy,
         task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
+        test_run::TestRun,
     },
 };
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{CodingAgent, ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryType, utils::patch::ConversationPatch},
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryType,
+        test_run::{TestRunSummary, parse_test_output},
+        utils::patch::{
+            ConversationPatch, extract_indexed_normalized_entries, extract_normalized_entries,
+            normalized_entry_changes,
+        },
+    },
     profile::ProfileVariantLabel,
 };
 use futures::{StreamExt, TryStreamExt, future};
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
-use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use utils::{diff::Diff, log_msg::LogMsg, msg_store::MsgStore};
 use uuid::Uuid;
 
 use crate::services::{
-    git::{GitService, GitServiceError},
+    execution_queue::ExecutionQueue,
+    file_activity::{FileActivity, build_file_activity},
+    git::{DiffTarget, GitService, GitServiceError},
+    handoff::{HandoffContext, build_handoff_prompt},
     image::ImageService,
+    retry_policy,
+    transcript::{TranscriptBundle, TranscriptProcess},
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
 
+/// Follow-up prompt used to continue a session interrupted by a server restart
+const RESUME_PROMPT: &str = "Your previous run was interrupted before it finished. Check the current state of the working tree and continue the task from where you left off.";
+
+// How often normalized entries of a running execution are written out
+const NORMALIZED_ENTRIES_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -68,7 +95,7 @@ pub enum ContainerError {
 }
 
 #[async_trait]
-pub trait ContainerService {
+pub trait ContainerService: Send + Sync {
     fn msg_stores(&self) -> &Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>;
 
     fn db(&self) -> &DBService;
@@ -77,6 +104,9 @@ pub trait ContainerService {
 
     fn task_attempt_to_current_dir(&self, task_attempt: &TaskAttempt) -> PathBuf;
 
+    /// Global limit on concurrently running coding agents, None means unlimited
+    async fn global_agent_limit(&self) -> Option<u32>;
+
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError>;
 
     async fn delete(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError> {
@@ -85,12 +115,33 @@ pub trait ContainerService {
     }
 
     async fn try_stop(&self, task_attempt: &TaskAttempt) {
+        // A failed execution waiting to be retried has no process, cancel the retry
+        match ExecutionProcess::cancel_retries_for_attempt(&self.db().pool, task_attempt.id).await {
+            Ok(0) => {}
+            Ok(_) => {
+                if let Ok(Some(task)) = task_attempt.parent_task(&self.db().pool).await
+                    && let Err(e) =
+                        Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await
+                {
+                    tracing::error!("Failed to update task status to InReview: {e}");
+                }
+            }
+            Err(e) => tracing::error!(
+                "Failed to cancel retries for task attempt {}: {}",
+                task_attempt.id,
+                e
+            ),
+        }
+
         // stop all execution processes for this attempt
         if let Ok(processes) =
             ExecutionProcess::find_by_task_attempt_id(&self.db().pool, task_attempt.id).await
         {
             for process in processes {
-                if process.status == ExecutionProcessStatus::Running {
+                if matches!(
+                    process.status,
+                    ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+                ) {
                     self.stop_execution(&process).await.unwrap_or_else(|e| {
                         tracing::debug!(
                             "Failed to stop execution process {} for task attempt {}: {}",
@@ -119,6 +170,13 @@ pub trait ContainerService {
         executor_action: &ExecutorAction,
     ) -> Result<(), ContainerError>;
 
+    /// Attach to a live process left behind by a previous server run, returning
+    /// false if it has exited
+    async fn reattach_execution_inner(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<bool, ContainerError>;
+
     async fn stop_execution(
         &self,
         execution_process: &ExecutionProcess,
@@ -155,7 +213,10 @@ pub trait ContainerService {
                 store
                     .history_plus_stream()
                     .filter(|msg| {
-                        future::ready(matches!(msg, Ok(LogMsg::Stdout(..) | LogMsg::Stderr(..))))
+                        future::ready(matches!(
+                            msg,
+                            Ok(LogMsg::Stdout(..) | LogMsg::Stderr(..) | LogMsg::Evicted(..))
+                        ))
                     })
                     .map_ok({
                         let counter = counter.clone();
@@ -170,7 +231,9 @@ pub trait ContainerService {
                                     let patch = ConversationPatch::add_stderr(index, content);
                                     LogMsg::JsonPatch(patch).to_sse_event()
                                 }
-                                _ => unreachable!("Filter should only pass Stdout/Stderr"),
+                                // Let the client know part of the log is missing
+                                evicted @ LogMsg::Evicted(_) => evicted.to_sse_event(),
+                                _ => unreachable!("Filter should only pass Stdout/Stderr/Evicted"),
                             }
                         }
                     })
@@ -235,143 +298,466 @@ pub trait ContainerService {
             Some(
                 store
                     .history_plus_stream() // BoxStream<Result<LogMsg, io::Error>>
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                    .filter(|msg| {
+                        future::ready(matches!(
+                            msg,
+                            Ok(LogMsg::JsonPatch(..) | LogMsg::Evicted(..))
+                        ))
+                    })
                     .map_ok(|m| m.to_sse_event()) // LogMsg -> Event
                     .boxed(),
             )
         } else {
-            // Fallback: load from DB and normalize
-            let logs_record =
-                match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
-                    Ok(Some(record)) => record,
-                    Ok(None) => return None, // No logs exist
-                    Err(e) => {
-                        tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
-                        return None;
-                    }
-                };
-
-            let raw_messages = match logs_record.parse_logs() {
-                Ok(msgs) => msgs,
-                Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
-                    return None;
+            // Serve the persisted conversation if there is one
+            match ExecutionProcessNormalizedEntry::find_by_execution_id(&self.db().pool, *id).await
+            {
+                Ok(entries) if !entries.is_empty() => {
+                    let events = entries.into_iter().map(|entry| {
+                        let patch = ConversationPatch::add_normalized_entry(
+                            entry.entry_index as usize,
+                            entry.entry.0,
+                        );
+                        Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch).to_sse_event())
+                    });
+                    return Some(
+                        futures::stream::iter(events)
+                            .chain(futures::stream::once(async {
+                                Ok::<_, std::io::Error>(LogMsg::Finished.to_sse_event())
+                            }))
+                            .boxed(),
+                    );
                 }
-            };
-
-            // Create temporary store and populate
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
-                    temp_store.push(msg);
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Failed to fetch normalized entries for {}: {}", id, e);
                 }
             }
-            temp_store.push_finished();
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
-                Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
-                    return None;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
-                    return None;
-                }
-            };
+            // Fallback: load raw logs from DB and normalize
+            let (temp_store, _normalizers) = self.normalize_stored_logs(id, true).await?;
+            Some(
+                temp_store
+                    .history_plus_stream()
+                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
+                    .map_ok(|m| m.to_sse_event())
+                    .chain(futures::stream::once(async {
+                        Ok::<_, std::io::Error>(LogMsg::Finished.to_sse_event())
+                    }))
+                    .boxed(),
+            )
+        }
+    }
 
-            // Get the task attempt to determine correct directory
-            let task_attempt = match process.parent_task_attempt(&self.db().pool).await {
-                Ok(Some(task_attempt)) => task_attempt,
-                Ok(None) => {
-                    tracing::error!("No task attempt found for ID: {}", process.task_attempt_id);
-                    return None;
-                }
+    /// Re-run log normalization over the raw logs stored for an execution
+    /// process, returning a finished store the normalizer patches are pushed to
+    /// and the normalizer tasks. Normalizers only need the worktree path, so it is
+    /// recreated only if asked for.
+    async fn normalize_stored_logs(
+        &self,
+        id: &Uuid,
+        recreate_worktree: bool,
+    ) -> Option<(Arc<MsgStore>, Vec<JoinHandle<()>>)> {
+        let logs_record =
+            match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(Some(record)) => record,
+                Ok(None) => return None, // No logs exist
                 Err(e) => {
-                    tracing::error!(
-                        "Failed to fetch task attempt {}: {}",
-                        process.task_attempt_id,
-                        e
-                    );
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
                     return None;
                 }
             };
 
-            if let Err(err) = self.ensure_container_exists(&task_attempt).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for task attempt {}: {}",
-                    task_attempt.id,
-                    err
-                );
+        let raw_messages = match logs_record.parse_logs() {
+            Ok(msgs) => msgs,
+            Err(e) => {
+                tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                return None;
             }
+        };
 
-            let current_dir = self.task_attempt_to_current_dir(&task_attempt);
+        // Create temporary store and populate
+        let temp_store = Arc::new(MsgStore::new());
+        for msg in raw_messages {
+            if matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
+                temp_store.push(msg);
+            }
+        }
+        temp_store.push_finished();
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the task attempt to determine correct directory
+        let task_attempt = match process.parent_task_attempt(&self.db().pool).await {
+            Ok(Some(task_attempt)) => task_attempt,
+            Ok(None) => {
+                tracing::error!("No task attempt found for ID: {}", process.task_attempt_id);
+                return None;
+            }
+            Err(e) => {
                 tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
+                    "Failed to fetch task attempt {}: {}",
+                    process.task_attempt_id,
+                    e
                 );
                 return None;
-            };
+            }
+        };
 
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    if let Ok(executor) =
-                        CodingAgent::from_profile_variant_label(&request.profile_variant_label)
-                    {
-                        // Inject the initial user prompt before normalization (DB fallback path)
-                        let user_entry = create_user_message(request.prompt.clone());
-                        temp_store
-                            .push_patch(ConversationPatch::add_normalized_entry(0, user_entry));
+        if recreate_worktree && let Err(err) = self.ensure_container_exists(&task_attempt).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for task attempt {}: {}",
+                task_attempt.id,
+                err
+            );
+        }
 
-                        executor.normalize_logs(temp_store.clone(), &current_dir);
-                    } else {
-                        tracing::error!(
-                            "Failed to resolve profile '{:?}' for normalization",
-                            request.profile_variant_label
-                        );
-                    }
-                }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    if let Ok(executor) =
-                        CodingAgent::from_profile_variant_label(&request.profile_variant_label)
-                    {
-                        // Inject the follow-up user prompt before normalization (DB fallback path)
-                        let user_entry = create_user_message(request.prompt.clone());
-                        temp_store
-                            .push_patch(ConversationPatch::add_normalized_entry(0, user_entry));
+        let current_dir = self.task_attempt_to_current_dir(&task_attempt);
 
-                        executor.normalize_logs(temp_store.clone(), &current_dir);
-                    } else {
-                        tracing::error!(
-                            "Failed to resolve profile '{:?}' for normalization",
-                            request.profile_variant_label
-                        );
-                    }
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
+
+        // Spawn normalizer on populated store
+        let normalizers = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                if let Ok(executor) =
+                    CodingAgent::from_profile_variant_label(&request.profile_variant_label)
+                {
+                    // Inject the initial user prompt before normalization (DB fallback path)
+                    let user_entry = create_user_message(request.prompt.clone());
+                    temp_store.push_patch(ConversationPatch::add_normalized_entry(0, user_entry));
+
+                    executor.normalize_logs(temp_store.clone(), &current_dir)
+                } else {
+                    tracing::error!(
+                        "Failed to resolve profile '{:?}' for normalization",
+                        request.profile_variant_label
+                    );
+                    Vec::new()
                 }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                if let Ok(executor) =
+                    CodingAgent::from_profile_variant_label(&request.profile_variant_label)
+                {
+                    // Inject the follow-up user prompt before normalization (DB fallback path)
+                    let user_entry = create_user_message(request.prompt.clone());
+                    temp_store.push_patch(ConversationPatch::add_normalized_entry(0, user_entry));
+
+                    executor.normalize_logs(temp_store.clone(), &current_dir)
+                } else {
+                    tracing::error!(
+                        "Failed to resolve profile '{:?}' for normalization",
+                        request.profile_variant_label
                     );
-                    return None;
+                    Vec::new()
                 }
             }
-            Some(
-                temp_store
-                    .history_plus_stream()
-                    .filter(|msg| future::ready(matches!(msg, Ok(LogMsg::JsonPatch(..)))))
-                    .map_ok(|m| m.to_sse_event())
-                    .chain(futures::stream::once(async {
-                        Ok::<_, std::io::Error>(LogMsg::Finished.to_sse_event())
-                    }))
-                    .boxed(),
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
+        };
+        Some((temp_store, normalizers))
+    }
+
+    /// The normalized conversation of an execution process, from its live store
+    /// if it is still running, or from the persisted entries otherwise
+    async fn normalized_entries(&self, id: &Uuid) -> Vec<NormalizedEntry> {
+        if let Some(store) = self.get_msg_store_by_id(id).await {
+            return extract_normalized_entries(&store.get_history());
+        }
+        match ExecutionProcessNormalizedEntry::find_by_execution_id(&self.db().pool, *id).await {
+            Ok(entries) if !entries.is_empty() => {
+                return entries.into_iter().map(|entry| entry.entry.0).collect();
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("Failed to fetch normalized entries for {}: {}", id, e);
+            }
+        }
+
+        // Executions from before entries were persisted, stored so this only runs once
+        let Some((store, normalizers)) = self.normalize_stored_logs(id, false).await else {
+            return Vec::new();
+        };
+        let entries = finished_normalized_entries(&store, normalizers).await;
+        if let Err(e) =
+            ExecutionProcessNormalizedEntry::replace_all(&self.db().pool, *id, &entries).await
+        {
+            tracing::error!("Failed to store normalized entries for {}: {}", id, e);
+        }
+        entries.into_values().collect()
+    }
+
+    /// Re-run normalization over the stored raw logs of a finished execution and
+    /// replace its persisted entries, e.g. after a parser upgrade. Returns the
+    /// number of entries stored.
+    async fn renormalize_execution(&self, id: &Uuid) -> Result<usize, ContainerError> {
+        if self.get_msg_store_by_id(id).await.is_some() {
+            return Err(ContainerError::Other(anyhow!(
+                "Execution process {} is still running",
+                id
+            )));
+        }
+        let Some((store, normalizers)) = self.normalize_stored_logs(id, false).await else {
+            return Ok(0);
+        };
+        let entries = finished_normalized_entries(&store, normalizers).await;
+        ExecutionProcessNormalizedEntry::replace_all(&self.db().pool, *id, &entries).await?;
+        Ok(entries.len())
+    }
+
+    /// Hand a task attempt over to a different coding agent. The new agent can't
+    /// resume the previous session, so it starts a fresh one in the same worktree
+    /// with a prompt built from the conversation so far and the current diff.
+    async fn start_handoff(
+        &self,
+        task_attempt: &TaskAttempt,
+        previous_profile: &ProfileVariantLabel,
+        profile_variant_label: ProfileVariantLabel,
+        instructions: &str,
+        next_action: Option<Box<ExecutorAction>>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let task = task_attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        // Runs reverted to a checkpoint are left out, their work is gone from the branch
+        let processes = ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await?;
+        let reverted: HashSet<Uuid> = processes
+            .iter()
+            .filter(|process| process.reverted)
+            .map(|process| process.id)
+            .collect();
+        let sessions: Vec<ExecutorSession> =
+            ExecutorSession::find_by_task_attempt_id(pool, task_attempt.id)
+                .await?
+                .into_iter()
+                .filter(|session| !reverted.contains(&session.execution_process_id))
+                .collect();
+
+        let mut conversation = Vec::new();
+        for process in &processes {
+            if process.run_reason == ExecutionProcessRunReason::CodingAgent && !process.reverted {
+                conversation.extend(self.normalized_entries(&process.id).await);
+            }
+        }
+
+        let worktree_path = PathBuf::from(self.ensure_container_exists(task_attempt).await?);
+        let diffs = match &task_attempt.branch {
+            Some(branch_name) => self.git().get_diffs(
+                DiffTarget::Worktree {
+                    worktree_path: &worktree_path,
+                    branch_name,
+                    base_branch: &task_attempt.base_branch,
+                },
+                None,
+            )?,
+            None => Vec::new(),
+        };
+
+        let prompt = build_handoff_prompt(&HandoffContext {
+            task_prompt: &task.to_prompt(),
+            previous_agent: &previous_profile.profile,
+            sessions: &sessions,
+            conversation: &conversation,
+            diffs: &diffs,
+            instructions,
+        });
+
+        let executor_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                profile_variant_label: profile_variant_label.clone(),
+            }),
+            next_action,
+        );
+        let execution_process = self
+            .start_execution(
+                task_attempt,
+                &executor_action,
+                &ExecutionProcessRunReason::CodingAgent,
             )
+            .await?;
+
+        TaskAttempt::update_profile(pool, task_attempt.id, &profile_variant_label.profile).await?;
+        Ok(execution_process)
+    }
+
+    /// Gather the transcript of a task attempt: every execution process with its
+    /// conversation or output, and the final diff
+    async fn build_transcript(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<TranscriptBundle, ContainerError> {
+        let pool = &self.db().pool;
+        let task = task_attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let mut processes = Vec::new();
+        let mut execution_processes =
+            ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await?;
+        execution_processes.sort_by_key(|ep| ep.started_at);
+        for execution_process in execution_processes {
+            let (entries, output) =
+                if execution_process.run_reason == ExecutionProcessRunReason::CodingAgent {
+                    (self.normalized_entries(&execution_process.id).await, None)
+                } else {
+                    (
+                        Vec::new(),
+                        Some(self.collect_output(&execution_process.id).await),
+                    )
+                };
+            processes.push(TranscriptProcess {
+                execution_process,
+                entries,
+                output,
+            });
+        }
+
+        let diffs = self.final_diffs(task_attempt, task.project_id).await?;
+
+        Ok(TranscriptBundle {
+            task,
+            task_attempt: task_attempt.clone(),
+            processes,
+            diffs,
+            exported_at: Utc::now(),
+        })
+    }
+
+    /// What an attempt changed: the merged commit once merged, the worktree's
+    /// changes before that
+    async fn final_diffs(
+        &self,
+        task_attempt: &TaskAttempt,
+        project_id: Uuid,
+    ) -> Result<Vec<Diff>, ContainerError> {
+        let pool = &self.db().pool;
+        let latest_merge = Merge::find_latest_by_task_attempt_id(pool, task_attempt.id).await?;
+        let diffs = match (
+            latest_merge.as_ref().and_then(|merge| merge.merge_commit()),
+            &task_attempt.branch,
+        ) {
+            (Some(commit), _) => {
+                let project = Project::find_by_id(pool, project_id)
+                    .await?
+                    .ok_or(SqlxError::RowNotFound)?;
+                self.git().get_diffs(
+                    DiffTarget::Commit {
+                        repo_path: &project.git_repo_path,
+                        commit_sha: &commit,
+                    },
+                    None,
+                )?
+            }
+            (None, Some(branch_name)) => {
+                let worktree_path =
+                    PathBuf::from(self.ensure_container_exists(task_attempt).await?);
+                self.git().get_diffs(
+                    DiffTarget::Worktree {
+                        worktree_path: &worktree_path,
+                        branch_name,
+                        base_branch: &task_attempt.base_branch,
+                    },
+                    None,
+                )?
+            }
+            (None, None) => Vec::new(),
+        };
+        Ok(diffs)
+    }
+
+    /// Files the attempt's coding agents read and changed, compared against
+    /// its final diff
+    async fn file_activity(
+        &self,
+        task_attempt: &TaskAttempt,
+    ) -> Result<Vec<FileActivity>, ContainerError> {
+        let pool = &self.db().pool;
+        let task = task_attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let mut execution_processes =
+            ExecutionProcess::find_by_task_attempt_id(pool, task_attempt.id).await?;
+        execution_processes.sort_by_key(|ep| ep.started_at);
+        let mut processes = Vec::new();
+        for execution_process in execution_processes
+            .iter()
+            .filter(|ep| ep.run_reason == ExecutionProcessRunReason::CodingAgent)
+        {
+            processes.push((
+                execution_process.id,
+                self.normalized_entries(&execution_process.id).await,
+            ));
         }
+
+        let diffs = self.final_diffs(task_attempt, task.project_id).await?;
+        Ok(build_file_activity(&processes, &diffs))
+    }
+
+    /// Persist the normalized entries of an execution as the normalizer produces them
+    fn spawn_stream_normalized_entries_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
+        let execution_id = *execution_id;
+        let msg_stores = self.msg_stores().clone();
+        let db = self.db().clone();
+
+        tokio::spawn(async move {
+            // Only keep the stream, so the store can be dropped once the execution ends
+            let stream = {
+                let map = msg_stores.read().await;
+                map.get(&execution_id)
+                    .map(|store| store.history_plus_stream())
+            };
+            let Some(mut stream) = stream else {
+                return;
+            };
+
+            let mut pending = BTreeMap::new();
+            let mut flush_interval = tokio::time::interval(NORMALIZED_ENTRIES_FLUSH_INTERVAL);
+            loop {
+                // Normalizers may still push entries after the process has finished,
+                // so follow the store until it is gone
+                tokio::select! {
+                    msg = stream.next() => match msg {
+                        Some(Ok(LogMsg::JsonPatch(patch))) => {
+                            pending.extend(normalized_entry_changes(&patch));
+                        }
+                        Some(Ok(_)) => {}
+                        _ => break,
+                    },
+                    _ = flush_interval.tick() => {
+                        flush_normalized_entries(&db, execution_id, &mut pending).await;
+                    }
+                }
+            }
+            flush_normalized_entries(&db, execution_id, &mut pending).await;
+        })
     }
 
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
@@ -388,8 +774,27 @@ pub trait ContainerService {
 
             if let Some(store) = store {
                 let mut stream = store.history_plus_stream();
+                let mut writer = ExecutionLogWriter::new(db.pool.clone(), execution_id);
+                let mut flush_interval = tokio::time::interval(ExecutionLogWriter::FLUSH_INTERVAL);
+
+                loop {
+                    let msg = tokio::select! {
+                        msg = stream.next() => match msg {
+                            Some(Ok(msg)) => msg,
+                            _ => break,
+                        },
+                        _ = flush_interval.tick() => {
+                            if let Err(e) = writer.flush().await {
+                                tracing::error!(
+                                    "Failed to write logs for execution {}: {}",
+                                    execution_id,
+                                    e
+                                );
+                            }
+                            continue;
+                        }
+                    };
 
-                while let Some(Ok(msg)) = stream.next().await {
                     match &msg {
                         LogMsg::Stdout(_) | LogMsg::Stderr(_) => {
                             // Serialize this individual message as a JSONL line
@@ -397,16 +802,11 @@ pub trait ContainerService {
                                 Ok(jsonl_line) => {
                                     let jsonl_line_with_newline = format!("{jsonl_line}\n");
 
-                                    // Append this line to the database
-                                    if let Err(e) = ExecutionProcessLogs::append_log_line(
-                                        &db.pool,
-                                        execution_id,
-                                        &jsonl_line_with_newline,
-                                    )
-                                    .await
+                                    // Buffer the line, it's written with the next chunk
+                                    if let Err(e) = writer.push_line(&jsonl_line_with_newline).await
                                     {
                                         tracing::error!(
-                                            "Failed to append log line for execution {}: {}",
+                                            "Failed to write logs for execution {}: {}",
                                             execution_id,
                                             e
                                         );
@@ -441,9 +841,20 @@ pub trait ContainerService {
                         LogMsg::Finished => {
                             break;
                         }
+                        LogMsg::Evicted(count) => {
+                            tracing::warn!(
+                                "{} log messages of execution {} were evicted before they could be written",
+                                count,
+                                execution_id
+                            );
+                        }
                         LogMsg::JsonPatch(_) => continue,
                     }
                 }
+
+                if let Err(e) = writer.flush().await {
+                    tracing::error!("Failed to write logs for execution {}: {}", execution_id, e);
+                }
             }
         })
     }
@@ -541,6 +952,18 @@ pub trait ContainerService {
         task_attempt: &TaskAttempt,
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        self.start_linked_execution(task_attempt, executor_action, run_reason, None)
+            .await
+    }
+
+    /// Like `start_execution`, recording the new process as a retry of `retry_of`
+    async fn start_linked_execution(
+        &self,
+        task_attempt: &TaskAttempt,
+        executor_action: &ExecutorAction,
+        run_reason: &ExecutionProcessRunReason,
+        retry_of: Option<Uuid>,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Update task status to InProgress when starting an attempt
         let task = task_attempt
@@ -552,11 +975,20 @@ pub trait ContainerService {
         {
             Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
         }
+        // Coding agents and setup scripts go through the queue so concurrency limits are respected
+        let queued = ExecutionQueue::is_limited(run_reason);
+
         // Create new execution process record
         let create_execution_process = CreateExecutionProcess {
             task_attempt_id: task_attempt.id,
             executor_action: executor_action.clone(),
             run_reason: run_reason.clone(),
+            status: if queued {
+                ExecutionProcessStatus::Queued
+            } else {
+                ExecutionProcessStatus::Running
+            },
+            retry_of,
         };
 
         let execution_process =
@@ -588,10 +1020,268 @@ pub trait ContainerService {
             .await?;
         }
 
-        let _ = self
-            .start_execution_inner(task_attempt, &execution_process, executor_action)
+        if queued {
+            ExecutionQueueEntry::create(
+                &self.db().pool,
+                &CreateExecutionQueueEntry {
+                    execution_process_id: execution_process.id,
+                    project_id: task.project_id,
+                    priority: 0,
+                },
+                Uuid::new_v4(),
+            )
+            .await?;
+            self.dispatch_queued_executions().await?;
+
+            // Return the latest state, the process may have been started straight away
+            let execution_process =
+                ExecutionProcess::find_by_id(&self.db().pool, execution_process.id)
+                    .await?
+                    .ok_or(SqlxError::RowNotFound)?;
+            return Ok(execution_process);
+        }
+
+        self.launch_execution(task_attempt, &execution_process, executor_action)
+            .await?;
+        Ok(execution_process)
+    }
+
+    /// Spawn the process for an execution record and start log processing
+    async fn launch_execution(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        self.start_execution_inner(task_attempt, execution_process, executor_action)
+            .await?;
+        self.start_log_processing(task_attempt, execution_process, executor_action)
+            .await;
+        Ok(())
+    }
+
+    /// Re-attach to an execution whose process outlived the previous server run.
+    /// Returns false if the process is no longer alive.
+    async fn reattach_execution(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        if !self
+            .reattach_execution_inner(&ctx.execution_process)
+            .await?
+        {
+            return Ok(false);
+        }
+
+        // The log files hold the whole run, so the persisted logs are rebuilt from them
+        ExecutionProcessLogs::delete_by_execution_id(&self.db().pool, ctx.execution_process.id)
+            .await?;
+        ExecutionProcessNormalizedEntry::delete_by_execution_id(
+            &self.db().pool,
+            ctx.execution_process.id,
+        )
+        .await?;
+
+        let executor_action = ctx.execution_process.executor_action()?;
+        self.start_log_processing(&ctx.task_attempt, &ctx.execution_process, executor_action)
+            .await;
+        Ok(true)
+    }
+
+    /// Continue a coding agent session that was interrupted by a server restart
+    /// in a new execution process. Returns None if the session can't be resumed.
+    async fn resume_interrupted_execution(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<ExecutionProcess>, ContainerError> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return Ok(None);
+        }
+
+        let Some(session_id) = ExecutorSession::find_by_execution_process_id(
+            &self.db().pool,
+            ctx.execution_process.id,
+        )
+        .await?
+        .and_then(|session| session.session_id) else {
+            return Ok(None);
+        };
+
+        let executor_action = ctx.execution_process.executor_action()?;
+        let profile_variant_label = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                request.profile_variant_label.clone()
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                request.profile_variant_label.clone()
+            }
+            _ => return Ok(None),
+        };
+
+        let resume_action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt: RESUME_PROMPT.to_string(),
+                session_id,
+                profile_variant_label,
+            }),
+            executor_action.next_action.clone(),
+        );
+
+        // Not linked as a retry, so resuming doesn't use up the retry policy's attempts
+        self.ensure_container_exists(&ctx.task_attempt).await?;
+        let execution_process = self
+            .start_execution(
+                &ctx.task_attempt,
+                &resume_action,
+                &ExecutionProcessRunReason::CodingAgent,
+            )
             .await?;
+        Ok(Some(execution_process))
+    }
+
+    /// How long to wait before retrying a failed execution under the project's
+    /// retry policy, or None if it shouldn't be retried
+    async fn retry_delay(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<Option<Duration>, ContainerError> {
+        if ctx.execution_process.status != ExecutionProcessStatus::Failed {
+            return Ok(None);
+        }
+
+        let pool = &self.db().pool;
+        let Some(policy) = ProjectRetryPolicy::find_for_run_reason(
+            pool,
+            ctx.task.project_id,
+            &ctx.execution_process.run_reason,
+        )
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let attempts =
+            ExecutionProcess::count_attempts_in_retry_chain(pool, ctx.execution_process.id).await?;
+        if attempts >= policy.max_attempts {
+            tracing::info!(
+                "Execution process {} failed after {} attempts, not retrying",
+                ctx.execution_process.id,
+                attempts
+            );
+            return Ok(None);
+        }
+
+        let stderr = self.collect_stderr(&ctx.execution_process.id).await;
+        if !retry_policy::is_retryable(&policy, ctx.execution_process.exit_code, &stderr) {
+            return Ok(None);
+        }
+
+        Ok(Some(retry_policy::backoff(&policy, attempts)))
+    }
+
+    /// Run the action of a failed execution again as a new, linked execution process
+    async fn retry_execution(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let executor_action = ctx.execution_process.executor_action()?.clone();
+        self.ensure_container_exists(&ctx.task_attempt).await?;
+
+        tracing::info!(
+            "Retrying failed execution process {} for task attempt {}",
+            ctx.execution_process.id,
+            ctx.task_attempt.id
+        );
+        self.start_linked_execution(
+            &ctx.task_attempt,
+            &executor_action,
+            &ctx.execution_process.run_reason,
+            Some(ctx.execution_process.id),
+        )
+        .await
+    }
+
+    /// Raw log messages of an execution, from memory if it is still there or the DB otherwise
+    async fn raw_log_messages(&self, id: &Uuid) -> Vec<LogMsg> {
+        if let Some(store) = self.get_msg_store_by_id(id).await {
+            store.get_history()
+        } else {
+            match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
+                Ok(Some(record)) => record.parse_logs().unwrap_or_default(),
+                Ok(None) => Vec::new(),
+                Err(e) => {
+                    tracing::error!("Failed to fetch logs for execution {}: {}", id, e);
+                    Vec::new()
+                }
+            }
+        }
+    }
+
+    /// All stderr output of an execution
+    async fn collect_stderr(&self, id: &Uuid) -> String {
+        self.raw_log_messages(id)
+            .await
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stderr(content) => Some(content),
+                _ => None,
+            })
+            .collect()
+    }
 
+    /// Stdout and stderr of an execution, interleaved as they were produced
+    async fn collect_output(&self, id: &Uuid) -> String {
+        self.raw_log_messages(id)
+            .await
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(content) | LogMsg::Stderr(content) => Some(content),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Record the test results found in a finished execution: test runs in a
+    /// coding agent's conversation, or the output of a script
+    async fn record_test_runs(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
+        let execution_process = &ctx.execution_process;
+        let runs: Vec<(Option<String>, TestRunSummary)> = match execution_process.run_reason {
+            ExecutionProcessRunReason::CodingAgent => self
+                .normalized_entries(&execution_process.id)
+                .await
+                .into_iter()
+                .filter_map(|entry| match entry.entry_type {
+                    NormalizedEntryType::ToolUse {
+                        action_type: ActionType::TestRun { command, summary },
+                        ..
+                    } => Some((Some(command), summary)),
+                    _ => None,
+                })
+                .collect(),
+            ExecutionProcessRunReason::SetupScript | ExecutionProcessRunReason::CleanupScript => {
+                parse_test_output(&self.collect_output(&execution_process.id).await)
+                    .map(|summary| vec![(None, summary)])
+                    .unwrap_or_default()
+            }
+            ExecutionProcessRunReason::DevServer => return Ok(()),
+        };
+        if runs.is_empty() {
+            return Ok(());
+        }
+        TestRun::replace_for_execution(
+            &self.db().pool,
+            execution_process.id,
+            ctx.task_attempt.id,
+            &runs,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Normalise and persist the logs of a running execution
+    async fn start_log_processing(
+        &self,
+        task_attempt: &TaskAttempt,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) {
         // Start processing normalised logs for executor requests and follow ups
         match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(request) => {
@@ -641,8 +1331,98 @@ pub trait ContainerService {
             _ => {}
         };
 
+        if matches!(
+            executor_action.typ(),
+            ExecutorActionType::CodingAgentInitialRequest(_)
+                | ExecutorActionType::CodingAgentFollowUpRequest(_)
+        ) {
+            self.spawn_stream_normalized_entries_to_db(&execution_process.id);
+        }
         self.spawn_stream_raw_logs_to_db(&execution_process.id);
-        Ok(execution_process)
+    }
+
+    /// Start queued executions in priority order while concurrency limits allow
+    async fn dispatch_queued_executions(&self) -> Result<(), ContainerError> {
+        let _guard = ExecutionQueue::lock().await;
+        let pool = &self.db().pool;
+        let global_limit = self.global_agent_limit().await;
+
+        for entry in ExecutionQueueEntry::find_all(pool).await? {
+            let Some(project) = Project::find_by_id(pool, entry.project_id).await? else {
+                ExecutionQueueEntry::delete_by_execution_process_id(
+                    pool,
+                    entry.execution_process_id,
+                )
+                .await?;
+                continue;
+            };
+
+            if !ExecutionQueue::has_capacity(
+                pool,
+                project.id,
+                project.max_concurrent_agents,
+                global_limit,
+            )
+            .await?
+            {
+                continue;
+            }
+
+            if let Err(e) = self
+                .start_queued_execution(entry.execution_process_id)
+                .await
+            {
+                tracing::error!(
+                    "Failed to start queued execution process {}: {}",
+                    entry.execution_process_id,
+                    e
+                );
+                ExecutionProcess::update_completion(
+                    pool,
+                    entry.execution_process_id,
+                    ExecutionProcessStatus::Failed,
+                    None,
+                )
+                .await?;
+
+                // Hand the task back for review like a failed run, else it stays in progress
+                let ctx = ExecutionProcess::load_context(pool, entry.execution_process_id).await?;
+                Task::update_status(pool, ctx.task.id, TaskStatus::InReview).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move a single queued execution to running and spawn it
+    async fn start_queued_execution(
+        &self,
+        execution_process_id: Uuid,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        ExecutionQueueEntry::delete_by_execution_process_id(pool, execution_process_id).await?;
+
+        let ctx = ExecutionProcess::load_context(pool, execution_process_id).await?;
+        if ctx.execution_process.status != ExecutionProcessStatus::Queued {
+            return Ok(());
+        }
+
+        // The worktree may have been removed while the execution was waiting
+        self.ensure_container_exists(&ctx.task_attempt).await?;
+
+        ExecutionProcess::mark_started(pool, execution_process_id).await?;
+        let execution_process = ExecutionProcess::find_by_id(pool, execution_process_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let executor_action = execution_process.executor_action()?.clone();
+
+        tracing::info!(
+            "Starting queued execution process {} for task attempt {}",
+            execution_process_id,
+            ctx.task_attempt.id
+        );
+        self.launch_execution(&ctx.task_attempt, &execution_process, &executor_action)
+            .await
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -688,5 +1468,44 @@ fn create_user_message(prompt: String) -> NormalizedEntry {
         entry_type: NormalizedEntryType::UserMessage,
         content: prompt,
         metadata: None,
+        parent_id: None,
+    }
+}
+
+/// Wait for the normalizers on a re-normalized store to finish, then read the
+/// conversation with the index of each entry
+async fn finished_normalized_entries(
+    store: &MsgStore,
+    normalizers: Vec<JoinHandle<()>>,
+) -> BTreeMap<usize, NormalizedEntry> {
+    for normalizer in normalizers {
+        if let Err(e) = normalizer.await {
+            tracing::error!("Log normalizer failed: {}", e);
+        }
+    }
+    extract_indexed_normalized_entries(&store.get_history())
+}
+
+async fn flush_normalized_entries(
+    db: &DBService,
+    execution_id: Uuid,
+    pending: &mut BTreeMap<usize, Option<NormalizedEntry>>,
+) {
+    if pending.is_empty() {
+        return;
+    }
+    let changes: Vec<_> = std::mem::take(pending).into_iter().collect();
+    if let Err(e) =
+        ExecutionProcessNormalizedEntry::apply_changes(&db.pool, execution_id, &changes).await
+    {
+        tracing::error!(
+            "Failed to persist normalized entries for execution {}: {}",
+            execution_id,
+            e
+        );
+        // Keep the changes for the next flush, unless newer ones replaced them
+        for (index, entry) in changes {
+            pending.entry(index).or_insert(entry);
+        }
     }
 }
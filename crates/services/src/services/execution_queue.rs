@@ -0,0 +1,53 @@
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use sqlx::SqlitePool;
+use tokio::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+// Global lock so capacity checks and launches from different exit monitors don't race
+lazy_static::lazy_static! {
+    static ref DISPATCH_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Concurrency policy for coding agents and the setup scripts that precede them. Queued executions are persisted in the
+/// `execution_queue` table and dispatched by `ContainerService::dispatch_queued_executions`.
+pub struct ExecutionQueue;
+
+impl ExecutionQueue {
+    /// Hold this guard while deciding which queued executions to launch
+    pub async fn lock() -> MutexGuard<'static, ()> {
+        DISPATCH_LOCK.lock().await
+    }
+
+    /// Coding agents and setup scripts count towards the concurrency limits, setup
+    /// scripts install dependencies and are often as heavy as the agent itself
+    pub fn is_limited(run_reason: &ExecutionProcessRunReason) -> bool {
+        matches!(
+            run_reason,
+            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::SetupScript
+        )
+    }
+
+    /// Whether another limited execution may start in the given project
+    pub async fn has_capacity(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        project_limit: Option<i64>,
+        global_limit: Option<u32>,
+    ) -> Result<bool, sqlx::Error> {
+        if let Some(limit) = global_limit {
+            let running = ExecutionProcess::count_running_limited(pool, None).await?;
+            if running >= limit as i64 {
+                return Ok(false);
+            }
+        }
+
+        if let Some(limit) = project_limit {
+            let running = ExecutionProcess::count_running_limited(pool, Some(project_id)).await?;
+            if running >= limit {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,277 @@
+//! Index of the files an attempt's agents read and changed, built from the
+//! tool actions in their conversations and compared against the final diff.
+
+use std::collections::HashMap;
+
+use executors::logs::{ActionType, FileChange, NormalizedEntry, NormalizedEntryType};
+use serde::Serialize;
+use ts_rs::TS;
+use utils::diff::{Diff, DiffChangeKind};
+use uuid::Uuid;
+
+/// Position of a conversation entry within an attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+pub struct FileActivityEntry {
+    pub execution_process_id: Uuid,
+    pub entry_index: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FileActivity {
+    pub path: String,
+    pub reads: usize,
+    pub edits: usize,
+    /// Added by the final diff, i.e. not in the base branch
+    pub created: bool,
+    /// Deleted by the final diff
+    pub deleted: bool,
+    /// New path, when the agent renamed the file
+    pub renamed_to: Option<String>,
+    /// `None` for files that only show up in the diff, e.g. changed by a
+    /// script or a shell command
+    pub first_entry: Option<FileActivityEntry>,
+    pub last_entry: Option<FileActivityEntry>,
+    pub in_final_diff: bool,
+}
+
+impl FileActivity {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            reads: 0,
+            edits: 0,
+            created: false,
+            deleted: false,
+            renamed_to: None,
+            first_entry: None,
+            last_entry: None,
+            in_final_diff: false,
+        }
+    }
+
+    fn touch(&mut self, entry: FileActivityEntry) {
+        self.first_entry.get_or_insert(entry);
+        self.last_entry = Some(entry);
+    }
+}
+
+#[derive(Default)]
+struct Index {
+    files: Vec<FileActivity>,
+    by_path: HashMap<String, usize>,
+}
+
+impl Index {
+    fn file(&mut self, path: &str) -> &mut FileActivity {
+        let path = normalize_path(path);
+        let i = match self.by_path.get(&path) {
+            Some(i) => *i,
+            None => {
+                self.files.push(FileActivity::new(path.clone()));
+                self.by_path.insert(path, self.files.len() - 1);
+                self.files.len() - 1
+            }
+        };
+        &mut self.files[i]
+    }
+}
+
+/// Files in the order the agents first touched them, followed by the files
+/// only the diff knows about. `processes` holds each execution's normalized
+/// conversation, oldest first.
+pub fn build_file_activity(
+    processes: &[(Uuid, Vec<NormalizedEntry>)],
+    diffs: &[Diff],
+) -> Vec<FileActivity> {
+    let mut index = Index::default();
+
+    for (execution_process_id, entries) in processes {
+        for (entry_index, entry) in entries.iter().enumerate() {
+            let NormalizedEntryType::ToolUse { action_type, .. } = &entry.entry_type else {
+                continue;
+            };
+            let at = FileActivityEntry {
+                execution_process_id: *execution_process_id,
+                entry_index,
+            };
+            match action_type {
+                ActionType::FileRead { path } => {
+                    let file = index.file(path);
+                    file.reads += 1;
+                    file.touch(at);
+                }
+                ActionType::FileEdit { path, changes } => {
+                    let file = index.file(path);
+                    file.edits += 1;
+                    file.touch(at);
+                    let renamed_to = changes.iter().rev().find_map(|change| match change {
+                        FileChange::Rename { new_path } => Some(normalize_path(new_path)),
+                        _ => None,
+                    });
+                    if let Some(new_path) = renamed_to {
+                        file.renamed_to = Some(new_path.clone());
+                        index.file(&new_path).touch(at);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Whether a file was created or deleted depends on the base branch, which
+    // only the diff knows: an agent writing a whole file may be replacing it
+    for diff in diffs {
+        for path in [&diff.new_path, &diff.old_path].into_iter().flatten() {
+            index.file(path).in_final_diff = true;
+        }
+        match (&diff.change, &diff.new_path, &diff.old_path) {
+            (DiffChangeKind::Added, Some(path), _) => index.file(path).created = true,
+            (DiffChangeKind::Deleted, _, Some(path)) => index.file(path).deleted = true,
+            _ => {}
+        }
+    }
+
+    index.files
+}
+
+fn normalize_path(path: &str) -> String {
+    path.trim_start_matches("./").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(action_type: ActionType) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+            },
+            content: String::new(),
+            metadata: None,
+            parent_id: None,
+        }
+    }
+
+    fn read(path: &str) -> NormalizedEntry {
+        tool(ActionType::FileRead {
+            path: path.to_string(),
+        })
+    }
+
+    fn edit(path: &str, changes: Vec<FileChange>) -> NormalizedEntry {
+        tool(ActionType::FileEdit {
+            path: path.to_string(),
+            changes,
+        })
+    }
+
+    fn diff(change: DiffChangeKind, old_path: Option<&str>, new_path: Option<&str>) -> Diff {
+        Diff {
+            change,
+            old_path: old_path.map(str::to_string),
+            new_path: new_path.map(str::to_string),
+            old_content: None,
+            new_content: None,
+        }
+    }
+
+    fn modified(path: &str) -> Diff {
+        diff(DiffChangeKind::Modified, Some(path), Some(path))
+    }
+
+    #[test]
+    fn counts_reads_and_edits_across_processes() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let processes = vec![
+            (
+                first,
+                vec![
+                    read("src/lib.rs"),
+                    edit("./src/lib.rs", vec![]),
+                    read("README.md"),
+                ],
+            ),
+            (second, vec![read("src/lib.rs")]),
+        ];
+        let files = build_file_activity(&processes, &[modified("src/lib.rs")]);
+
+        assert_eq!(files.len(), 2);
+        let lib = &files[0];
+        assert_eq!(lib.path, "src/lib.rs");
+        assert_eq!((lib.reads, lib.edits), (2, 1));
+        assert_eq!(
+            lib.first_entry,
+            Some(FileActivityEntry {
+                execution_process_id: first,
+                entry_index: 0
+            })
+        );
+        assert_eq!(
+            lib.last_entry,
+            Some(FileActivityEntry {
+                execution_process_id: second,
+                entry_index: 0
+            })
+        );
+        assert!(lib.in_final_diff);
+        assert!(!files[1].in_final_diff);
+    }
+
+    #[test]
+    fn tracks_created_deleted_and_renamed_files() {
+        let id = Uuid::new_v4();
+        let processes = vec![(
+            id,
+            vec![
+                edit(
+                    "new.rs",
+                    vec![FileChange::Write {
+                        content: "fn main() {}".to_string(),
+                    }],
+                ),
+                edit("old.rs", vec![FileChange::Delete]),
+                edit(
+                    "a.rs",
+                    vec![FileChange::Rename {
+                        new_path: "b.rs".to_string(),
+                    }],
+                ),
+                // Rewritten in full, but already in the base branch
+                edit(
+                    "existing.rs",
+                    vec![FileChange::Write {
+                        content: "fn main() {}".to_string(),
+                    }],
+                ),
+            ],
+        )];
+        let diffs = vec![
+            diff(DiffChangeKind::Added, None, Some("new.rs")),
+            diff(DiffChangeKind::Deleted, Some("old.rs"), None),
+            diff(DiffChangeKind::Renamed, Some("a.rs"), Some("b.rs")),
+            modified("existing.rs"),
+        ];
+        let files = build_file_activity(&processes, &diffs);
+
+        assert!(files[0].created);
+        assert!(files[1].deleted);
+        assert_eq!(files[2].renamed_to.as_deref(), Some("b.rs"));
+        assert_eq!(files[3].path, "b.rs");
+        assert!(!files[3].created);
+        assert_eq!(files[4].path, "existing.rs");
+        assert!(!files[4].created);
+        assert!(files.iter().all(|file| file.in_final_diff));
+    }
+
+    #[test]
+    fn lists_files_only_in_the_diff() {
+        let files = build_file_activity(&[], &[modified("Cargo.lock")]);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].in_final_diff);
+        assert!(files[0].first_entry.is_none());
+    }
+}
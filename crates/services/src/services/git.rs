@@ -1,12 +1,22 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use db::models::{merge::MergeStrategy, project_commit_settings::CommitSigningFormat};
 use git2::{
-    BranchType, CherrypickOptions, Delta, DiffFindOptions, DiffOptions, Error as GitError,
-    FetchOptions, Repository, Sort, build::CheckoutBuilder,
+    BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, FetchOptions, Repository,
+    Sort, build::CheckoutBuilder,
 };
+use once_cell::sync::Lazy;
 use regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
 use utils::diff::{Diff, DiffChangeKind, FileDiffDetails};
@@ -27,6 +37,18 @@ pub enum GitServiceError {
     BranchNotFound(String),
     #[error("Merge conflicts: {0}")]
     MergeConflicts(String),
+    #[error("Cannot fast-forward {0}, rebase the task branch first")]
+    NotFastForward(String),
+    #[error("Rebase stopped on conflicts in {0}")]
+    RebaseConflicts(String),
+    #[error("A rebase is in progress, continue or abort it first")]
+    RebaseInProgress,
+    #[error("No rebase in progress")]
+    NoRebaseInProgress,
+    #[error("Branch already exists: {0}")]
+    BranchExists(String),
+    #[error("Checkpoint {0} is no longer on the task branch")]
+    CheckpointNotOnBranch(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
     #[error("{0} has uncommitted changes: {1}")]
@@ -35,6 +57,27 @@ pub enum GitServiceError {
     InvalidFilePaths(String),
     #[error("No GitHub token available.")]
     TokenUnavailable,
+    #[error("Failed to sign commit: {0}")]
+    SigningFailed(String),
+}
+
+/// Signers still running after this long are stopped, they are most likely
+/// waiting for a passphrase or a security key nobody will provide
+const SIGNING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Merge previews by repository and task branch, valid while neither the task
+/// branch nor its base moves
+static MERGE_PREVIEW_CACHE: Lazy<DashMap<(PathBuf, String), CachedMergePreview>> =
+    Lazy::new(DashMap::new);
+
+/// Previews kept before the least recently used are evicted
+const MERGE_PREVIEW_CACHE_CAPACITY: usize = 256;
+
+struct CachedMergePreview {
+    base_oid: git2::Oid,
+    task_oid: git2::Oid,
+    preview: MergePreview,
+    last_used: Instant,
 }
 
 /// Service for managing Git operations in task execution workflows
@@ -56,6 +99,131 @@ pub struct HeadInfo {
     pub oid: String,
 }
 
+/// Outcome of merging a task branch into its base branch in memory
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MergePreview {
+    /// Whether the task branch merges into its base branch without conflicts
+    pub clean: bool,
+    pub conflicted_files: Vec<String>,
+    /// Base branch commit the merge was simulated against
+    pub base_commit: String,
+}
+
+/// A file left conflicted by a stopped rebase. While rebasing, "ours" is the new
+/// base and "theirs" is the task branch commit being replayed. Sides are `None`
+/// when they deleted the file or it's binary, the flags tell which.
+#[derive(Debug, Serialize, TS)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub ours_deleted: bool,
+    pub theirs_deleted: bool,
+    pub ours_binary: bool,
+    pub theirs_binary: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RebaseConflicts {
+    /// The task branch commit that didn't apply cleanly
+    pub commit: Option<String>,
+    pub commit_summary: Option<String>,
+    /// Empty once every file is resolved and the rebase can continue
+    pub files: Vec<ConflictedFile>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Manual { content: String },
+}
+
+/// How a direct merge is made, see [`GitService::merge_changes`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectMergeOptions {
+    pub strategy: MergeStrategy,
+    /// Update the files of the main repository when it has the base branch
+    /// checked out. Local edits are kept, the update fails if they conflict.
+    pub update_checkout: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectMergeOutcome {
+    pub merge_commit: String,
+    /// Base branch tip before the merge
+    pub base_commit: String,
+    /// Set when the main repository has the base branch checked out but its
+    /// files weren't updated to the merge
+    pub checkout_warning: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RevertOutcome {
+    pub revert_commit: String,
+    /// Set when the main repository has the branch checked out but its files
+    /// weren't updated to the revert
+    pub checkout_warning: Option<String>,
+}
+
+/// Identity, trailers and signing for the commits made for an attempt. The
+/// default commits with the git config identity, unsigned and without trailers.
+#[derive(Debug, Clone, Default)]
+pub struct CommitOptions {
+    /// Name and email used as author and committer
+    pub identity: Option<(String, String)>,
+    /// Credit the git config identity with a `Co-authored-by` trailer when it
+    /// isn't the one committing
+    pub co_authored_by: bool,
+    /// `Key: value` lines added to new commit messages
+    pub trailers: Vec<(String, String)>,
+    pub signing: Option<CommitSigner>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitSigner {
+    pub format: CommitSigningFormat,
+    /// SSH key path or GPG key id, git config `user.signingkey` when unset
+    pub key: Option<String>,
+}
+
+/// A file of the attempt diff to accept. All of its changes are taken unless
+/// `hunks` picks some of them by index, as listed by
+/// [`GitService::get_selectable_hunks`].
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct FileSelection {
+    pub path: String,
+    #[serde(default)]
+    pub hunks: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct FileHunks {
+    pub path: String,
+    /// Empty for binary files, which can only be taken whole
+    pub hunks: Vec<HunkInfo>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct HunkInfo {
+    pub header: String,
+    /// The hunk's lines, prefixed with ' ', '+' or '-'
+    pub content: String,
+}
+
+/// Commit created from a selection of an attempt's changes
+#[derive(Debug, Serialize, TS)]
+pub struct SelectionCommit {
+    pub commit: String,
+    /// Files and hunks of the attempt diff that were left out
+    pub excluded: Vec<String>,
+    /// Set when the target branch is checked out in the main repository, whose
+    /// files weren't updated
+    pub checkout_warning: Option<String>,
+}
+
 /// Target for diff generation
 pub enum DiffTarget<'p> {
     /// Work-in-progress branch checked out in this worktree
@@ -159,9 +327,21 @@ impl GitService {
         Ok(())
     }
 
-    pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+    pub fn commit(
+        &self,
+        path: &Path,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<bool, GitServiceError> {
         let repo = Repository::open(path)?;
 
+        // Changes made while a rebase is stopped resolve its conflicts, they're
+        // committed when the rebase continues
+        if Self::rebase_in_progress(&repo) {
+            tracing::debug!("Rebase in progress, leaving changes uncommitted");
+            return Ok(false);
+        }
+
         // Check if there are any changes to commit
         let status = repo.statuses(None)?;
 
@@ -192,19 +372,242 @@ impl GitService {
         let tree_id = index.write_tree()?;
         let tree = repo.find_tree(tree_id)?;
 
-        let signature = repo.signature()?;
-        repo.commit(
-            Some("HEAD"),
+        let signature = Self::commit_signature(&repo, options)?;
+        let message = Self::message_with_trailers(&repo, message, options);
+        let commit_id = Self::create_commit(
+            &repo,
             &signature,
             &signature,
-            message,
+            &message,
             &tree,
             &[&parent_commit],
+            options,
         )?;
+        head.resolve()?.set_target(commit_id, "commit")?;
 
         Ok(true)
     }
 
+    /// Author and committer of commits made with `options`
+    fn commit_signature(
+        repo: &Repository,
+        options: &CommitOptions,
+    ) -> Result<git2::Signature<'static>, GitServiceError> {
+        match &options.identity {
+            Some((name, email)) => Ok(git2::Signature::now(name, email)?),
+            None => Ok(repo.signature()?),
+        }
+    }
+
+    /// `message` followed by the trailers of `options`
+    fn message_with_trailers(repo: &Repository, message: &str, options: &CommitOptions) -> String {
+        let mut trailers: Vec<String> = options
+            .trailers
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .collect();
+        if options.co_authored_by
+            && let Some((_, email)) = &options.identity
+            && let Ok(user) = repo.signature()
+            && user.email() != Some(email.as_str())
+        {
+            trailers.push(format!(
+                "Co-authored-by: {} <{}>",
+                user.name().unwrap_or_default(),
+                user.email().unwrap_or_default()
+            ));
+        }
+
+        if trailers.is_empty() {
+            return message.to_string();
+        }
+        format!("{}\n\n{}\n", message.trim_end(), trailers.join("\n"))
+    }
+
+    /// Create a commit without moving any reference, signed when `options` asks
+    /// for it
+    fn create_commit(
+        repo: &Repository,
+        author: &git2::Signature,
+        committer: &git2::Signature,
+        message: &str,
+        tree: &git2::Tree,
+        parents: &[&git2::Commit],
+        options: &CommitOptions,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let Some(signer) = &options.signing else {
+            return Ok(repo.commit(None, author, committer, message, tree, parents)?);
+        };
+        let buffer = repo.commit_create_buffer(author, committer, message, tree, parents)?;
+        let content = buffer.as_str().ok_or_else(|| {
+            GitServiceError::SigningFailed("commit content isn't valid UTF-8".to_string())
+        })?;
+        let signature = Self::sign_commit_content(repo, content, signer)?;
+        Ok(repo.commit_signed(content, &signature, None)?)
+    }
+
+    /// Sign commit content like git does, with `gpg` or `ssh-keygen` or the
+    /// programs set by git config `gpg.program` and `gpg.ssh.program`
+    fn sign_commit_content(
+        repo: &Repository,
+        content: &str,
+        signer: &CommitSigner,
+    ) -> Result<String, GitServiceError> {
+        let config = repo.config()?;
+        let key = signer
+            .key
+            .clone()
+            .or_else(|| config.get_string("user.signingkey").ok());
+
+        let (program, args) = match signer.format {
+            CommitSigningFormat::Gpg => {
+                // Fail instead of prompting for a passphrase nobody can enter
+                let mut args: Vec<String> = [
+                    "--batch",
+                    "--pinentry-mode",
+                    "loopback",
+                    "--status-fd=2",
+                    "-bsa",
+                ]
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+                if let Some(key) = key {
+                    args.extend(["-u".to_string(), key]);
+                }
+                (
+                    config
+                        .get_string("gpg.program")
+                        .unwrap_or_else(|_| "gpg".to_string()),
+                    args,
+                )
+            }
+            CommitSigningFormat::Ssh => {
+                let key = key.ok_or_else(|| {
+                    GitServiceError::SigningFailed("no SSH signing key is set".to_string())
+                })?;
+                (
+                    config
+                        .get_string("gpg.ssh.program")
+                        .unwrap_or_else(|_| "ssh-keygen".to_string()),
+                    ["-Y", "sign", "-n", "git", "-f"]
+                        .into_iter()
+                        .map(str::to_string)
+                        .chain([key])
+                        .collect(),
+                )
+            }
+        };
+
+        // The signer blocks this thread, possibly for a while, so the runtime is
+        // told to move its other tasks off it
+        let sign = || Self::run_signer(&program, &args, content);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(sign)
+            }
+            _ => sign(),
+        }
+    }
+
+    /// Run a signing program on `content`, stopping it after [`SIGNING_TIMEOUT`]
+    fn run_signer(
+        program: &str,
+        args: &[String],
+        content: &str,
+    ) -> Result<String, GitServiceError> {
+        let failed =
+            |detail: String| GitServiceError::SigningFailed(format!("{program}: {detail}"));
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| failed(e.to_string()))?;
+
+        // Fed and drained on their own threads, so a signer that stops reading or
+        // fills a pipe can't block past the timeout
+        let stdin = child.stdin.take();
+        let content = content.to_string();
+        let writer = std::thread::spawn(move || {
+            if let Some(mut stdin) = stdin {
+                let _ = stdin.write_all(content.as_bytes());
+            }
+        });
+        let stdout = drain_pipe(child.stdout.take());
+        let stderr = drain_pipe(child.stderr.take());
+
+        let deadline = Instant::now() + SIGNING_TIMEOUT;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(failed(format!(
+                    "no signature after {}s, it may be waiting for a passphrase",
+                    SIGNING_TIMEOUT.as_secs()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        };
+        let _ = writer.join();
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+
+        let signature = String::from_utf8_lossy(&stdout).to_string();
+        if !status.success() || signature.trim().is_empty() {
+            return Err(failed(String::from_utf8_lossy(&stderr).trim().to_string()));
+        }
+        Ok(signature)
+    }
+
+    /// Sign the commits HEAD gained since `since`, rewriting them with the same
+    /// content. libgit2's rebase can't sign the commits it creates.
+    fn sign_commits_since(
+        repo: &Repository,
+        since: git2::Oid,
+        options: &CommitOptions,
+    ) -> Result<(), GitServiceError> {
+        if options.signing.is_none() {
+            return Ok(());
+        }
+
+        let mut head = repo.head()?.resolve()?;
+        let head_id = head.peel_to_commit()?.id();
+        let mut walker = repo.revwalk()?;
+        walker.push(head_id)?;
+        walker.hide(since)?;
+        walker.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+        let mut rewritten: HashMap<git2::Oid, git2::Oid> = HashMap::new();
+        for commit_id in walker {
+            let commit = repo.find_commit(commit_id?)?;
+            let parents = commit
+                .parent_ids()
+                .map(|id| repo.find_commit(*rewritten.get(&id).unwrap_or(&id)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let parents: Vec<&git2::Commit> = parents.iter().collect();
+            let signed_id = Self::create_commit(
+                repo,
+                &commit.author(),
+                &commit.committer(),
+                &String::from_utf8_lossy(commit.message_bytes()),
+                &commit.tree()?,
+                &parents,
+                options,
+            )?;
+            rewritten.insert(commit.id(), signed_id);
+        }
+
+        if let Some(signed_head) = rewritten.get(&head_id) {
+            head.set_target(*signed_head, "Sign rebased commits")?;
+        }
+        Ok(())
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
@@ -509,7 +912,10 @@ impl GitService {
         }
     }
 
-    /// Merge changes from a worktree branch back to the main repository
+    /// Merge changes from a worktree branch back to the main repository. The merge
+    /// is built in the object database and only moves `refs/heads/<base>`, the
+    /// main repository's files are left alone unless `update_checkout` is set.
+    #[allow(clippy::too_many_arguments)]
     pub fn merge_changes(
         &self,
         repo_path: &Path,
@@ -517,14 +923,18 @@ impl GitService {
         branch_name: &str,
         base_branch_name: &str,
         commit_message: &str,
-    ) -> Result<String, GitServiceError> {
+        options: DirectMergeOptions,
+        commit_options: &CommitOptions,
+    ) -> Result<DirectMergeOutcome, GitServiceError> {
         // Open the worktree repository
         let worktree_repo = self.open_repo(worktree_path)?;
         let main_repo = self.open_repo(repo_path)?;
 
         // Check if worktree is dirty before proceeding
+        if Self::rebase_in_progress(&worktree_repo) {
+            return Err(GitServiceError::RebaseInProgress);
+        }
         self.check_worktree_clean(&worktree_repo)?;
-        self.check_worktree_clean(&main_repo)?;
 
         // Verify the task branch exists in the worktree
         let task_branch = worktree_repo
@@ -540,44 +950,554 @@ impl GitService {
         let base_commit = base_branch.get().peel_to_commit()?;
         let task_commit = task_branch.get().peel_to_commit()?;
 
-        // Get the signature for the merge commit
-        let signature = worktree_repo.signature()?;
-
-        // Perform a squash merge - create a single commit with all changes
-        let squash_commit_id = self.perform_squash_merge(
-            &worktree_repo,
-            &base_commit,
-            &task_commit,
-            &signature,
-            commit_message,
-            base_branch_name,
-        )?;
+        let merged_commit_id = match options.strategy {
+            // Create a single commit with all changes
+            MergeStrategy::Squash => self.perform_squash_merge(
+                &worktree_repo,
+                &base_commit,
+                &task_commit,
+                commit_message,
+                base_branch_name,
+                commit_options,
+            )?,
+            MergeStrategy::MergeCommit => self.perform_merge_commit(
+                &worktree_repo,
+                &base_commit,
+                &task_commit,
+                commit_message,
+                base_branch_name,
+                commit_options,
+            )?,
+            MergeStrategy::Rebase => self.perform_rebase_merge(
+                &worktree_repo,
+                &base_commit,
+                &task_commit,
+                base_branch_name,
+                commit_options,
+            )?,
+            MergeStrategy::FastForward => self.perform_fast_forward(
+                &worktree_repo,
+                &base_commit,
+                &task_commit,
+                base_branch_name,
+            )?,
+        };
 
-        // Reset the task branch to point to the squash commit
+        // Reset the task branch to point to the merged commit
         // This allows follow-up work to continue from the merged state without conflicts
         let task_refname = format!("refs/heads/{branch_name}");
         main_repo.reference(
             &task_refname,
-            squash_commit_id,
+            merged_commit_id,
             true,
             "Reset task branch after merge in main repo",
         )?;
 
-        // Fix: Update main repo's HEAD if it's pointing to the base branch
-        let refname = format!("refs/heads/{base_branch_name}");
+        let checkout_warning = Self::sync_checkout(
+            &main_repo,
+            base_branch_name,
+            base_commit.id(),
+            options.update_checkout,
+        )?;
+
+        Ok(DirectMergeOutcome {
+            merge_commit: merged_commit_id.to_string(),
+            base_commit: base_commit.id().to_string(),
+            checkout_warning,
+        })
+    }
+
+    /// Hunks of the attempt's changes since it branched off its base, per file,
+    /// for picking what to accept with [`Self::commit_selection`]
+    pub fn get_selectable_hunks(
+        &self,
+        source: DiffTarget,
+    ) -> Result<Vec<FileHunks>, GitServiceError> {
+        let (repo, base_branch) = self.open_selection_source(&source)?;
+        let base_commit = Self::find_local_commit(&repo, base_branch)?;
+        let diff = Self::selection_diff(&repo, &source, &base_commit)?;
+
+        let mut files = Vec::with_capacity(diff.deltas().len());
+        for idx in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(&diff, idx)? else {
+                continue;
+            };
+            let Some(path) = Self::delta_path(&patch.delta()) else {
+                continue;
+            };
+            let mut hunks = Vec::with_capacity(patch.num_hunks());
+            for hunk_idx in 0..patch.num_hunks() {
+                let (hunk, line_count) = patch.hunk(hunk_idx)?;
+                let mut content = String::new();
+                for line_idx in 0..line_count {
+                    let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                    if matches!(line.origin(), ' ' | '+' | '-') {
+                        content.push(line.origin());
+                    }
+                    content.push_str(&String::from_utf8_lossy(line.content()));
+                }
+                hunks.push(HunkInfo {
+                    header: String::from_utf8_lossy(hunk.header())
+                        .trim_end()
+                        .to_string(),
+                    content,
+                });
+            }
+            files.push(FileHunks { path, hunks });
+        }
+        Ok(files)
+    }
+
+    /// Commit only the selected files and hunks of an attempt's changes on top of
+    /// its base branch, or on a new branch created from the base when
+    /// `target_branch` is given. What was left out is listed in the commit message.
+    /// The task branch is left as it is.
+    pub fn commit_selection(
+        &self,
+        repo_path: &Path,
+        source: DiffTarget,
+        selection: &[FileSelection],
+        target_branch: Option<&str>,
+        commit_message: &str,
+        commit_options: &CommitOptions,
+    ) -> Result<SelectionCommit, GitServiceError> {
+        let (repo, base_branch) = self.open_selection_source(&source)?;
+        if Self::rebase_in_progress(&repo) {
+            return Err(GitServiceError::RebaseInProgress);
+        }
+        let main_repo = self.open_repo(repo_path)?;
+        if let Some(branch) = target_branch
+            && repo.find_branch(branch, BranchType::Local).is_ok()
+        {
+            return Err(GitServiceError::BranchExists(branch.to_string()));
+        }
+
+        let base_commit = Self::find_local_commit(&repo, base_branch)?;
+        let diff = Self::selection_diff(&repo, &source, &base_commit)?;
+
+        let selected: HashMap<&str, Option<&[usize]>> = selection
+            .iter()
+            .map(|file| (file.path.as_str(), file.hunks.as_deref()))
+            .collect();
+        let (excluded, taken) = Self::excluded_changes(&diff, &selected)?;
+        if taken.is_empty() {
+            return Err(GitServiceError::InvalidFilePaths(
+                "nothing from the attempt diff is selected".to_string(),
+            ));
+        }
+
+        // Apply the diff to the base, skipping whatever wasn't picked. Hunks are
+        // numbered per file in the order they're visited.
+        let current: std::cell::RefCell<(Option<Option<&[usize]>>, usize)> =
+            std::cell::RefCell::new((None, 0));
+        let mut apply_opts = git2::ApplyOptions::new();
+        apply_opts.delta_callback(|delta| {
+            let choice = delta
+                .and_then(|delta| Self::delta_path(&delta))
+                .filter(|path| taken.contains(path))
+                .and_then(|path| selected.get(path.as_str()).copied());
+            *current.borrow_mut() = (choice, 0);
+            choice.is_some()
+        });
+        apply_opts.hunk_callback(|_| {
+            let mut current = current.borrow_mut();
+            let idx = current.1;
+            current.1 += 1;
+            match current.0 {
+                Some(Some(hunks)) => hunks.contains(&idx),
+                Some(None) => true,
+                None => false,
+            }
+        });
+        let mut index = repo
+            .apply_to_tree(&base_commit.tree()?, &diff, Some(&mut apply_opts))
+            .map_err(|e| {
+                GitServiceError::MergeConflicts(format!(
+                    "The selected changes don't apply to {base_branch}: {}",
+                    e.message()
+                ))
+            })?;
+        let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+
+        let target = target_branch.unwrap_or(base_branch);
+        let mut message = commit_message.to_string();
+        if !excluded.is_empty() {
+            message.push_str("\n\nLeft out of this commit:\n");
+            for change in &excluded {
+                message.push_str(&format!("- {change}\n"));
+            }
+        }
+        let message = Self::message_with_trailers(&repo, &message, commit_options);
+        let signature = Self::commit_signature(&repo, commit_options)?;
+        let commit_id = Self::create_commit(
+            &repo,
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &[&base_commit],
+            commit_options,
+        )?;
+        repo.reference(
+            &format!("refs/heads/{target}"),
+            commit_id,
+            target_branch.is_none(),
+            "Commit selected changes",
+        )?;
+        let checkout_warning = Self::sync_checkout(&main_repo, target, base_commit.id(), false)?;
+
+        Ok(SelectionCommit {
+            commit: commit_id.to_string(),
+            excluded,
+            checkout_warning,
+        })
+    }
+
+    fn open_selection_source<'t>(
+        &self,
+        source: &DiffTarget<'t>,
+    ) -> Result<(Repository, &'t str), GitServiceError> {
+        match *source {
+            DiffTarget::Worktree {
+                worktree_path,
+                base_branch,
+                ..
+            } => Ok((Repository::open(worktree_path)?, base_branch)),
+            DiffTarget::Branch {
+                repo_path,
+                base_branch,
+                ..
+            } => Ok((self.open_repo(repo_path)?, base_branch)),
+            DiffTarget::Commit { .. } => Err(GitServiceError::InvalidRepository(
+                "Changes can only be selected from a task branch or worktree".to_string(),
+            )),
+        }
+    }
+
+    fn find_local_commit<'r>(
+        repo: &'r Repository,
+        branch_name: &str,
+    ) -> Result<git2::Commit<'r>, GitServiceError> {
+        Ok(repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?
+            .get()
+            .peel_to_commit()?)
+    }
+
+    /// The attempt's own changes: from where it branched off the base to the
+    /// branch tip, or to the worktree including uncommitted changes
+    fn selection_diff<'r>(
+        repo: &'r Repository,
+        source: &DiffTarget,
+        base_commit: &git2::Commit,
+    ) -> Result<git2::Diff<'r>, GitServiceError> {
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.include_typechange(true).show_binary(true);
+
+        match *source {
+            DiffTarget::Worktree { branch_name, .. } => {
+                let head = Self::find_local_commit(repo, branch_name)?;
+                let merge_base = Self::get_merge_base(repo, head.id(), base_commit.id())?;
+                let base_tree = repo.find_commit(merge_base)?.tree()?;
+                diff_opts
+                    .include_untracked(true)
+                    .recurse_untracked_dirs(true)
+                    .show_untracked_content(true);
+                Ok(repo.diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut diff_opts))?)
+            }
+            DiffTarget::Branch { branch_name, .. } => {
+                let head = Self::find_local_commit(repo, branch_name)?;
+                let merge_base = Self::get_merge_base(repo, head.id(), base_commit.id())?;
+                let base_tree = repo.find_commit(merge_base)?.tree()?;
+                Ok(repo.diff_tree_to_tree(
+                    Some(&base_tree),
+                    Some(&head.tree()?),
+                    Some(&mut diff_opts),
+                )?)
+            }
+            DiffTarget::Commit { .. } => Err(GitServiceError::InvalidRepository(
+                "Changes can only be selected from a task branch or worktree".to_string(),
+            )),
+        }
+    }
+
+    fn delta_path(delta: &git2::DiffDelta) -> Option<String> {
+        delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| path.to_string_lossy().to_string())
+    }
+
+    /// Describe what a selection leaves out, whole files by path and partially
+    /// taken files with the number of hunks left out. Also returns the files that
+    /// are at least partially taken.
+    fn excluded_changes(
+        diff: &git2::Diff,
+        selected: &HashMap<&str, Option<&[usize]>>,
+    ) -> Result<(Vec<String>, HashSet<String>), GitServiceError> {
+        let mut excluded = Vec::new();
+        let mut taken = HashSet::new();
+        for (idx, delta) in diff.deltas().enumerate() {
+            let Some(path) = Self::delta_path(&delta) else {
+                continue;
+            };
+            match selected.get(path.as_str()) {
+                None => excluded.push(path),
+                Some(Some([])) => excluded.push(path),
+                Some(None) => {
+                    taken.insert(path);
+                }
+                Some(Some(hunks)) => {
+                    let total = git2::Patch::from_diff(diff, idx)?
+                        .map(|patch| patch.num_hunks())
+                        .unwrap_or(0);
+                    // Binary files have no hunks and are taken whole
+                    let taken_hunks = (0..total).filter(|i| hunks.contains(i)).count();
+                    if total > 0 && taken_hunks == 0 {
+                        excluded.push(path);
+                        continue;
+                    }
+                    if taken_hunks < total {
+                        excluded.push(format!("{path} ({} of {total} hunks)", total - taken_hunks));
+                    }
+                    taken.insert(path);
+                }
+            }
+        }
+        Ok((excluded, taken))
+    }
+
+    /// Deal with the main repository's checkout after `branch_name` moved from
+    /// `old_tip`. When the branch is checked out its files still match the old
+    /// tip, they're updated if asked to, keeping local edits. Returns a warning
+    /// when the checkout is left behind.
+    fn sync_checkout(
+        repo: &Repository,
+        branch_name: &str,
+        old_tip: git2::Oid,
+        update: bool,
+    ) -> Result<Option<String>, GitServiceError> {
+        let on_branch = repo
+            .head()
+            .is_ok_and(|head| head.shorthand() == Some(branch_name));
+        if !on_branch {
+            return Ok(None);
+        }
+        let checkout_path = repo
+            .workdir()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+
+        if !update {
+            return Ok(Some(format!(
+                "{branch_name} is checked out in {checkout_path} and its files weren't updated, the checkout is now behind {branch_name}"
+            )));
+        }
 
-        if let Ok(main_head) = main_repo.head()
-            && let Some(branch_name) = main_head.shorthand()
-            && branch_name == base_branch_name
+        // Only files that match the old tip are rewritten, anything edited
+        // locally that the merge also changed stops the update. A safe checkout
+        // compares against HEAD, so HEAD points at the old tip meanwhile.
+        let new_tree = repo.head()?.peel_to_tree()?;
+        repo.set_head_detached(old_tip)?;
+        let mut co = CheckoutBuilder::new();
+        co.safe();
+        let result = repo.checkout_tree(new_tree.as_object(), Some(&mut co));
+        repo.set_head(&format!("refs/heads/{branch_name}"))?;
+        match result {
+            Ok(()) => Ok(None),
+            Err(e) => {
+                tracing::warn!("Failed to update checkout of {branch_name}: {e}");
+                Ok(Some(format!(
+                    "Couldn't update {branch_name} in {checkout_path} without overwriting local changes ({}), the checkout is now behind {branch_name}",
+                    e.message()
+                )))
+            }
+        }
+    }
+
+    /// Revert a commit on top of a local branch, moving the branch to the revert
+    /// commit without touching the main repository's files. With `base_commit`
+    /// everything from it up to the commit is reverted in one commit, for merges
+    /// that added several commits; otherwise merge commits are reverted against
+    /// their first parent.
+    pub fn revert_commit(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        commit_sha: &str,
+        base_commit: Option<&str>,
+        message: &str,
+        commit_options: &CommitOptions,
+    ) -> Result<RevertOutcome, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let onto = Self::find_local_commit(&repo, branch_name)?;
+        let revert_id = Self::revert_onto(
+            &repo,
+            commit_sha,
+            base_commit,
+            &onto,
+            message,
+            commit_options,
+        )?;
+        repo.reference(
+            &format!("refs/heads/{branch_name}"),
+            revert_id,
+            true,
+            "Revert merged attempt",
+        )?;
+        let checkout_warning = Self::sync_checkout(&repo, branch_name, onto.id(), false)?;
+        Ok(RevertOutcome {
+            revert_commit: revert_id.to_string(),
+            checkout_warning,
+        })
+    }
+
+    /// Revert a commit on a new branch created from the remote's tip of
+    /// `base_branch`, fetching first so the commit and the base are up to date
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_revert_branch(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        commit_sha: &str,
+        revert_branch: &str,
+        message: &str,
+        github_token: &str,
+        commit_options: &CommitOptions,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if repo.find_branch(revert_branch, BranchType::Local).is_ok() {
+            return Err(GitServiceError::BranchExists(revert_branch.to_string()));
+        }
+        self.fetch_from_remote(&repo, github_token)?;
+
+        let remote_base = format!("origin/{base_branch}");
+        let onto = repo
+            .find_branch(&remote_base, BranchType::Remote)
+            .map_err(|_| GitServiceError::BranchNotFound(remote_base.clone()))?
+            .get()
+            .peel_to_commit()?;
+        let revert_id = Self::revert_onto(&repo, commit_sha, None, &onto, message, commit_options)?;
+        repo.branch(revert_branch, &repo.find_commit(revert_id)?, false)?;
+        Ok(revert_id.to_string())
+    }
+
+    fn revert_onto(
+        repo: &Repository,
+        commit_sha: &str,
+        base_commit: Option<&str>,
+        onto: &git2::Commit,
+        message: &str,
+        commit_options: &CommitOptions,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let commit = repo.find_commit(git2::Oid::from_str(commit_sha)?)?;
+        let mut index = match base_commit {
+            // Undo the changes from the base to the commit: merge the base's tree
+            // into `onto` with the commit as the common ancestor
+            Some(base_commit) => {
+                let base = repo.find_commit(git2::Oid::from_str(base_commit)?)?;
+                repo.merge_trees(&commit.tree()?, &onto.tree()?, &base.tree()?, None)?
+            }
+            None => {
+                let mainline = if commit.parent_count() > 1 { 1 } else { 0 };
+                repo.revert_commit(&commit, onto, mainline, None)?
+            }
+        };
+        if index.has_conflicts() {
+            let paths: Vec<String> = index
+                .conflicts()?
+                .filter_map(Result::ok)
+                .filter_map(|conflict| conflict_path(&conflict))
+                .collect();
+            return Err(GitServiceError::MergeConflicts(format!(
+                "Reverting {} conflicts with later changes in {}",
+                &commit_sha[..commit_sha.len().min(7)],
+                paths.join(", ")
+            )));
+        }
+
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        let signature = Self::commit_signature(repo, commit_options)?;
+        let message = Self::message_with_trailers(repo, message, commit_options);
+        Self::create_commit(
+            repo,
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &[onto],
+            commit_options,
+        )
+    }
+
+    /// Simulate merging the task branch into its base branch without touching any
+    /// worktree. Results are reused until either branch moves.
+    pub fn preview_merge(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<MergePreview, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let task_commit = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?
+            .get()
+            .peel_to_commit()?;
+        let base_commit = repo
+            .find_branch(base_branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(base_branch_name.to_string()))?
+            .get()
+            .peel_to_commit()?;
+
+        let key = (repo_path.to_path_buf(), branch_name.to_string());
+        if let Some(mut cached) = MERGE_PREVIEW_CACHE.get_mut(&key)
+            && cached.base_oid == base_commit.id()
+            && cached.task_oid == task_commit.id()
         {
-            // Only update main repo's HEAD if it's currently on the base branch
-            main_repo.set_head(&refname)?;
-            let mut co = CheckoutBuilder::new();
-            co.force();
-            main_repo.checkout_head(Some(&mut co))?;
+            cached.last_used = Instant::now();
+            return Ok(cached.preview.clone());
         }
 
-        Ok(squash_commit_id.to_string())
+        let index = repo.merge_commits(&base_commit, &task_commit, None)?;
+        let conflicted_files: Vec<String> = if index.has_conflicts() {
+            index
+                .conflicts()?
+                .filter_map(Result::ok)
+                .filter_map(|conflict| conflict_path(&conflict))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let preview = MergePreview {
+            clean: conflicted_files.is_empty(),
+            conflicted_files,
+            base_commit: base_commit.id().to_string(),
+        };
+
+        if MERGE_PREVIEW_CACHE.len() >= MERGE_PREVIEW_CACHE_CAPACITY
+            && !MERGE_PREVIEW_CACHE.contains_key(&key)
+        {
+            let least_recent = MERGE_PREVIEW_CACHE
+                .iter()
+                .min_by_key(|entry| entry.last_used)
+                .map(|entry| entry.key().clone());
+            if let Some(least_recent) = least_recent {
+                MERGE_PREVIEW_CACHE.remove(&least_recent);
+            }
+        }
+        MERGE_PREVIEW_CACHE.insert(
+            key,
+            CachedMergePreview {
+                base_oid: base_commit.id(),
+                task_oid: task_commit.id(),
+                preview: preview.clone(),
+                last_used: Instant::now(),
+            },
+        );
+        Ok(preview)
     }
 
     pub fn get_local_branch_status(
@@ -786,33 +1706,23 @@ impl GitService {
         repo: &Repository,
         base_commit: &git2::Commit,
         task_commit: &git2::Commit,
-        signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        options: &CommitOptions,
     ) -> Result<git2::Oid, GitServiceError> {
-        // Attempt an in-memory merge to detect conflicts
-        let merge_opts = git2::MergeOptions::new();
-        let mut index = repo.merge_commits(base_commit, task_commit, Some(&merge_opts))?;
-
-        // If there are conflicts, return an error
-        if index.has_conflicts() {
-            return Err(GitServiceError::MergeConflicts(
-                "Merge failed due to conflicts. Please resolve conflicts manually.".to_string(),
-            ));
-        }
-
-        // Write the merged tree back to the repository
-        let tree_id = index.write_tree_to(repo)?;
-        let tree = repo.find_tree(tree_id)?;
+        let tree = Self::merged_tree(repo, base_commit, task_commit)?;
+        let signature = Self::commit_signature(repo, options)?;
+        let message = Self::message_with_trailers(repo, commit_message, options);
 
         // Create a squash commit: use merged tree with base_commit as sole parent
-        let squash_commit_id = repo.commit(
-            None,           // Don't update any reference yet
-            signature,      // Author
-            signature,      // Committer
-            commit_message, // Custom message
+        let squash_commit_id = Self::create_commit(
+            repo,
+            &signature,     // Author
+            &signature,     // Committer
+            &message,       // Custom message
             &tree,          // Merged tree content
             &[base_commit], // Single parent: base branch commit
+            options,
         )?;
 
         // Update the base branch reference to point to the new commit
@@ -822,29 +1732,161 @@ impl GitService {
         Ok(squash_commit_id)
     }
 
-    /// Rebase a worktree branch onto a new base
+    /// Merge the task branch into the base branch with a merge commit, keeping the
+    /// branch's history, but fail on conflicts
+    fn perform_merge_commit(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        commit_message: &str,
+        base_branch_name: &str,
+        options: &CommitOptions,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let tree = Self::merged_tree(repo, base_commit, task_commit)?;
+        let signature = Self::commit_signature(repo, options)?;
+        let message = Self::message_with_trailers(repo, commit_message, options);
+
+        let merge_commit_id = Self::create_commit(
+            repo,
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &[base_commit, task_commit],
+            options,
+        )?;
+
+        let refname = format!("refs/heads/{base_branch_name}");
+        repo.reference(&refname, merge_commit_id, true, "Merge commit")?;
+
+        Ok(merge_commit_id)
+    }
+
+    /// Replay the task branch's commits onto the base branch in memory and fast-forward
+    /// the base branch to the result, but fail on conflicts
+    fn perform_rebase_merge(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        base_branch_name: &str,
+        options: &CommitOptions,
+    ) -> Result<git2::Oid, GitServiceError> {
+        let merge_base = Self::get_merge_base(repo, base_commit.id(), task_commit.id())?;
+        let signature = Self::commit_signature(repo, options)?;
+
+        let mut walker = repo.revwalk()?;
+        walker.push(task_commit.id())?;
+        walker.hide(merge_base)?;
+        walker.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+        let mut head = base_commit.clone();
+        for commit_id in walker {
+            let commit = repo.find_commit(commit_id?)?;
+            // Like `git rebase`, merges on the task branch are flattened: the commits
+            // they bring in are replayed on their own
+            if commit.parent_count() > 1 {
+                continue;
+            }
+
+            let mut index = repo.cherrypick_commit(&commit, &head, 0, None)?;
+            if index.has_conflicts() {
+                return Err(GitServiceError::MergeConflicts(format!(
+                    "Rebase failed due to conflicts on commit {}, please resolve conflicts manually",
+                    commit.id()
+                )));
+            }
+            let tree_id = index.write_tree_to(repo)?;
+            // Commits whose changes are already on the base become empty, drop them
+            if tree_id == head.tree_id() {
+                continue;
+            }
+            let tree = repo.find_tree(tree_id)?;
+
+            let rebased_id = Self::create_commit(
+                repo,
+                &commit.author(),
+                &signature,
+                commit.message().unwrap_or("Rebased commit"),
+                &tree,
+                &[&head],
+                options,
+            )?;
+            head = repo.find_commit(rebased_id)?;
+        }
+
+        let refname = format!("refs/heads/{base_branch_name}");
+        repo.reference(&refname, head.id(), true, "Rebase merge")?;
+
+        Ok(head.id())
+    }
+
+    /// Move the base branch to the task branch, failing if the base branch has
+    /// commits the task branch doesn't
+    fn perform_fast_forward(
+        &self,
+        repo: &Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+        base_branch_name: &str,
+    ) -> Result<git2::Oid, GitServiceError> {
+        if base_commit.id() != task_commit.id()
+            && !repo.graph_descendant_of(task_commit.id(), base_commit.id())?
+        {
+            return Err(GitServiceError::NotFastForward(
+                base_branch_name.to_string(),
+            ));
+        }
+
+        let refname = format!("refs/heads/{base_branch_name}");
+        repo.reference(&refname, task_commit.id(), true, "Fast-forward merge")?;
+
+        Ok(task_commit.id())
+    }
+
+    /// Merge the task branch into the base branch in memory, failing on conflicts
+    fn merged_tree<'r>(
+        repo: &'r Repository,
+        base_commit: &git2::Commit,
+        task_commit: &git2::Commit,
+    ) -> Result<git2::Tree<'r>, GitServiceError> {
+        // Attempt an in-memory merge to detect conflicts
+        let merge_opts = git2::MergeOptions::new();
+        let mut index = repo.merge_commits(base_commit, task_commit, Some(&merge_opts))?;
+
+        // If there are conflicts, return an error
+        if index.has_conflicts() {
+            return Err(GitServiceError::MergeConflicts(
+                "Merge failed due to conflicts. Please resolve conflicts manually.".to_string(),
+            ));
+        }
+
+        // Write the merged tree back to the repository
+        let tree_id = index.write_tree_to(repo)?;
+        Ok(repo.find_tree(tree_id)?)
+    }
+
+    /// Rebase a worktree branch onto a new base, replaying its commits after
+    /// `upstream` when given, e.g. the parent tip of a stacked attempt, or else
+    /// after the old base's tip
+    #[allow(clippy::too_many_arguments)]
     pub fn rebase_branch(
         &self,
         repo_path: &Path,
         worktree_path: &Path,
         new_base_branch: Option<&str>,
         old_base_branch: &str,
+        upstream: Option<&str>,
         github_token: Option<String>,
+        commit_options: &CommitOptions,
     ) -> Result<String, GitServiceError> {
         let worktree_repo = Repository::open(worktree_path)?;
         let main_repo = self.open_repo(repo_path)?;
 
-        // Check if there's an existing rebase in progress and abort it
-        let state = worktree_repo.state();
-        if state == git2::RepositoryState::Rebase
-            || state == git2::RepositoryState::RebaseInteractive
-            || state == git2::RepositoryState::RebaseMerge
-        {
-            tracing::warn!("Existing rebase in progress, aborting it first");
-            // Try to abort the existing rebase
-            if let Ok(mut existing_rebase) = worktree_repo.open_rebase(None) {
-                let _ = existing_rebase.abort();
-            }
+        // A stopped rebase has to be continued or aborted first
+        if Self::rebase_in_progress(&worktree_repo) {
+            return Err(GitServiceError::RebaseInProgress);
         }
 
         // Get the target base branch reference
@@ -858,116 +1900,330 @@ impl GitService {
         };
         let base_branch_name = base_branch_name.as_str();
 
-        // Handle remote branches by fetching them first and creating/updating local tracking branches
-        let local_branch_name = if base_branch_name.starts_with("origin/") {
-            let github_token = github_token.ok_or(GitServiceError::TokenUnavailable)?;
-            // This is a remote branch, fetch it and create/update local tracking branch
-            let remote_branch_name = base_branch_name.strip_prefix("origin/").unwrap();
-
-            // First, fetch the latest changes from remote
-            self.fetch_from_remote(&main_repo, &github_token)?;
-
-            // Try to find the remote branch after fetch
-            let remote_branch = main_repo
-                .find_branch(base_branch_name, BranchType::Remote)
-                .map_err(|_| GitServiceError::BranchNotFound(base_branch_name.to_string()))?;
-
-            // Check if local tracking branch exists
-            match main_repo.find_branch(remote_branch_name, BranchType::Local) {
-                Ok(mut local_branch) => {
-                    // Local tracking branch exists, update it to match remote
-                    let remote_commit = remote_branch.get().peel_to_commit()?;
-                    local_branch
-                        .get_mut()
-                        .set_target(remote_commit.id(), "Update local branch to match remote")?;
-                }
-                Err(_) => {
-                    // Local tracking branch doesn't exist, create it
-                    let remote_commit = remote_branch.get().peel_to_commit()?;
-                    main_repo.branch(remote_branch_name, &remote_commit, false)?;
-                }
-            }
+        // The old base is resolved before fetching so a remote base still points
+        // where the task branch started from
+        let old_base_commit_id = match (upstream, old_base_branch.strip_prefix("origin/")) {
+            (Some(upstream), _) => git2::Oid::from_str(upstream)?,
+            (None, Some(remote_branch_name)) => main_repo
+                .find_branch(old_base_branch, BranchType::Remote)
+                .or_else(|_| main_repo.find_branch(remote_branch_name, BranchType::Local))
+                .map_err(|_| GitServiceError::BranchNotFound(old_base_branch.to_string()))?
+                .get()
+                .peel_to_commit()?
+                .id(),
+            (None, None) => Self::find_local_commit(&main_repo, old_base_branch)?.id(),
+        };
 
-            // Use the local branch name for rebase
-            remote_branch_name
-        } else {
-            // This is already a local branch
-            base_branch_name
+        // Remote branches are fetched first and rebased onto as of the fetch
+        let new_base_commit_id = match base_branch_name.strip_prefix("origin/") {
+            Some(remote_branch_name) => {
+                let github_token = github_token.ok_or(GitServiceError::TokenUnavailable)?;
+                self.fetch_from_remote(&main_repo, &github_token)?;
+                Self::remote_base_commit(&main_repo, remote_branch_name)?.id()
+            }
+            None => Self::find_local_commit(&main_repo, base_branch_name)?.id(),
         };
 
-        // Get the local branch for rebase
-        let base_branch = main_repo
-            .find_branch(local_branch_name, BranchType::Local)
-            .map_err(|_| GitServiceError::BranchNotFound(local_branch_name.to_string()))?;
+        let signature = Self::commit_signature(&worktree_repo, commit_options)?;
 
-        let new_base_commit_id = base_branch.get().peel_to_commit()?.id();
+        // Replay the commits unique to the task branch onto the new base, like
+        // `git rebase --onto <new base> <old base>`. On conflicts the rebase stops
+        // so they can be resolved and the rebase continued or aborted.
+        let upstream = worktree_repo.find_annotated_commit(old_base_commit_id)?;
+        let onto = worktree_repo.find_annotated_commit(new_base_commit_id)?;
+        let mut rebase = worktree_repo.rebase(None, Some(&upstream), Some(&onto), None)?;
+        Self::run_rebase(&worktree_repo, &mut rebase, &signature, commit_options)?;
 
-        // Remember the original task-branch commit before we touch anything
-        let original_head_oid = worktree_repo.head()?.peel_to_commit()?.id();
+        // Get the final commit ID after rebase
+        let final_head = worktree_repo.head()?;
+        let final_commit = final_head.peel_to_commit()?;
 
-        // Get the HEAD commit of the worktree (the changes to rebase)
-        let head = worktree_repo.head()?;
-        let task_branch_commit_id = head.peel_to_commit()?.id();
+        Ok(final_commit.id().to_string())
+    }
 
-        let signature = worktree_repo.signature()?;
+    /// Rebase the worktree's branch onto `onto_branch`, replaying its commits after
+    /// `upstream`, or after its merge base with `onto_branch` when `None`. Used to
+    /// restack attempts based on another attempt's branch, where `upstream` is the
+    /// parent's tip before it was rewritten.
+    pub fn rebase_onto(
+        &self,
+        worktree_path: &Path,
+        onto_branch: &str,
+        upstream: Option<&str>,
+        commit_options: &CommitOptions,
+    ) -> Result<String, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        if Self::rebase_in_progress(&repo) {
+            return Err(GitServiceError::RebaseInProgress);
+        }
 
-        // Find the old base branch
-        let old_base_branch_ref = if old_base_branch.starts_with("origin/") {
-            // Remote branch - get local tracking branch name
-            let remote_branch_name = old_base_branch.strip_prefix("origin/").unwrap();
-            main_repo
-                .find_branch(remote_branch_name, BranchType::Local)
-                .map_err(|_| GitServiceError::BranchNotFound(remote_branch_name.to_string()))?
-        } else {
-            // Local branch
-            main_repo
-                .find_branch(old_base_branch, BranchType::Local)
-                .map_err(|_| GitServiceError::BranchNotFound(old_base_branch.to_string()))?
+        let onto_commit_id = repo
+            .find_branch(onto_branch, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(onto_branch.to_string()))?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let head_commit_id = repo.head()?.peel_to_commit()?.id();
+        let upstream_id = match upstream {
+            Some(sha) => git2::Oid::from_str(sha)?,
+            None => Self::get_merge_base(&repo, head_commit_id, onto_commit_id)?,
         };
 
-        let old_base_commit_id = old_base_branch_ref.get().peel_to_commit()?.id();
+        let signature = Self::commit_signature(&repo, commit_options)?;
+        let upstream = repo.find_annotated_commit(upstream_id)?;
+        let onto = repo.find_annotated_commit(onto_commit_id)?;
+        let mut rebase = repo.rebase(None, Some(&upstream), Some(&onto), None)?;
+        Self::run_rebase(&repo, &mut rebase, &signature, commit_options)?;
 
-        // Find commits unique to the task branch
-        let unique_commits = Self::find_unique_commits(
-            &worktree_repo,
-            task_branch_commit_id,
-            old_base_commit_id,
-            new_base_commit_id,
-        )?;
+        Ok(repo.head()?.peel_to_commit()?.id().to_string())
+    }
 
-        // Attempt the rebase operation
-        let rebase_result = if !unique_commits.is_empty() {
-            // Reset HEAD to the new base branch
-            let new_base_commit = worktree_repo.find_commit(new_base_commit_id)?;
-            worktree_repo.reset(new_base_commit.as_object(), git2::ResetType::Hard, None)?;
+    /// Commit a local branch points to
+    pub fn get_branch_oid(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let commit = repo
+            .find_branch(branch_name, BranchType::Local)
+            .map_err(|_| GitServiceError::BranchNotFound(branch_name.to_string()))?
+            .get()
+            .peel_to_commit()?;
+        Ok(commit.id().to_string())
+    }
 
-            // Cherry-pick the unique commits
-            Self::cherry_pick_commits(&worktree_repo, &unique_commits, &signature)
-        } else {
-            // No unique commits to rebase, just reset to new base
-            let new_base_commit = worktree_repo.find_commit(new_base_commit_id)?;
-            worktree_repo.reset(new_base_commit.as_object(), git2::ResetType::Hard, None)?;
-            Ok(())
+    /// Hard reset the worktree's branch to a checkpoint commit, discarding later
+    /// commits and uncommitted changes. Ignored files are left alone.
+    pub fn reset_to_checkpoint(
+        &self,
+        worktree_path: &Path,
+        checkpoint: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        if Self::rebase_in_progress(&repo) {
+            return Err(GitServiceError::RebaseInProgress);
+        }
+
+        let checkpoint_oid = git2::Oid::from_str(checkpoint)?;
+        let head_oid = repo.head()?.peel_to_commit()?.id();
+        // Rebasing rewrites the branch, older checkpoints no longer describe it
+        if checkpoint_oid != head_oid && !repo.graph_descendant_of(head_oid, checkpoint_oid)? {
+            return Err(GitServiceError::CheckpointNotOnBranch(
+                checkpoint.chars().take(7).collect(),
+            ));
+        }
+
+        let commit = repo.find_commit(checkpoint_oid)?;
+        repo.reset(commit.as_object(), git2::ResetType::Hard, None)?;
+        // A hard reset forces its own checkout and keeps untracked files, so
+        // they're removed by a second checkout
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.force().remove_untracked(true);
+        repo.checkout_head(Some(&mut checkout))?;
+        Ok(())
+    }
+
+    /// Whether a rebase stopped on conflicts in this worktree
+    pub fn is_rebase_in_progress(&self, worktree_path: &Path) -> Result<bool, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        Ok(Self::rebase_in_progress(&repo))
+    }
+
+    /// The conflicts of a stopped rebase, `None` if no rebase is in progress
+    pub fn get_rebase_conflicts(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Option<RebaseConflicts>, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        if !Self::rebase_in_progress(&repo) {
+            return Ok(None);
+        }
+
+        let mut rebase = repo.open_rebase(None)?;
+        let commit = match rebase.operation_current() {
+            Some(current) => match rebase.nth(current) {
+                Some(operation) => Some(repo.find_commit(operation.id())?),
+                None => None,
+            },
+            None => None,
         };
 
-        // Handle rebase failure by restoring original state
-        if let Err(e) = rebase_result {
-            // Clean up any cherry-pick state
-            let _ = worktree_repo.cleanup_state();
+        let mut files = Vec::new();
+        for conflict in repo.index()?.conflicts()? {
+            let conflict = conflict?;
+            let Some(path) = conflict_path(&conflict) else {
+                continue;
+            };
+            files.push(ConflictedFile {
+                path,
+                base: conflict_side_text(&repo, conflict.ancestor.as_ref()),
+                ours: conflict_side_text(&repo, conflict.our.as_ref()),
+                theirs: conflict_side_text(&repo, conflict.their.as_ref()),
+                ours_deleted: conflict.our.is_none(),
+                theirs_deleted: conflict.their.is_none(),
+                ours_binary: conflict_side_is_binary(&repo, conflict.our.as_ref()),
+                theirs_binary: conflict_side_is_binary(&repo, conflict.their.as_ref()),
+            });
+        }
+
+        Ok(Some(RebaseConflicts {
+            commit: commit.as_ref().map(|c| c.id().to_string()),
+            commit_summary: commit
+                .as_ref()
+                .and_then(|c| c.summary().map(|s| s.to_string())),
+            files,
+        }))
+    }
 
-            // Restore original task branch state
-            if let Ok(orig_commit) = worktree_repo.find_commit(original_head_oid) {
-                let _ = worktree_repo.reset(orig_commit.as_object(), git2::ResetType::Hard, None);
+    /// Resolve one conflicted file of a stopped rebase, writing the chosen content
+    /// to the worktree and staging it
+    pub fn resolve_conflict(
+        &self,
+        worktree_path: &Path,
+        file_path: &str,
+        resolution: ConflictResolution,
+    ) -> Result<(), GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        if !Self::rebase_in_progress(&repo) {
+            return Err(GitServiceError::NoRebaseInProgress);
+        }
+
+        let mut index = repo.index()?;
+        let conflict = index
+            .conflicts()?
+            .filter_map(Result::ok)
+            .find(|conflict| conflict_path(conflict).as_deref() == Some(file_path))
+            .ok_or_else(|| {
+                GitServiceError::InvalidFilePaths(format!("{file_path} is not conflicted"))
+            })?;
+
+        let side_content = |entry: Option<&git2::IndexEntry>| -> Result<_, GitServiceError> {
+            match entry {
+                Some(entry) => Ok(Some(repo.find_blob(entry.id)?.content().to_vec())),
+                None => Ok(None),
             }
+        };
+        let content = match resolution {
+            ConflictResolution::Ours => side_content(conflict.our.as_ref())?,
+            ConflictResolution::Theirs => side_content(conflict.their.as_ref())?,
+            ConflictResolution::Manual { content } => Some(content.into_bytes()),
+        };
 
-            return Err(e);
+        let full_path = worktree_path.join(file_path);
+        match content {
+            Some(content) => {
+                if let Some(parent) = full_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&full_path, content)?;
+                // Adding the path replaces its conflict entries
+                index.add_path(Path::new(file_path))?;
+            }
+            None => {
+                if full_path.exists() {
+                    std::fs::remove_file(&full_path)?;
+                }
+                index.remove_path(Path::new(file_path))?;
+            }
         }
+        index.write()?;
 
-        // Get the final commit ID after rebase
-        let final_head = worktree_repo.head()?;
-        let final_commit = final_head.peel_to_commit()?;
+        Ok(())
+    }
 
-        Ok(final_commit.id().to_string())
+    /// Commit the resolved operation of a stopped rebase and apply the rest,
+    /// stopping again if another commit conflicts
+    pub fn continue_rebase(
+        &self,
+        worktree_path: &Path,
+        commit_options: &CommitOptions,
+    ) -> Result<String, GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        if !Self::rebase_in_progress(&repo) {
+            return Err(GitServiceError::NoRebaseInProgress);
+        }
+
+        let signature = Self::commit_signature(&repo, commit_options)?;
+        let mut rebase = repo.open_rebase(None)?;
+        Self::check_rebase_conflicts(&repo)?;
+        Self::commit_rebase_operation(&mut rebase, &signature)?;
+        Self::run_rebase(&repo, &mut rebase, &signature, commit_options)?;
+
+        let head = repo.head()?.peel_to_commit()?;
+        Ok(head.id().to_string())
+    }
+
+    /// Abort a stopped rebase, restoring the task branch as it was before
+    pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let repo = Repository::open(worktree_path)?;
+        if !Self::rebase_in_progress(&repo) {
+            return Err(GitServiceError::NoRebaseInProgress);
+        }
+
+        repo.open_rebase(None)?.abort()?;
+        Ok(())
+    }
+
+    fn rebase_in_progress(repo: &Repository) -> bool {
+        matches!(
+            repo.state(),
+            git2::RepositoryState::Rebase
+                | git2::RepositoryState::RebaseInteractive
+                | git2::RepositoryState::RebaseMerge
+        )
+    }
+
+    /// Apply the remaining operations of a rebase, stopping at the first one that
+    /// conflicts. The rebased commits are signed once the rebase finishes.
+    fn run_rebase(
+        repo: &Repository,
+        rebase: &mut git2::Rebase,
+        signature: &git2::Signature,
+        options: &CommitOptions,
+    ) -> Result<(), GitServiceError> {
+        while let Some(operation) = rebase.next() {
+            operation?;
+            Self::check_rebase_conflicts(repo)?;
+            Self::commit_rebase_operation(rebase, signature)?;
+        }
+        // The rebase state is removed by finish, so read the new base first
+        let onto = Self::rebase_onto_commit(repo)?;
+        rebase.finish(Some(signature))?;
+        Self::sign_commits_since(repo, onto, options)
+    }
+
+    /// The commit the in-progress rebase replays onto, as recorded in the
+    /// rebase state directory.
+    fn rebase_onto_commit(repo: &Repository) -> Result<git2::Oid, GitServiceError> {
+        let onto = std::fs::read_to_string(repo.path().join("rebase-merge").join("onto"))?;
+        Ok(git2::Oid::from_str(onto.trim())?)
+    }
+
+    fn check_rebase_conflicts(repo: &Repository) -> Result<(), GitServiceError> {
+        let index = repo.index()?;
+        if !index.has_conflicts() {
+            return Ok(());
+        }
+        let paths: Vec<String> = index
+            .conflicts()?
+            .filter_map(Result::ok)
+            .filter_map(|conflict| conflict_path(&conflict))
+            .collect();
+        Err(GitServiceError::RebaseConflicts(paths.join(", ")))
+    }
+
+    /// Commit the current rebase operation, keeping its author. Commits whose
+    /// changes are already on the new base are dropped.
+    fn commit_rebase_operation(
+        rebase: &mut git2::Rebase,
+        signature: &git2::Signature,
+    ) -> Result<(), GitServiceError> {
+        match rebase.commit(None, signature, None) {
+            Ok(_) => Ok(()),
+            Err(e) if e.code() == git2::ErrorCode::Applied => Ok(()),
+            Err(e) => Err(e.into()),
+        }
     }
 
     /// Delete a file from the repository and commit the change
@@ -1062,6 +2318,72 @@ impl GitService {
         }
     }
 
+    /// Fetch every branch of origin into `refs/remotes/origin/*`
+    pub fn fetch_origin(
+        &self,
+        repo_path: &Path,
+        github_token: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        self.fetch_from_remote(&repo, github_token)
+    }
+
+    /// Create `branch_name` from the tip of `origin/<base_branch>` as of the
+    /// last fetch, returning the commit it starts from
+    pub fn create_branch_from_remote(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if repo.find_branch(branch_name, BranchType::Local).is_ok() {
+            return Err(GitServiceError::BranchExists(branch_name.to_string()));
+        }
+        let base_commit = Self::remote_base_commit(&repo, base_branch)?;
+        repo.branch(branch_name, &base_commit, false)?;
+        Ok(base_commit.id().to_string())
+    }
+
+    /// Commits the local branch has that `origin/<branch>` doesn't, and the
+    /// other way around, as of the last fetch. `None` when there's no remote
+    /// branch to compare with.
+    pub fn get_base_remote_drift(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<(usize, usize)>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let local_oid = Self::find_local_commit(&repo, branch_name)?.id();
+        let Ok(remote_branch) =
+            repo.find_branch(&format!("origin/{branch_name}"), BranchType::Remote)
+        else {
+            return Ok(None);
+        };
+        let remote_oid = remote_branch.get().peel_to_commit()?.id();
+        Ok(Some(repo.graph_ahead_behind(local_oid, remote_oid)?))
+    }
+
+    /// Tip of `origin/<branch>` as of the last fetch. The local branch is created
+    /// there when missing, an existing one is left where it is: it may be
+    /// checked out, and the task branch starts from the remote commit anyway.
+    fn remote_base_commit<'r>(
+        repo: &'r Repository,
+        branch_name: &str,
+    ) -> Result<git2::Commit<'r>, GitServiceError> {
+        let remote_name = format!("origin/{branch_name}");
+        let remote_commit = repo
+            .find_branch(&remote_name, BranchType::Remote)
+            .map_err(|_| GitServiceError::BranchNotFound(remote_name))?
+            .get()
+            .peel_to_commit()?;
+
+        if repo.find_branch(branch_name, BranchType::Local).is_err() {
+            repo.branch(branch_name, &remote_commit, false)?;
+        }
+        Ok(remote_commit)
+    }
+
     /// Push the branch to GitHub remote
     pub fn push_to_github(
         &self,
@@ -1184,87 +2506,6 @@ impl GitService {
             .map_err(GitServiceError::Git)
     }
 
-    /// Find commits that are unique to the task branch (not in either base branch)
-    fn find_unique_commits(
-        repo: &Repository,
-        task_branch_commit: git2::Oid,
-        old_base_commit: git2::Oid,
-        new_base_commit: git2::Oid,
-    ) -> Result<Vec<git2::Oid>, GitServiceError> {
-        // Find merge-base between task branch and old base branch
-        let task_old_base_merge_base =
-            Self::get_merge_base(repo, task_branch_commit, old_base_commit)?;
-
-        // Find merge-base between old base and new base
-        let old_new_base_merge_base = Self::get_merge_base(repo, old_base_commit, new_base_commit)?;
-
-        // Get all commits from task branch back to the merge-base with old base
-        let mut walker = repo.revwalk()?;
-        walker.push(task_branch_commit)?;
-        walker.hide(task_old_base_merge_base)?;
-
-        let mut task_commits = Vec::new();
-        for commit_id in walker {
-            let commit_id = commit_id?;
-
-            // Check if this commit is not in the old base branch lineage
-            // (i.e., it's not between old_new_base_merge_base and old_base_commit)
-            let is_in_old_base = repo
-                .graph_descendant_of(commit_id, old_new_base_merge_base)
-                .unwrap_or(false)
-                && repo
-                    .graph_descendant_of(old_base_commit, commit_id)
-                    .unwrap_or(false);
-
-            if !is_in_old_base {
-                task_commits.push(commit_id);
-            }
-        }
-
-        // Reverse to get chronological order for cherry-picking
-        task_commits.reverse();
-        Ok(task_commits)
-    }
-
-    /// Cherry-pick specific commits onto a new base
-    fn cherry_pick_commits(
-        repo: &Repository,
-        commits: &[git2::Oid],
-        signature: &git2::Signature,
-    ) -> Result<(), GitServiceError> {
-        for &commit_id in commits {
-            let commit = repo.find_commit(commit_id)?;
-
-            // Cherry-pick the commit
-            let mut cherrypick_opts = CherrypickOptions::new();
-            repo.cherrypick(&commit, Some(&mut cherrypick_opts))?;
-
-            // Check for conflicts
-            let mut index = repo.index()?;
-            if index.has_conflicts() {
-                return Err(GitServiceError::MergeConflicts(format!(
-                    "Cherry-pick failed due to conflicts on commit {commit_id}, please resolve conflicts manually"
-                )));
-            }
-
-            // Commit the cherry-pick
-            let tree_id = index.write_tree()?;
-            let tree = repo.find_tree(tree_id)?;
-            let head_commit = repo.head()?.peel_to_commit()?;
-
-            repo.commit(
-                Some("HEAD"),
-                signature,
-                signature,
-                commit.message().unwrap_or("Cherry-picked commit"),
-                &tree,
-                &[&head_commit],
-            )?;
-        }
-
-        Ok(())
-    }
-
     /// Clone a repository to the specified directory
     #[cfg(feature = "cloud")]
     pub fn clone_repository(
@@ -1533,6 +2774,40 @@ impl GitService {
     }
 }
 
+fn conflict_path(conflict: &git2::IndexConflict) -> Option<String> {
+    let entry = conflict
+        .our
+        .as_ref()
+        .or(conflict.their.as_ref())
+        .or(conflict.ancestor.as_ref())?;
+    Some(String::from_utf8_lossy(&entry.path).into_owned())
+}
+
+/// Read a child's output pipe to the end on its own thread
+fn drain_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        output
+    })
+}
+
+fn conflict_side_is_binary(repo: &Repository, entry: Option<&git2::IndexEntry>) -> bool {
+    entry
+        .and_then(|entry| repo.find_blob(entry.id).ok())
+        .is_some_and(|blob| blob.is_binary())
+}
+
+fn conflict_side_text(repo: &Repository, entry: Option<&git2::IndexEntry>) -> Option<String> {
+    let blob = repo.find_blob(entry?.id).ok()?;
+    if blob.is_binary() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use tempfile::TempDir;
@@ -1571,3 +2846,432 @@ impl GitService {
 //         assert_eq!(branch_name, "main");
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// A repository on `main` with a task branch checked out in a worktree, both
+    /// starting from a commit with `a.txt`
+    struct Fixture {
+        _dir: TempDir,
+        repo: PathBuf,
+        worktree: PathBuf,
+        start: String,
+    }
+
+    fn setup() -> Fixture {
+        let dir = TempDir::new().unwrap();
+        let repo_path = dir.path().join("repo");
+        let git = GitService::new();
+        git.initialize_repo_with_main_branch(&repo_path).unwrap();
+        let repo = Repository::open(&repo_path).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+
+        write(&repo_path, "a.txt", &numbered_lines(20));
+        commit_all(&repo_path, "Add a.txt");
+        let start = head(&repo_path);
+
+        let start_commit = repo
+            .find_commit(git2::Oid::from_str(&start).unwrap())
+            .unwrap();
+        let branch = repo.branch("task", &start_commit, false).unwrap();
+        let worktree_path = dir.path().join("worktree");
+        let mut opts = git2::WorktreeAddOptions::new();
+        opts.reference(Some(branch.get()));
+        repo.worktree("task", &worktree_path, Some(&opts)).unwrap();
+
+        Fixture {
+            _dir: dir,
+            repo: repo_path,
+            worktree: worktree_path,
+            start,
+        }
+    }
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count).map(|n| format!("line {n}\n")).collect()
+    }
+
+    fn write(path: &Path, file: &str, content: &str) {
+        std::fs::write(path.join(file), content).unwrap();
+    }
+
+    fn read(path: &Path, file: &str) -> String {
+        std::fs::read_to_string(path.join(file)).unwrap()
+    }
+
+    fn commit_all(path: &Path, message: &str) {
+        assert!(
+            GitService::new()
+                .commit(path, message, &CommitOptions::default())
+                .unwrap()
+        );
+    }
+
+    fn head(path: &Path) -> String {
+        let repo = Repository::open(path).unwrap();
+        repo.head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id()
+            .to_string()
+    }
+
+    fn branch_commit(path: &Path, branch: &str) -> (String, Vec<String>) {
+        let repo = Repository::open(path).unwrap();
+        let commit = GitService::find_local_commit(&repo, branch).unwrap();
+        let parents = commit.parent_ids().map(|id| id.to_string()).collect();
+        (commit.id().to_string(), parents)
+    }
+
+    fn file_at(path: &Path, rev: &str, file: &str) -> Option<String> {
+        let repo = Repository::open(path).unwrap();
+        let tree = repo.revparse_single(rev).unwrap().peel_to_tree().unwrap();
+        let entry = tree.get_path(Path::new(file)).ok()?;
+        let blob = repo.find_blob(entry.id()).unwrap();
+        Some(String::from_utf8_lossy(blob.content()).into_owned())
+    }
+
+    fn merge(
+        fixture: &Fixture,
+        strategy: MergeStrategy,
+    ) -> Result<DirectMergeOutcome, GitServiceError> {
+        GitService::new().merge_changes(
+            &fixture.repo,
+            &fixture.worktree,
+            "task",
+            "main",
+            "Merge task",
+            DirectMergeOptions {
+                strategy,
+                update_checkout: false,
+            },
+            &CommitOptions::default(),
+        )
+    }
+
+    /// Two task commits adding `b.txt` and `c.txt`
+    fn commit_task_files(fixture: &Fixture) {
+        write(&fixture.worktree, "b.txt", "b\n");
+        commit_all(&fixture.worktree, "Add b.txt");
+        write(&fixture.worktree, "c.txt", "c\n");
+        commit_all(&fixture.worktree, "Add c.txt");
+    }
+
+    fn move_main(fixture: &Fixture) -> String {
+        write(&fixture.repo, "d.txt", "d\n");
+        commit_all(&fixture.repo, "Add d.txt");
+        head(&fixture.repo)
+    }
+
+    #[test]
+    fn squash_merge_makes_one_commit_on_base() {
+        let fixture = setup();
+        commit_task_files(&fixture);
+
+        let outcome = merge(&fixture, MergeStrategy::Squash).unwrap();
+
+        let (tip, parents) = branch_commit(&fixture.repo, "main");
+        assert_eq!(tip, outcome.merge_commit);
+        assert_eq!(parents, vec![fixture.start.clone()]);
+        assert_eq!(outcome.base_commit, fixture.start);
+        assert_eq!(
+            file_at(&fixture.repo, "main", "b.txt").as_deref(),
+            Some("b\n")
+        );
+        assert_eq!(
+            file_at(&fixture.repo, "main", "c.txt").as_deref(),
+            Some("c\n")
+        );
+    }
+
+    #[test]
+    fn merge_commit_keeps_both_parents() {
+        let fixture = setup();
+        commit_task_files(&fixture);
+        let task_tip = head(&fixture.worktree);
+        let main_tip = move_main(&fixture);
+
+        let outcome = merge(&fixture, MergeStrategy::MergeCommit).unwrap();
+
+        let (tip, parents) = branch_commit(&fixture.repo, "main");
+        assert_eq!(tip, outcome.merge_commit);
+        assert_eq!(parents, vec![main_tip, task_tip]);
+        assert!(file_at(&fixture.repo, "main", "c.txt").is_some());
+        assert!(file_at(&fixture.repo, "main", "d.txt").is_some());
+    }
+
+    #[test]
+    fn rebase_merge_replays_task_commits_on_base() {
+        let fixture = setup();
+        commit_task_files(&fixture);
+        let main_tip = move_main(&fixture);
+
+        let outcome = merge(&fixture, MergeStrategy::Rebase).unwrap();
+
+        let repo = Repository::open(&fixture.repo).unwrap();
+        let tip = GitService::find_local_commit(&repo, "main").unwrap();
+        assert_eq!(tip.id().to_string(), outcome.merge_commit);
+        assert_eq!(tip.summary(), Some("Add c.txt"));
+        let first = tip.parent(0).unwrap();
+        assert_eq!(tip.parent_count(), 1);
+        assert_eq!(first.summary(), Some("Add b.txt"));
+        assert_eq!(first.parent_id(0).unwrap().to_string(), main_tip);
+        assert_eq!(outcome.base_commit, main_tip);
+    }
+
+    #[test]
+    fn fast_forward_moves_base_to_task_tip() {
+        let fixture = setup();
+        commit_task_files(&fixture);
+        let task_tip = head(&fixture.worktree);
+
+        let outcome = merge(&fixture, MergeStrategy::FastForward).unwrap();
+
+        assert_eq!(outcome.merge_commit, task_tip);
+        assert_eq!(branch_commit(&fixture.repo, "main").0, task_tip);
+    }
+
+    #[test]
+    fn fast_forward_fails_when_base_moved() {
+        let fixture = setup();
+        commit_task_files(&fixture);
+        let main_tip = move_main(&fixture);
+
+        let result = merge(&fixture, MergeStrategy::FastForward);
+
+        assert!(matches!(result, Err(GitServiceError::NotFastForward(_))));
+        assert_eq!(branch_commit(&fixture.repo, "main").0, main_tip);
+    }
+
+    /// Edit the first line of `a.txt` differently on `main` and the task branch
+    fn conflicting_edits(fixture: &Fixture) -> Result<String, GitServiceError> {
+        write(
+            &fixture.repo,
+            "a.txt",
+            &numbered_lines(20).replacen("line 1\n", "main\n", 1),
+        );
+        commit_all(&fixture.repo, "Edit a.txt on main");
+        write(
+            &fixture.worktree,
+            "a.txt",
+            &numbered_lines(20).replacen("line 1\n", "task\n", 1),
+        );
+        commit_all(&fixture.worktree, "Edit a.txt on task");
+
+        GitService::new().rebase_branch(
+            &fixture.repo,
+            &fixture.worktree,
+            Some("main"),
+            "main",
+            Some(&fixture.start),
+            None,
+            &CommitOptions::default(),
+        )
+    }
+
+    #[test]
+    fn rebase_conflicts_are_resolved_and_continued() {
+        let fixture = setup();
+        let git = GitService::new();
+        let result = conflicting_edits(&fixture);
+        assert!(matches!(result, Err(GitServiceError::RebaseConflicts(_))));
+        assert!(git.is_rebase_in_progress(&fixture.worktree).unwrap());
+
+        let conflicts = git
+            .get_rebase_conflicts(&fixture.worktree)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            conflicts.commit_summary.as_deref(),
+            Some("Edit a.txt on task")
+        );
+        assert_eq!(conflicts.files.len(), 1);
+        let file = &conflicts.files[0];
+        assert_eq!(file.path, "a.txt");
+        assert!(file.ours.as_deref().unwrap().starts_with("main\n"));
+        assert!(file.theirs.as_deref().unwrap().starts_with("task\n"));
+        assert!(!file.ours_deleted && !file.theirs_deleted);
+
+        let resolved = numbered_lines(20).replacen("line 1\n", "both\n", 1);
+        git.resolve_conflict(
+            &fixture.worktree,
+            "a.txt",
+            ConflictResolution::Manual {
+                content: resolved.clone(),
+            },
+        )
+        .unwrap();
+        let new_head = git
+            .continue_rebase(&fixture.worktree, &CommitOptions::default())
+            .unwrap();
+
+        assert!(!git.is_rebase_in_progress(&fixture.worktree).unwrap());
+        assert_eq!(new_head, head(&fixture.worktree));
+        let (_, parents) = branch_commit(&fixture.worktree, "task");
+        assert_eq!(parents, vec![head(&fixture.repo)]);
+        assert_eq!(read(&fixture.worktree, "a.txt"), resolved);
+    }
+
+    #[test]
+    fn aborted_rebase_restores_task_branch() {
+        let fixture = setup();
+        let git = GitService::new();
+        let before = {
+            write(&fixture.repo, "a.txt", "main\n");
+            commit_all(&fixture.repo, "Edit a.txt on main");
+            write(&fixture.worktree, "a.txt", "task\n");
+            commit_all(&fixture.worktree, "Edit a.txt on task");
+            head(&fixture.worktree)
+        };
+        let result = git.rebase_branch(
+            &fixture.repo,
+            &fixture.worktree,
+            Some("main"),
+            "main",
+            Some(&fixture.start),
+            None,
+            &CommitOptions::default(),
+        );
+        assert!(matches!(result, Err(GitServiceError::RebaseConflicts(_))));
+
+        git.abort_rebase(&fixture.worktree).unwrap();
+
+        assert!(!git.is_rebase_in_progress(&fixture.worktree).unwrap());
+        assert_eq!(head(&fixture.worktree), before);
+        assert_eq!(read(&fixture.worktree, "a.txt"), "task\n");
+    }
+
+    #[test]
+    fn commit_selection_takes_only_selected_hunks() {
+        let fixture = setup();
+        let git = GitService::new();
+        let edited = numbered_lines(20)
+            .replacen("line 1\n", "first\n", 1)
+            .replacen("line 20\n", "last\n", 1);
+        write(&fixture.worktree, "a.txt", &edited);
+        write(&fixture.worktree, "b.txt", "b\n");
+        commit_all(&fixture.worktree, "Edit a.txt, add b.txt");
+        let source = || DiffTarget::Branch {
+            repo_path: &fixture.repo,
+            branch_name: "task",
+            base_branch: "main",
+        };
+
+        let files = git.get_selectable_hunks(source()).unwrap();
+        let a = files.iter().find(|file| file.path == "a.txt").unwrap();
+        assert_eq!(a.hunks.len(), 2);
+        assert!(files.iter().any(|file| file.path == "b.txt"));
+
+        let selection = [FileSelection {
+            path: "a.txt".to_string(),
+            hunks: Some(vec![0]),
+        }];
+        let result = git
+            .commit_selection(
+                &fixture.repo,
+                source(),
+                &selection,
+                None,
+                "Take the first hunk",
+                &CommitOptions::default(),
+            )
+            .unwrap();
+
+        let (tip, parents) = branch_commit(&fixture.repo, "main");
+        assert_eq!(tip, result.commit);
+        assert_eq!(parents, vec![fixture.start.clone()]);
+        let a = file_at(&fixture.repo, "main", "a.txt").unwrap();
+        assert!(a.starts_with("first\n"));
+        assert!(a.ends_with("line 20\n"));
+        assert!(file_at(&fixture.repo, "main", "b.txt").is_none());
+        assert!(!result.excluded.is_empty());
+    }
+
+    #[test]
+    fn reset_to_checkpoint_discards_later_work() {
+        let fixture = setup();
+        let git = GitService::new();
+        write(&fixture.worktree, "b.txt", "b\n");
+        commit_all(&fixture.worktree, "Add b.txt");
+        let checkpoint = head(&fixture.worktree);
+        write(&fixture.worktree, "c.txt", "c\n");
+        commit_all(&fixture.worktree, "Add c.txt");
+        write(&fixture.worktree, "d.txt", "uncommitted\n");
+
+        git.reset_to_checkpoint(&fixture.worktree, &checkpoint)
+            .unwrap();
+
+        assert_eq!(head(&fixture.worktree), checkpoint);
+        assert!(fixture.worktree.join("b.txt").exists());
+        assert!(!fixture.worktree.join("c.txt").exists());
+        assert!(!fixture.worktree.join("d.txt").exists());
+    }
+
+    #[test]
+    fn reset_to_checkpoint_rejects_commits_off_the_branch() {
+        let fixture = setup();
+        let main_tip = move_main(&fixture);
+
+        let result = GitService::new().reset_to_checkpoint(&fixture.worktree, &main_tip);
+
+        assert!(matches!(
+            result,
+            Err(GitServiceError::CheckpointNotOnBranch(_))
+        ));
+        assert_eq!(head(&fixture.worktree), fixture.start);
+    }
+
+    #[test]
+    fn revert_commit_undoes_a_squash_merge() {
+        let fixture = setup();
+        commit_task_files(&fixture);
+        let outcome = merge(&fixture, MergeStrategy::Squash).unwrap();
+
+        let revert = GitService::new()
+            .revert_commit(
+                &fixture.repo,
+                "main",
+                &outcome.merge_commit,
+                None,
+                "Revert task",
+                &CommitOptions::default(),
+            )
+            .unwrap();
+
+        let (tip, parents) = branch_commit(&fixture.repo, "main");
+        assert_eq!(tip, revert.revert_commit);
+        assert_eq!(parents, vec![outcome.merge_commit]);
+        assert!(file_at(&fixture.repo, "main", "b.txt").is_none());
+        assert!(file_at(&fixture.repo, "main", "c.txt").is_none());
+    }
+
+    #[test]
+    fn revert_commit_from_base_undoes_every_rebased_commit() {
+        let fixture = setup();
+        commit_task_files(&fixture);
+        move_main(&fixture);
+        let outcome = merge(&fixture, MergeStrategy::Rebase).unwrap();
+
+        GitService::new()
+            .revert_commit(
+                &fixture.repo,
+                "main",
+                &outcome.merge_commit,
+                Some(&outcome.base_commit),
+                "Revert task",
+                &CommitOptions::default(),
+            )
+            .unwrap();
+
+        assert!(file_at(&fixture.repo, "main", "b.txt").is_none());
+        assert!(file_at(&fixture.repo, "main", "c.txt").is_none());
+        assert!(file_at(&fixture.repo, "main", "d.txt").is_some());
+    }
+}
@@ -0,0 +1,214 @@
+//! Prompt used to hand a task attempt over from one coding agent to another.
+//!
+//! The new agent can't resume the previous agent's session, so it starts a
+//! fresh one in the same worktree with a summary of what happened so far.
+
+use db::models::executor_session::ExecutorSession;
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+use utils::diff::{Diff, create_unified_diff};
+
+// Keep the handoff prompt well inside every agent's context window
+const MAX_CONVERSATION_CHARS: usize = 20_000;
+const MAX_DIFF_CHARS: usize = 20_000;
+const MAX_ENTRY_CHARS: usize = 2_000;
+
+pub struct HandoffContext<'a> {
+    pub task_prompt: &'a str,
+    pub previous_agent: &'a str,
+    pub sessions: &'a [ExecutorSession],
+    pub conversation: &'a [NormalizedEntry],
+    pub diffs: &'a [Diff],
+    pub instructions: &'a str,
+}
+
+pub fn build_handoff_prompt(ctx: &HandoffContext) -> String {
+    let mut prompt = format!(
+        "You are taking over a task that another coding agent ({}) has been working on. \
+         Its changes so far are already in the working tree. Review them, then continue \
+         with the instructions at the end.\n\n## Task\n\n{}\n",
+        ctx.previous_agent, ctx.task_prompt
+    );
+
+    let summaries: Vec<&str> = ctx
+        .sessions
+        .iter()
+        .filter_map(|session| session.summary.as_deref())
+        .filter(|summary| !summary.trim().is_empty())
+        .collect();
+    if !summaries.is_empty() {
+        prompt.push_str("\n## Summaries of previous runs\n\n");
+        for (i, summary) in summaries.iter().enumerate() {
+            prompt.push_str(&format!(
+                "{}. {}\n",
+                i + 1,
+                truncate(summary.trim(), MAX_ENTRY_CHARS)
+            ));
+        }
+    }
+
+    let conversation = render_conversation(ctx.conversation);
+    if !conversation.is_empty() {
+        prompt.push_str("\n## Conversation so far\n\n");
+        prompt.push_str(&conversation);
+    }
+
+    let diff = render_diffs(ctx.diffs);
+    if !diff.is_empty() {
+        prompt.push_str("\n## Current changes\n\n```diff\n");
+        prompt.push_str(&diff);
+        prompt.push_str("```\n");
+    }
+
+    prompt.push_str("\n## Instructions\n\n");
+    prompt.push_str(ctx.instructions.trim());
+    prompt.push('\n');
+    prompt
+}
+
+/// Render the conversation, keeping the most recent entries when it is too long
+fn render_conversation(entries: &[NormalizedEntry]) -> String {
+    let mut lines = Vec::new();
+    let mut total = 0;
+    for entry in entries.iter().rev() {
+        let Some(line) = render_entry(entry) else {
+            continue;
+        };
+        if total + line.len() > MAX_CONVERSATION_CHARS {
+            lines.push("(earlier conversation omitted)\n".to_string());
+            break;
+        }
+        total += line.len();
+        lines.push(line);
+    }
+    lines.reverse();
+    lines.concat()
+}
+
+fn render_entry(entry: &NormalizedEntry) -> Option<String> {
+    let content = truncate(entry.content.trim(), MAX_ENTRY_CHARS);
+    let line = match &entry.entry_type {
+        NormalizedEntryType::UserMessage => format!("**User:** {content}\n\n"),
+        NormalizedEntryType::AssistantMessage => format!("**Agent:** {content}\n\n"),
+        NormalizedEntryType::ErrorMessage => format!("**Error:** {content}\n\n"),
+        NormalizedEntryType::ToolUse { action_type, .. } => match action_type {
+            ActionType::FileRead { path } => format!("- Read `{path}`\n"),
+            ActionType::FileEdit { path, .. } => format!("- Edited `{path}`\n"),
+            ActionType::CommandRun { command } => format!("- Ran `{command}`\n"),
+            ActionType::TestRun { command, summary } => {
+                format!("- Ran `{command}`: {}\n", summary.describe())
+            }
+            _ => format!("- {content}\n"),
+        },
+        NormalizedEntryType::SystemMessage | NormalizedEntryType::Thinking => return None,
+    };
+    Some(line)
+}
+
+/// Render unified diffs until the budget runs out, then list the remaining files
+fn render_diffs(diffs: &[Diff]) -> String {
+    let mut out = String::new();
+    let mut omitted = Vec::new();
+    for diff in diffs {
+        let path = diff
+            .new_path
+            .as_deref()
+            .or(diff.old_path.as_deref())
+            .unwrap_or_default();
+        let unified = create_unified_diff(
+            path,
+            diff.old_content.as_deref().unwrap_or_default(),
+            diff.new_content.as_deref().unwrap_or_default(),
+        );
+        if !omitted.is_empty() || out.len() + unified.len() > MAX_DIFF_CHARS {
+            omitted.push(path);
+            continue;
+        }
+        out.push_str(&unified);
+    }
+    if !omitted.is_empty() {
+        out.push_str(&format!(
+            "# Diff truncated, also changed: {}\n",
+            omitted.join(", ")
+        ));
+    }
+    out
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::diff::DiffChangeKind;
+
+    use super::*;
+
+    fn message(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+            parent_id: None,
+        }
+    }
+
+    fn context<'a>(conversation: &'a [NormalizedEntry], diffs: &'a [Diff]) -> HandoffContext<'a> {
+        HandoffContext {
+            task_prompt: "Fix the login form",
+            previous_agent: "claude-code",
+            sessions: &[],
+            conversation,
+            diffs,
+            instructions: "Add tests for the validation",
+        }
+    }
+
+    #[test]
+    fn includes_conversation_diff_and_instructions() {
+        let conversation = vec![
+            message(NormalizedEntryType::UserMessage, "Fix the login form"),
+            message(NormalizedEntryType::Thinking, "hmm"),
+            message(
+                NormalizedEntryType::ToolUse {
+                    tool_name: "bash".to_string(),
+                    action_type: ActionType::CommandRun {
+                        command: "npm test".to_string(),
+                    },
+                },
+                "npm test",
+            ),
+        ];
+        let diffs = vec![Diff {
+            change: DiffChangeKind::Modified,
+            old_path: Some("src/login.ts".to_string()),
+            new_path: Some("src/login.ts".to_string()),
+            old_content: Some("a\n".to_string()),
+            new_content: Some("b\n".to_string()),
+        }];
+
+        let prompt = build_handoff_prompt(&context(&conversation, &diffs));
+        assert!(prompt.contains("**User:** Fix the login form"));
+        assert!(prompt.contains("- Ran `npm test`"));
+        assert!(!prompt.contains("hmm"));
+        assert!(prompt.contains("+++ b/src/login.ts"));
+        assert!(prompt.ends_with("Add tests for the validation\n"));
+    }
+
+    #[test]
+    fn long_conversations_keep_latest_entries() {
+        let long = "x".repeat(MAX_ENTRY_CHARS);
+        let mut conversation: Vec<_> = (0..20)
+            .map(|_| message(NormalizedEntryType::AssistantMessage, &long))
+            .collect();
+        conversation.push(message(NormalizedEntryType::AssistantMessage, "latest"));
+
+        let prompt = build_handoff_prompt(&context(&conversation, &[]));
+        assert!(prompt.contains("(earlier conversation omitted)"));
+        assert!(prompt.contains("**Agent:** latest"));
+    }
+}
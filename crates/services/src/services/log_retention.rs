@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use db::{
+    DBService,
+    models::{
+        execution_process::{ExecutionProcess, ExecutionProcessStatus},
+        execution_process_logs::{ExecutionLogSize, ExecutionProcessLogs},
+        execution_process_normalized_entry::ExecutionProcessNormalizedEntry,
+    },
+};
+use utils::assets::execution_logs_dir;
+use uuid::Uuid;
+
+use crate::services::config::LogRetentionConfig;
+
+/// Outcome of one retention pass
+#[derive(Debug, Default)]
+pub struct RetentionReport {
+    pub compacted: usize,
+    pub purged: usize,
+    /// Executions whose normalized conversation was deleted to meet the size cap
+    pub purged_conversations: usize,
+    pub removed_log_files: usize,
+}
+
+/// Merge and compress the logs of finished executions, then delete the raw logs
+/// that fall outside the configured limits. Normalized conversations are kept,
+/// so purged executions can still be viewed, unless the size cap can't be met
+/// by deleting raw logs alone.
+pub async fn run(
+    db: &DBService,
+    config: &LogRetentionConfig,
+) -> Result<RetentionReport, sqlx::Error> {
+    let mut report = RetentionReport::default();
+
+    for execution_id in ExecutionProcessLogs::find_compactable_execution_ids(&db.pool).await? {
+        match ExecutionProcessLogs::compact(&db.pool, execution_id).await {
+            Ok(true) => report.compacted += 1,
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to compact logs for {}: {}", execution_id, e),
+        }
+    }
+
+    let mut purge = HashSet::new();
+    if let Some(days) = config.max_age_days {
+        let before = Utc::now() - Duration::days(days as i64);
+        purge.extend(
+            ExecutionProcessLogs::find_execution_ids_completed_before(&db.pool, before).await?,
+        );
+    }
+    if let Some(keep) = config.keep_last_attempts {
+        purge.extend(
+            ExecutionProcessLogs::find_execution_ids_beyond_last_attempts(&db.pool, keep as i64)
+                .await?,
+        );
+    }
+    for execution_id in &purge {
+        ExecutionProcessLogs::delete_by_execution_id(&db.pool, *execution_id).await?;
+    }
+    report.purged += purge.len();
+
+    // The size cap applies to whatever is left after the other limits, and counts
+    // normalized conversations too. Raw logs go first, conversations only if
+    // that isn't enough.
+    if let Some(max_total_mb) = config.max_total_mb {
+        let cap = i64::try_from(max_total_mb.saturating_mul(1024 * 1024)).unwrap_or(i64::MAX);
+        let total = ExecutionProcessLogs::total_stored_bytes(&db.pool).await?;
+        if total > cap {
+            let sizes = ExecutionProcessLogs::find_finished_sizes(&db.pool, None, None).await?;
+            let over = select_over_size_cap(&sizes, total, cap);
+            for execution_id in &over {
+                ExecutionProcessLogs::delete_by_execution_id(&db.pool, *execution_id).await?;
+            }
+            report.purged += over.len();
+        }
+
+        let total = ExecutionProcessLogs::total_stored_bytes(&db.pool).await?;
+        if total > cap {
+            let sizes = ExecutionProcessNormalizedEntry::find_finished_sizes(&db.pool).await?;
+            let over = select_over_size_cap(&sizes, total, cap);
+            for execution_id in &over {
+                ExecutionProcessNormalizedEntry::delete_by_execution_id(&db.pool, *execution_id)
+                    .await?;
+            }
+            report.purged_conversations += over.len();
+        }
+    }
+
+    if report.compacted > 0 || report.purged > 0 || report.purged_conversations > 0 {
+        ExecutionProcessLogs::reclaim_space(&db.pool).await?;
+    }
+
+    report.removed_log_files = sweep_log_files(db).await?;
+
+    Ok(report)
+}
+
+/// Delete the log files of executions that are no longer running. They are
+/// removed when an execution finishes, this catches the ones left behind by
+/// stopped executions or a server that went away.
+async fn sweep_log_files(db: &DBService) -> Result<usize, sqlx::Error> {
+    let Ok(mut entries) = tokio::fs::read_dir(execution_logs_dir()).await else {
+        return Ok(0);
+    };
+
+    let mut removed = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Some(execution_id) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| Uuid::parse_str(name).ok())
+        else {
+            continue;
+        };
+        let running = ExecutionProcess::find_by_id(&db.pool, execution_id)
+            .await?
+            .is_some_and(|process| process.status == ExecutionProcessStatus::Running);
+        if running {
+            continue;
+        }
+
+        match tokio::fs::remove_dir_all(entry.path()).await {
+            Ok(()) => removed += 1,
+            Err(e) => tracing::warn!(
+                "Failed to remove log files of execution {}: {}",
+                execution_id,
+                e
+            ),
+        }
+    }
+    Ok(removed)
+}
+
+/// Delete the raw logs of a project's finished executions, optionally only
+/// those that completed before `before`. Returns how many were purged.
+pub async fn purge_project(
+    db: &DBService,
+    project_id: Uuid,
+    before: Option<DateTime<Utc>>,
+) -> Result<usize, sqlx::Error> {
+    let sizes =
+        ExecutionProcessLogs::find_finished_sizes(&db.pool, Some(project_id), before).await?;
+    for size in &sizes {
+        ExecutionProcessLogs::delete_by_execution_id(&db.pool, size.execution_id).await?;
+    }
+    Ok(sizes.len())
+}
+
+/// Pick the executions to delete, oldest first, until `total` is within `cap`.
+/// `sizes` must be ordered oldest first.
+pub fn select_over_size_cap(sizes: &[ExecutionLogSize], total: i64, cap: i64) -> Vec<Uuid> {
+    let mut remaining = total;
+    sizes
+        .iter()
+        .take_while(|size| {
+            if remaining <= cap {
+                return false;
+            }
+            remaining -= size.stored_bytes;
+            true
+        })
+        .map(|size| size.execution_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizes(bytes: &[i64]) -> Vec<ExecutionLogSize> {
+        bytes
+            .iter()
+            .map(|stored_bytes| ExecutionLogSize {
+                execution_id: Uuid::new_v4(),
+                stored_bytes: *stored_bytes,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn under_cap_selects_nothing() {
+        assert!(select_over_size_cap(&sizes(&[10, 20]), 30, 50).is_empty());
+    }
+
+    #[test]
+    fn selects_oldest_until_within_cap() {
+        let sizes = sizes(&[10, 20, 30, 40]);
+        let selected = select_over_size_cap(&sizes, 100, 65);
+        assert_eq!(
+            selected,
+            vec![
+                sizes[0].execution_id,
+                sizes[1].execution_id,
+                sizes[2].execution_id
+            ]
+        );
+    }
+
+    #[test]
+    fn selects_everything_when_finished_logs_are_not_enough() {
+        // Running executions count towards the total but can't be purged
+        let sizes = sizes(&[10, 10]);
+        assert_eq!(select_over_size_cap(&sizes, 100, 50).len(), 2);
+    }
+}
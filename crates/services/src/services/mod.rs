@@ -1,18 +1,28 @@
 pub mod analytics;
 pub mod auth;
+pub mod commit_settings;
 pub mod config;
 pub mod container;
 pub mod events;
+pub mod execution_queue;
+pub mod file_activity;
 pub mod file_ranker;
 pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
 pub mod github_service;
+pub mod handoff;
 pub mod image;
 pub mod jira_auth;
 pub mod jira_service;
+pub mod log_retention;
 pub mod notification;
 pub mod pr_monitor;
+pub mod redaction;
+pub mod retry_policy;
+pub mod search;
 pub mod secure_storage;
 pub mod sentry;
+pub mod task_scheduler;
+pub mod transcript;
 pub mod worktree_manager;
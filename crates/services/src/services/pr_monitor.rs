@@ -15,6 +15,7 @@ use tracing::{debug, error, info};
 
 use crate::services::{
     config::Config,
+    git::GitService,
     github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
 };
 
@@ -126,9 +127,52 @@ impl PrMonitorService {
                     pr_merge.pr_info.number, task_attempt.task_id
                 );
                 Task::update_status(&self.db.pool, task_attempt.task_id, TaskStatus::Done).await?;
+                self.retarget_stacked_children(&task_attempt).await?;
             }
         }
 
         Ok(())
     }
+
+    /// Move the attempts stacked on an attempt whose PR was merged onto its base
+    /// branch. GitHub may have squashed or rebased the parent's commits, so their
+    /// next rebase replays only the commits after the parent tip they were last
+    /// based on, which stays recorded.
+    async fn retarget_stacked_children(&self, merged: &TaskAttempt) -> Result<(), PrMonitorError> {
+        let children = TaskAttempt::find_stacked_children(&self.db.pool, merged.id).await?;
+        if children.is_empty() {
+            return Ok(());
+        }
+
+        // Attempts stacked before parent tips were recorded are based on the
+        // merged attempt's branch as it is locally
+        let merged_tip = match (&merged.branch, merged.parent_task(&self.db.pool).await?) {
+            (Some(branch), Some(task)) => {
+                task.parent_project(&self.db.pool)
+                    .await?
+                    .and_then(|project| {
+                        GitService::new()
+                            .get_branch_oid(&project.git_repo_path, branch)
+                            .ok()
+                    })
+            }
+            _ => None,
+        };
+
+        for child in children {
+            if child.stacked_on_commit.is_none()
+                && let Some(tip) = &merged_tip
+            {
+                TaskAttempt::update_stacked_on_commit(&self.db.pool, child.id, Some(tip)).await?;
+            }
+            info!(
+                "Retargeting stacked attempt {} onto {} after its parent's PR was merged",
+                child.id, merged.base_branch
+            );
+            TaskAttempt::update_base_branch(&self.db.pool, child.id, &merged.base_branch).await?;
+            TaskAttempt::update_parent_attempt(&self.db.pool, child.id, merged.parent_attempt_id)
+                .await?;
+        }
+        Ok(())
+    }
 }
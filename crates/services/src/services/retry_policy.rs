@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use db::models::project_retry_policy::{ProjectRetryPolicy, UpsertProjectRetryPolicy};
+use regex::Regex;
+use thiserror::Error;
+
+// Keep a runaway multiplier from scheduling retries days into the future
+const MAX_BACKOFF_SECS: f64 = 3600.0;
+
+#[derive(Debug, Error)]
+pub enum RetryPolicyError {
+    #[error("max_attempts must be at least 1")]
+    InvalidMaxAttempts,
+    #[error("backoff_seconds must not be negative")]
+    InvalidBackoff,
+    #[error("backoff_multiplier must be at least 1")]
+    InvalidBackoffMultiplier,
+    #[error("Invalid stderr pattern '{0}': {1}")]
+    InvalidPattern(String, regex::Error),
+}
+
+/// Check a policy before it is stored
+pub fn validate(data: &UpsertProjectRetryPolicy) -> Result<(), RetryPolicyError> {
+    if data.max_attempts < 1 {
+        return Err(RetryPolicyError::InvalidMaxAttempts);
+    }
+    if data.backoff_seconds < 0 {
+        return Err(RetryPolicyError::InvalidBackoff);
+    }
+    if data.backoff_multiplier.is_some_and(|m| m < 1.0) {
+        return Err(RetryPolicyError::InvalidBackoffMultiplier);
+    }
+    for pattern in &data.retryable_stderr_patterns {
+        Regex::new(pattern).map_err(|e| RetryPolicyError::InvalidPattern(pattern.clone(), e))?;
+    }
+    Ok(())
+}
+
+/// Whether a failed run counts as retryable. A policy without exit codes or
+/// stderr patterns retries every failure; otherwise any match is enough.
+pub fn is_retryable(policy: &ProjectRetryPolicy, exit_code: Option<i64>, stderr: &str) -> bool {
+    let exit_codes = &policy.retryable_exit_codes.0;
+    let patterns = &policy.retryable_stderr_patterns.0;
+    if exit_codes.is_empty() && patterns.is_empty() {
+        return true;
+    }
+
+    if exit_code.is_some_and(|code| exit_codes.contains(&code)) {
+        return true;
+    }
+
+    patterns.iter().any(|pattern| match Regex::new(pattern) {
+        Ok(re) => re.is_match(stderr),
+        Err(e) => {
+            tracing::warn!("Skipping invalid retry pattern '{}': {}", pattern, e);
+            false
+        }
+    })
+}
+
+/// Delay before the next run, given how many runs have happened so far
+pub fn backoff(policy: &ProjectRetryPolicy, attempts: i64) -> Duration {
+    let exponent = (attempts - 1).max(0) as i32;
+    let secs = policy.backoff_seconds as f64 * policy.backoff_multiplier.powi(exponent);
+    Duration::from_secs_f64(secs.clamp(0.0, MAX_BACKOFF_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::execution_process::ExecutionProcessRunReason;
+    use sqlx::types::Json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn policy(exit_codes: Vec<i64>, patterns: Vec<&str>) -> ProjectRetryPolicy {
+        ProjectRetryPolicy {
+            id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            run_reason: ExecutionProcessRunReason::SetupScript,
+            max_attempts: 3,
+            backoff_seconds: 10,
+            backoff_multiplier: 2.0,
+            retryable_exit_codes: Json(exit_codes),
+            retryable_stderr_patterns: Json(patterns.into_iter().map(String::from).collect()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn empty_policy_retries_everything() {
+        assert!(is_retryable(&policy(vec![], vec![]), Some(1), ""));
+        assert!(is_retryable(&policy(vec![], vec![]), None, ""));
+    }
+
+    #[test]
+    fn matches_exit_codes_or_stderr() {
+        let p = policy(vec![137], vec![r"npm ERR! code E(TIMEDOUT|CONNRESET)"]);
+        assert!(is_retryable(&p, Some(137), ""));
+        assert!(is_retryable(&p, Some(1), "npm ERR! code ETIMEDOUT\n"));
+        assert!(!is_retryable(&p, Some(1), "npm ERR! code E404\n"));
+    }
+
+    #[test]
+    fn backoff_grows_exponentially() {
+        let p = policy(vec![], vec![]);
+        assert_eq!(backoff(&p, 1), Duration::from_secs(10));
+        assert_eq!(backoff(&p, 2), Duration::from_secs(20));
+        assert_eq!(backoff(&p, 3), Duration::from_secs(40));
+    }
+}
@@ -0,0 +1,58 @@
+//! Turns user search input into an FTS5 query.
+//!
+//! Input is free text like `auth middleware` or `rm -rf`, which FTS5 would read
+//! as operators and column filters, so every word is quoted and all of them have
+//! to match. A trailing `*` on a word still does a prefix search.
+
+/// Build the FTS5 MATCH expression for `input`, or None if it has no words
+pub fn to_match_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .filter_map(|word| {
+            let (word, prefix) = match word.strip_suffix('*') {
+                Some(stem) => (stem, true),
+                None => (word, false),
+            };
+            if word.is_empty() {
+                return None;
+            }
+            let quoted = format!("\"{}\"", word.replace('"', "\"\""));
+            Some(if prefix { format!("{quoted}*") } else { quoted })
+        })
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_every_word() {
+        assert_eq!(
+            to_match_query("auth middleware").as_deref(),
+            Some(r#""auth" "middleware""#)
+        );
+        assert_eq!(to_match_query("rm -rf").as_deref(), Some(r#""rm" "-rf""#));
+        assert_eq!(
+            to_match_query(r#"say "hi" NOT"#).as_deref(),
+            Some(r#""say" """hi""" "NOT""#)
+        );
+    }
+
+    #[test]
+    fn keeps_prefix_searches() {
+        assert_eq!(to_match_query("middle*").as_deref(), Some(r#""middle"*"#));
+        assert_eq!(to_match_query("*").as_deref(), None);
+    }
+
+    #[test]
+    fn empty_input_has_no_query() {
+        assert_eq!(to_match_query("   "), None);
+    }
+}
@@ -0,0 +1,328 @@
+use std::{str::FromStr, time::Duration};
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        task::{CreateTask, Task, TaskStatus, TaskType},
+        task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+        task_schedule::TaskSchedule,
+        task_template::TaskTemplate,
+    },
+};
+use executors::profile::ProfileVariantLabel;
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::services::container::{ContainerError, ContainerService};
+
+#[derive(Debug, Error)]
+pub enum TaskSchedulerError {
+    #[error("Invalid cron expression '{0}': {1}")]
+    InvalidCron(String, String),
+    #[error("Task {0} not found")]
+    TaskNotFound(Uuid),
+    #[error("Task template {0} not found")]
+    TemplateNotFound(Uuid),
+    #[error(transparent)]
+    TaskAttemptError(#[from] TaskAttemptError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+}
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Parse a cron expression. Standard five field expressions
+/// (`min hour day month weekday`) are accepted as well as the six/seven field
+/// form with seconds (and years).
+///
+/// In the five field form numeric weekdays follow standard cron (0 or 7 is
+/// Sunday, 1 is Monday). The six/seven field form is passed to the `cron` crate
+/// as is, where 1 is Sunday.
+pub fn parse_cron(expression: &str) -> Result<Schedule, TaskSchedulerError> {
+    let invalid = |reason: String| TaskSchedulerError::InvalidCron(expression.to_string(), reason);
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let normalized = if fields.len() == 5 {
+        let day_of_week = standard_day_of_week(fields[4]).map_err(invalid)?;
+        format!("0 {} {day_of_week}", fields[..4].join(" "))
+    } else {
+        fields.join(" ")
+    };
+
+    Schedule::from_str(&normalized).map_err(|e| invalid(e.to_string()))
+}
+
+/// Rewrite a standard cron weekday field with day names, which the `cron` crate
+/// reads the same way, instead of its own 1-7 numbering
+fn standard_day_of_week(field: &str) -> Result<String, String> {
+    let mut items = Vec::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (item, None),
+        };
+        if range.chars().any(|c| c.is_ascii_alphabetic())
+            || (step.is_none() && range == "*")
+            || range == "?"
+        {
+            items.push(item.to_string());
+            continue;
+        }
+
+        let day = |value: &str| match value.parse::<usize>() {
+            Ok(day) if day <= 7 => Ok(day),
+            _ => Err(format!("'{value}' is not a day of the week (0-7)")),
+        };
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((first, last)) => (day(first)?, day(last)?),
+            None if step.is_some() => (day(range)?, 6),
+            None => (day(range)?, day(range)?),
+        };
+        if first > last {
+            return Err(format!("'{item}' is not an ascending range"));
+        }
+        let step = match step.map(str::parse::<usize>) {
+            None => 1,
+            Some(Ok(step)) if step > 0 => step,
+            Some(_) => return Err(format!("'{item}' has an invalid step")),
+        };
+
+        items.extend(
+            (first..=last)
+                .step_by(step)
+                .map(|day| DAY_NAMES[day % 7].to_string()),
+        );
+    }
+    Ok(items.join(","))
+}
+
+/// The first occurrence of `expression` strictly after `after`
+pub fn next_run_after(
+    expression: &str,
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, TaskSchedulerError> {
+    Ok(parse_cron(expression)?.after(&after).next())
+}
+
+/// Service that starts attempts for task schedules when they fall due
+pub struct TaskSchedulerService<'a, C: ContainerService> {
+    db: DBService,
+    container: &'a C,
+    poll_interval: Duration,
+}
+
+impl<'a, C: ContainerService> TaskSchedulerService<'a, C> {
+    pub fn new(db: DBService, container: &'a C) -> Self {
+        Self {
+            db,
+            container,
+            poll_interval: Duration::from_secs(30),
+        }
+    }
+
+    pub async fn start(&self) {
+        info!(
+            "Starting task scheduler service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules().await {
+                error!("Error running task schedules: {}", e);
+            }
+        }
+    }
+
+    async fn run_due_schedules(&self) -> Result<(), TaskSchedulerError> {
+        let now = Utc::now();
+        let due = TaskSchedule::find_due(&self.db.pool, now).await?;
+
+        if due.is_empty() {
+            debug!("No task schedules due");
+            return Ok(());
+        }
+
+        for schedule in due {
+            // Move on to the next occurrence first so a failing trigger doesn't retry every poll.
+            // Runs missed while the server was down collapse into this single trigger.
+            let next_run_at = match next_run_after(&schedule.cron_expression, now) {
+                Ok(next) => next,
+                Err(e) => {
+                    error!("Disabling schedule {}: {}", schedule.id, e);
+                    None
+                }
+            };
+            TaskSchedule::mark_run(&self.db.pool, schedule.id, now, next_run_at).await?;
+
+            if let Err(e) = self.trigger(&schedule).await {
+                error!("Failed to trigger schedule {}: {}", schedule.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create or reuse the schedule's task and start an attempt for it
+    async fn trigger(&self, schedule: &TaskSchedule) -> Result<(), TaskSchedulerError> {
+        let pool = &self.db.pool;
+
+        let task = if let Some(task_id) = schedule.task_id {
+            Task::find_by_id(pool, task_id)
+                .await?
+                .ok_or(TaskSchedulerError::TaskNotFound(task_id))?
+        } else if let Some(template_id) = schedule.task_template_id {
+            self.template_task(schedule, template_id).await?
+        } else {
+            return Ok(());
+        };
+
+        // Don't pile up attempts if the previous run is still going
+        if ExecutionProcess::has_active_for_task(pool, task.id).await? {
+            info!(
+                "Skipping schedule {}: task {} still has an active attempt",
+                schedule.id, task.id
+            );
+            return Ok(());
+        }
+
+        let task_attempt = TaskAttempt::create(
+            pool,
+            &CreateTaskAttempt {
+                profile: schedule.profile.clone(),
+                base_branch: schedule.base_branch.clone(),
+            },
+            task.id,
+        )
+        .await?;
+
+        let profile_variant_label = ProfileVariantLabel {
+            profile: schedule.profile.clone(),
+            variant: schedule.variant.clone(),
+        };
+        let execution_process = self
+            .container
+            .start_attempt(&task_attempt, profile_variant_label)
+            .await?;
+
+        info!(
+            "Schedule {} started attempt {} for task {} (execution process {})",
+            schedule.id, task_attempt.id, task.id, execution_process.id
+        );
+        Ok(())
+    }
+
+    /// The task last created from the schedule's template while it isn't done,
+    /// otherwise a new task from the template
+    async fn template_task(
+        &self,
+        schedule: &TaskSchedule,
+        template_id: Uuid,
+    ) -> Result<Task, TaskSchedulerError> {
+        let pool = &self.db.pool;
+
+        if let Some(task_id) = schedule.template_task_id
+            && let Some(task) = Task::find_by_id(pool, task_id).await?
+            && !matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled)
+        {
+            return Ok(task);
+        }
+
+        let template = TaskTemplate::find_by_id(pool, template_id)
+            .await?
+            .ok_or(TaskSchedulerError::TemplateNotFound(template_id))?;
+        let task = Task::create(
+            pool,
+            &CreateTask {
+                project_id: schedule.project_id,
+                title: template.title,
+                description: template.description,
+                task_type: TaskType::Chore,
+                parent_task_attempt: None,
+                image_ids: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+        TaskSchedule::update_template_task(pool, schedule.id, task.id).await?;
+        Ok(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn accepts_five_field_expressions() {
+        let after = Utc.with_ymd_and_hms(2025, 8, 25, 10, 30, 0).unwrap();
+        // Every Monday at 09:00
+        let next = next_run_after("0 9 * * Mon", after).unwrap().unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2025, 9, 1, 9, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn reads_numeric_weekdays_as_standard_cron() {
+        // Monday 2025-08-25
+        let after = Utc.with_ymd_and_hms(2025, 8, 25, 10, 30, 0).unwrap();
+        let sunday = Utc.with_ymd_and_hms(2025, 8, 31, 9, 0, 0).unwrap();
+        assert_eq!(next_run_after("0 9 * * 0", after).unwrap(), Some(sunday));
+        assert_eq!(next_run_after("0 9 * * 7", after).unwrap(), Some(sunday));
+        assert_eq!(
+            next_run_after("0 9 * * 1", after).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 9, 1, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            next_run_after("0 9 * * 6,0", after).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 8, 30, 9, 0, 0).unwrap())
+        );
+
+        // Friday 2025-08-29, weekdays skip to Monday
+        let friday = Utc.with_ymd_and_hms(2025, 8, 29, 10, 30, 0).unwrap();
+        assert_eq!(
+            next_run_after("0 9 * * 1-5", friday).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 9, 1, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            next_run_after("0 9 * * 5-7", friday).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 8, 30, 9, 0, 0).unwrap())
+        );
+        // Every other day from Sunday: Sunday, Tuesday, Thursday, Saturday
+        assert_eq!(
+            next_run_after("0 9 * * */2", friday).unwrap(),
+            Some(Utc.with_ymd_and_hms(2025, 8, 30, 9, 0, 0).unwrap())
+        );
+
+        assert!(matches!(
+            parse_cron("0 9 * * 8"),
+            Err(TaskSchedulerError::InvalidCron(_, _))
+        ));
+    }
+
+    #[test]
+    fn accepts_expressions_with_seconds() {
+        let after = Utc.with_ymd_and_hms(2025, 8, 25, 10, 30, 0).unwrap();
+        let next = next_run_after("30 0 12 * * *", after).unwrap().unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2025, 8, 25, 12, 0, 30).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(matches!(
+            parse_cron("every monday"),
+            Err(TaskSchedulerError::InvalidCron(_, _))
+        ));
+    }
+}
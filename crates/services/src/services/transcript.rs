@@ -0,0 +1,384 @@
+//! Export of a task attempt's transcript: every execution process with its
+//! normalized conversation or script output, and the final diff.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    task::Task,
+    task_attempt::TaskAttempt,
+};
+use executors::logs::{ActionType, FileChange, NormalizedEntry, NormalizedEntryType};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::diff::{Diff, create_unified_diff};
+
+// GitHub rejects PR bodies longer than this
+const MAX_PR_BODY_CHARS: usize = 65_536;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Html,
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptProcess {
+    pub execution_process: ExecutionProcess,
+    /// Normalized conversation of coding agent runs
+    pub entries: Vec<NormalizedEntry>,
+    /// Raw stdout and stderr of script runs
+    pub output: Option<String>,
+}
+
+/// Everything that happened in a task attempt, the JSON export format
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TranscriptBundle {
+    pub task: Task,
+    pub task_attempt: TaskAttempt,
+    pub processes: Vec<TranscriptProcess>,
+    pub diffs: Vec<Diff>,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// Format independent pieces of a rendered transcript
+enum Block {
+    Heading(u8, String),
+    Fields(Vec<(&'static str, String)>),
+    Text(Option<&'static str>, String),
+    Code(&'static str, String),
+    Details(String, String),
+    Quote(String),
+}
+
+fn blocks(bundle: &TranscriptBundle) -> Vec<Block> {
+    let mut blocks = vec![
+        Block::Heading(1, format!("Transcript: {}", bundle.task.title)),
+        Block::Fields(vec![
+            ("Attempt", bundle.task_attempt.id.to_string()),
+            ("Profile", bundle.task_attempt.profile.clone()),
+            (
+                "Branch",
+                bundle.task_attempt.branch.clone().unwrap_or_default(),
+            ),
+            ("Base branch", bundle.task_attempt.base_branch.clone()),
+            ("Exported at", bundle.exported_at.to_rfc3339()),
+        ]),
+    ];
+    if let Some(description) = bundle
+        .task
+        .description
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        blocks.push(Block::Heading(2, "Task".to_string()));
+        blocks.push(Block::Text(None, description.trim().to_string()));
+    }
+
+    for (i, process) in bundle.processes.iter().enumerate() {
+        let ep = &process.execution_process;
+        blocks.push(Block::Heading(
+            2,
+            format!(
+                "{}. {} ({:?}, {})",
+                i + 1,
+                run_reason_label(&ep.run_reason),
+                ep.status,
+                ep.started_at.to_rfc3339()
+            ),
+        ));
+        for entry in &process.entries {
+            entry_blocks(entry, &mut blocks);
+        }
+        if let Some(output) = process.output.as_deref().filter(|o| !o.is_empty()) {
+            blocks.push(Block::Code("", output.to_string()));
+        }
+    }
+
+    if !bundle.diffs.is_empty() {
+        blocks.push(Block::Heading(2, "Final diff".to_string()));
+        for diff in &bundle.diffs {
+            let path = diff
+                .new_path
+                .as_deref()
+                .or(diff.old_path.as_deref())
+                .unwrap_or_default();
+            blocks.push(Block::Code(
+                "diff",
+                create_unified_diff(
+                    path,
+                    diff.old_content.as_deref().unwrap_or_default(),
+                    diff.new_content.as_deref().unwrap_or_default(),
+                ),
+            ));
+        }
+    }
+    blocks
+}
+
+fn entry_blocks(entry: &NormalizedEntry, blocks: &mut Vec<Block>) {
+    let content = entry.content.trim().to_string();
+    match &entry.entry_type {
+        NormalizedEntryType::UserMessage => blocks.push(Block::Text(Some("User"), content)),
+        NormalizedEntryType::AssistantMessage => blocks.push(Block::Text(Some("Agent"), content)),
+        NormalizedEntryType::ErrorMessage => blocks.push(Block::Text(Some("Error"), content)),
+        NormalizedEntryType::SystemMessage => blocks.push(Block::Quote(content)),
+        NormalizedEntryType::Thinking => {
+            blocks.push(Block::Details("Thinking".to_string(), content))
+        }
+        NormalizedEntryType::ToolUse {
+            tool_name,
+            action_type,
+        } => match action_type {
+            ActionType::CommandRun { command } => {
+                blocks.push(Block::Text(Some("Ran"), String::new()));
+                blocks.push(Block::Code("sh", command.clone()));
+            }
+            ActionType::FileEdit { path, changes } => {
+                blocks.push(Block::Text(Some("Edited"), path.clone()));
+                for change in changes {
+                    match change {
+                        FileChange::Edit { unified_diff, .. } => {
+                            blocks.push(Block::Code("diff", unified_diff.clone()))
+                        }
+                        FileChange::Write { content } => {
+                            blocks.push(Block::Details(format!("Wrote {path}"), content.clone()))
+                        }
+                        FileChange::Delete => {
+                            blocks.push(Block::Text(Some("Deleted"), path.clone()))
+                        }
+                        FileChange::Rename { new_path } => blocks.push(Block::Text(
+                            Some("Renamed"),
+                            format!("{path} to {new_path}"),
+                        )),
+                    }
+                }
+            }
+            ActionType::TestRun { command, summary } => {
+                blocks.push(Block::Text(Some("Tested"), summary.describe()));
+                blocks.push(Block::Code("sh", command.clone()));
+                if !summary.failing_tests.is_empty() {
+                    blocks.push(Block::Code("", summary.failing_tests.join("\n")));
+                }
+            }
+            ActionType::FileRead { path } => blocks.push(Block::Text(Some("Read"), path.clone())),
+            _ => blocks.push(Block::Text(Some("Tool"), format!("{tool_name}: {content}"))),
+        },
+    }
+}
+
+fn run_reason_label(run_reason: &ExecutionProcessRunReason) -> &'static str {
+    match run_reason {
+        ExecutionProcessRunReason::SetupScript => "Setup script",
+        ExecutionProcessRunReason::CleanupScript => "Cleanup script",
+        ExecutionProcessRunReason::CodingAgent => "Coding agent",
+        ExecutionProcessRunReason::DevServer => "Dev server",
+    }
+}
+
+pub fn render_markdown(bundle: &TranscriptBundle) -> String {
+    render_markdown_blocks(bundle).concat()
+}
+
+/// The markdown of each block of the transcript, which can be cut short
+/// between blocks without leaving a code fence or `<details>` open
+pub fn render_markdown_blocks(bundle: &TranscriptBundle) -> Vec<String> {
+    blocks(bundle)
+        .into_iter()
+        .map(|block| match block {
+            Block::Heading(level, text) => {
+                format!("{} {}\n\n", "#".repeat(level as usize), text)
+            }
+            Block::Fields(fields) => {
+                let mut out = String::new();
+                for (name, value) in fields {
+                    out.push_str(&format!("- **{name}:** {value}\n"));
+                }
+                out.push('\n');
+                out
+            }
+            Block::Text(Some(label), text) if text.is_empty() => format!("**{label}:**\n\n"),
+            Block::Text(Some(label), text) => format!("**{label}:** {text}\n\n"),
+            Block::Text(None, text) => format!("{text}\n\n"),
+            Block::Code(lang, code) => fenced(lang, &code),
+            Block::Details(summary, text) => format!(
+                "<details>\n<summary>{summary}</summary>\n\n{}</details>\n\n",
+                fenced("", &text)
+            ),
+            Block::Quote(text) => {
+                let mut out = String::new();
+                for line in text.lines() {
+                    out.push_str(&format!("> {line}\n"));
+                }
+                out.push('\n');
+                out
+            }
+        })
+        .collect()
+}
+
+/// A code block fenced with more backticks than the code contains
+fn fenced(lang: &str, code: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in code.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest.max(2) + 1);
+    let newline = if code.ends_with('\n') { "" } else { "\n" };
+    format!("{fence}{lang}\n{code}{newline}{fence}\n\n")
+}
+
+/// A standalone HTML page
+pub fn render_html(bundle: &TranscriptBundle) -> String {
+    let mut body = String::new();
+    for block in blocks(bundle) {
+        match block {
+            Block::Heading(level, text) => {
+                body.push_str(&format!("<h{level}>{}</h{level}>\n", escape_html(&text)))
+            }
+            Block::Fields(fields) => {
+                body.push_str("<dl>\n");
+                for (name, value) in fields {
+                    body.push_str(&format!(
+                        "<dt>{name}</dt><dd>{}</dd>\n",
+                        escape_html(&value)
+                    ));
+                }
+                body.push_str("</dl>\n");
+            }
+            Block::Text(label, text) => {
+                body.push_str("<p>");
+                if let Some(label) = label {
+                    body.push_str(&format!("<strong>{label}:</strong> "));
+                }
+                body.push_str(&escape_html(&text));
+                body.push_str("</p>\n");
+            }
+            Block::Code(lang, code) => body.push_str(&format!(
+                "<pre class=\"{lang}\"><code>{}</code></pre>\n",
+                escape_html(&code)
+            )),
+            Block::Details(summary, text) => body.push_str(&format!(
+                "<details><summary>{}</summary><pre><code>{}</code></pre></details>\n",
+                escape_html(&summary),
+                escape_html(&text)
+            )),
+            Block::Quote(text) => body.push_str(&format!(
+                "<blockquote>{}</blockquote>\n",
+                escape_html(&text)
+            )),
+        }
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Transcript: {title}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }}
+p, blockquote {{ white-space: pre-wrap; }}
+pre {{ background: #f6f8fa; padding: 0.75rem; overflow-x: auto; }}
+blockquote {{ color: #57606a; border-left: 4px solid #d0d7de; margin: 0; padding-left: 1rem; }}
+dt {{ font-weight: bold; float: left; clear: left; margin-right: 0.5rem; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        title = escape_html(&bundle.task.title),
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Append the transcript blocks to a PR body in a collapsed section, leaving out
+/// the blocks after the first that would make the body too long for GitHub
+pub fn append_to_pr_body(body: Option<&str>, transcript_blocks: &[String]) -> String {
+    let mut out = body.unwrap_or_default().trim_end().to_string();
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    let open = "<details>\n<summary>Agent transcript</summary>\n\n";
+    let close = "\n</details>\n";
+    let truncated_note = "\n\n_Transcript truncated._\n";
+
+    let mut budget = MAX_PR_BODY_CHARS
+        .saturating_sub(out.chars().count() + open.len() + close.len() + truncated_note.len());
+    out.push_str(open);
+    for block in transcript_blocks {
+        let len = block.chars().count();
+        if len > budget {
+            out.push_str(truncated_note);
+            break;
+        }
+        out.push_str(block);
+        budget -= len;
+    }
+    out.push_str(close);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fences_code_containing_backticks() {
+        assert_eq!(fenced("sh", "echo hi"), "```sh\necho hi\n```\n\n");
+        assert_eq!(
+            fenced("", "```\ncode\n```\n"),
+            "````\n```\ncode\n```\n````\n\n"
+        );
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            escape_html("<script>alert(\"&\")</script>"),
+            "&lt;script&gt;alert(&quot;&amp;&quot;)&lt;/script&gt;"
+        );
+    }
+
+    #[test]
+    fn appends_transcript_to_pr_body() {
+        let body = append_to_pr_body(
+            Some("Fixes the login form\n"),
+            &["# Transcript\n".to_string()],
+        );
+        assert!(body.starts_with("Fixes the login form\n\n<details>"));
+        assert!(body.contains("# Transcript\n"));
+        assert!(body.ends_with("</details>\n"));
+    }
+
+    #[test]
+    fn truncates_long_transcripts() {
+        let transcript = vec!["x".repeat(MAX_PR_BODY_CHARS * 2)];
+        let body = append_to_pr_body(None, &transcript);
+        assert!(body.chars().count() <= MAX_PR_BODY_CHARS);
+        assert!(body.contains("_Transcript truncated._"));
+    }
+
+    #[test]
+    fn truncates_between_blocks() {
+        let block = fenced("sh", &"echo hi\n".repeat(1000));
+        let transcript = vec![block.clone(); 20];
+        let body = append_to_pr_body(None, &transcript);
+        assert!(body.chars().count() <= MAX_PR_BODY_CHARS);
+        assert!(body.contains("_Transcript truncated._"));
+        // Only whole code blocks, so every fence is closed
+        assert_eq!(body.matches("```").count() % 2, 0);
+        assert!(body.contains(&format!("{block}\n\n_Transcript truncated._")));
+    }
+}
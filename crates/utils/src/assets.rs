@@ -32,6 +32,10 @@ pub fn profiles_path() -> std::path::PathBuf {
     asset_dir().join("profiles.json")
 }
 
+pub fn execution_logs_dir() -> std::path::PathBuf {
+    asset_dir().join("execution_logs")
+}
+
 #[derive(RustEmbed)]
 #[folder = "../../assets/sounds"]
 pub struct SoundAssets;
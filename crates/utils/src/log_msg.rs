@@ -7,6 +7,7 @@ pub const EV_STDERR: &str = "stderr";
 pub const EV_JSON_PATCH: &str = "json_patch";
 pub const EV_SESSION_ID: &str = "session_id";
 pub const EV_FINISHED: &str = "finished";
+pub const EV_EVICTED: &str = "evicted";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum LogMsg {
@@ -15,6 +16,8 @@ pub enum LogMsg {
     JsonPatch(Patch),
     SessionId(String),
     Finished,
+    /// Stands in for this many messages that are no longer available
+    Evicted(usize),
 }
 
 impl LogMsg {
@@ -25,6 +28,7 @@ impl LogMsg {
             LogMsg::JsonPatch(_) => EV_JSON_PATCH,
             LogMsg::SessionId(_) => EV_SESSION_ID,
             LogMsg::Finished => EV_FINISHED,
+            LogMsg::Evicted(_) => EV_EVICTED,
         }
     }
 
@@ -38,6 +42,7 @@ impl LogMsg {
             }
             LogMsg::SessionId(s) => Event::default().event(EV_SESSION_ID).data(s.clone()),
             LogMsg::Finished => Event::default().event(EV_FINISHED).data(""),
+            LogMsg::Evicted(count) => Event::default().event(EV_EVICTED).data(count.to_string()),
         }
     }
 
@@ -53,6 +58,7 @@ impl LogMsg {
             }
             LogMsg::SessionId(s) => EV_SESSION_ID.len() + s.len() + OVERHEAD,
             LogMsg::Finished => EV_FINISHED.len() + OVERHEAD,
+            LogMsg::Evicted(_) => EV_EVICTED.len() + OVERHEAD,
         }
     }
 }
@@ -1,18 +1,26 @@
 use std::{
     collections::VecDeque,
+    fs,
+    io::Write,
+    path::PathBuf,
     sync::{Arc, RwLock},
     time::Instant,
 };
 
 use axum::response::sse::Event;
-use futures::{StreamExt, TryStreamExt, future};
-use tokio::{sync::broadcast, task::JoinHandle};
-use tokio_stream::wrappers::BroadcastStream;
+use futures::{StreamExt, TryStreamExt, future, stream::BoxStream};
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+use uuid::Uuid;
 
-use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
+use crate::{log_msg::LogMsg, redaction::Redactor, stream_lines::LinesStreamExt};
 
-// 100 MB Limit
-const HISTORY_BYTES: usize = 100000 * 1024;
+// Messages are kept in memory up to this size, older ones are spilled to disk
+const MEMORY_BYTES: usize = 16 * 1024 * 1024;
+// Size of each on-disk segment
+const SEGMENT_BYTES: usize = 4 * 1024 * 1024;
 
 #[derive(Debug)]
 pub struct MemoryMetrics {
@@ -21,6 +29,9 @@ pub struct MemoryMetrics {
     pub oldest_message_age_secs: u64,
     pub broadcast_receivers: usize,
     pub history_capacity: usize,
+    pub spilled_messages: usize,
+    pub spilled_bytes: usize,
+    pub evicted_messages: usize,
 }
 
 #[derive(Clone)]
@@ -30,15 +41,208 @@ struct StoredMsg {
     timestamp: Instant,
 }
 
+/// Messages spilled to a JSONL file, one message per line
+struct Segment {
+    path: PathBuf,
+    first_seq: usize,
+    len: usize,
+    bytes: usize,
+    newest: Instant,
+    /// The messages while the file is still being written
+    pending: Option<Arc<Vec<LogMsg>>>,
+}
+
+impl Segment {
+    fn end_seq(&self) -> usize {
+        self.first_seq + self.len
+    }
+}
+
+/// A segment taken out of memory, to be written without holding the lock
+struct SegmentWrite {
+    dir: PathBuf,
+    path: PathBuf,
+    first_seq: usize,
+    msgs: Arc<Vec<LogMsg>>,
+}
+
+impl SegmentWrite {
+    fn write(&self) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut file = std::io::BufWriter::new(fs::File::create(&self.path)?);
+        for msg in self.msgs.iter() {
+            serde_json::to_writer(&mut file, msg)?;
+            file.write_all(b"\n")?;
+        }
+        file.flush()
+    }
+
+    /// Write the segment and let readers use the file from then on. Messages
+    /// that could not be written are reported as evicted.
+    fn run(self, inner: &RwLock<Inner>) {
+        let result = self.write();
+        let mut inner = inner.write().unwrap();
+        let index = inner
+            .segments
+            .iter()
+            .position(|segment| segment.first_seq == self.first_seq);
+        match (result, index) {
+            (Ok(()), Some(index)) => inner.segments[index].pending = None,
+            // Cleaned up while it was being written
+            (Ok(()), None) => {
+                let _ = fs::remove_file(&self.path);
+            }
+            (Err(e), index) => {
+                // Subscribers replaying past this point get an `Evicted`
+                // marker in place of the messages
+                tracing::warn!(
+                    "Failed to spill {} log messages to disk: {}",
+                    self.msgs.len(),
+                    e
+                );
+                if let Some(index) = index {
+                    inner.segments.remove(index);
+                    inner.evicted += self.msgs.len();
+                }
+                let _ = fs::remove_file(&self.path);
+            }
+        }
+    }
+}
+
+/// Where the message with a given sequence number can be found
+enum Location {
+    /// Missing up to this sequence number
+    Evicted(usize),
+    Segment {
+        path: PathBuf,
+        first_seq: usize,
+        end_seq: usize,
+    },
+    Memory(Vec<LogMsg>),
+}
+
 struct Inner {
+    /// The most recent messages, starting at `memory_start`
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    memory_start: usize,
+    /// Older messages, in order. Gaps between segments were evicted.
+    segments: Vec<Segment>,
+    spill_dir: Option<PathBuf>,
+    evicted: usize,
+    memory_limit: usize,
+    segment_limit: usize,
     _created_at: Instant,
 }
 
+impl Inner {
+    fn next_seq(&self) -> usize {
+        self.memory_start + self.history.len()
+    }
+
+    fn spilled_bytes(&self) -> usize {
+        self.segments.iter().map(|segment| segment.bytes).sum()
+    }
+
+    /// Move the oldest messages into a new segment. They stay readable from
+    /// memory until the returned write has put them on disk.
+    fn spill(&mut self) -> Option<SegmentWrite> {
+        let mut bytes = 0;
+        let mut count = 0;
+        while count < self.history.len() && bytes < self.segment_limit {
+            bytes += self.history[count].bytes;
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+
+        let first_seq = self.memory_start;
+        let spilled: Vec<StoredMsg> = self.history.drain(..count).collect();
+        self.total_bytes = self.total_bytes.saturating_sub(bytes);
+        self.memory_start += count;
+
+        let dir = self
+            .spill_dir
+            .get_or_insert_with(|| {
+                std::env::temp_dir()
+                    .join("vibe-kanban-msg-store")
+                    .join(Uuid::new_v4().to_string())
+            })
+            .clone();
+        let path = dir.join(format!("{first_seq:012}.jsonl"));
+        let newest = spilled
+            .last()
+            .map_or_else(Instant::now, |msg| msg.timestamp);
+        let msgs = Arc::new(spilled.into_iter().map(|stored| stored.msg).collect());
+        self.segments.push(Segment {
+            path: path.clone(),
+            first_seq,
+            len: count,
+            bytes,
+            newest,
+            pending: Some(Arc::clone(&msgs)),
+        });
+        Some(SegmentWrite {
+            dir,
+            path,
+            first_seq,
+            msgs,
+        })
+    }
+
+    fn locate(&self, seq: usize, end: usize) -> Location {
+        for segment in &self.segments {
+            if seq < segment.first_seq {
+                return Location::Evicted(segment.first_seq);
+            }
+            if seq < segment.end_seq() {
+                if let Some(msgs) = &segment.pending {
+                    return Location::Memory(
+                        msgs.iter()
+                            .skip(seq - segment.first_seq)
+                            .take(end.min(segment.end_seq()).saturating_sub(seq))
+                            .cloned()
+                            .collect(),
+                    );
+                }
+                return Location::Segment {
+                    path: segment.path.clone(),
+                    first_seq: segment.first_seq,
+                    end_seq: segment.end_seq(),
+                };
+            }
+        }
+        if seq < self.memory_start {
+            return Location::Evicted(self.memory_start);
+        }
+        Location::Memory(
+            self.history
+                .iter()
+                .skip(seq - self.memory_start)
+                .take(end.saturating_sub(seq))
+                .map(|stored| stored.msg.clone())
+                .collect(),
+        )
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.spill_dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Messages of a push-only log with a bounded in-memory tail. Older messages
+/// are spilled to disk so the whole history stays replayable; messages that
+/// could not be kept are replaced by `LogMsg::Evicted` when replaying.
 pub struct MsgStore {
-    inner: RwLock<Inner>,
+    inner: Arc<RwLock<Inner>>,
     sender: broadcast::Sender<LogMsg>,
+    redactor: Option<Arc<Redactor>>,
 }
 
 impl Default for MsgStore {
@@ -49,35 +253,74 @@ impl Default for MsgStore {
 
 impl MsgStore {
     pub fn new() -> Self {
+        Self::with_limits(MEMORY_BYTES, SEGMENT_BYTES)
+    }
+
+    /// A store that masks secrets in pushed output before it is kept or sent
+    /// to listeners
+    pub fn with_redactor(redactor: Arc<Redactor>) -> Self {
+        let mut store = Self::new();
+        if !redactor.is_empty() {
+            store.redactor = Some(redactor);
+        }
+        store
+    }
+
+    fn with_limits(memory_limit: usize, segment_limit: usize) -> Self {
         let (sender, _) = broadcast::channel(10000);
         Self {
-            inner: RwLock::new(Inner {
+            inner: Arc::new(RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                memory_start: 0,
+                segments: Vec::new(),
+                spill_dir: None,
+                evicted: 0,
+                memory_limit,
+                segment_limit,
                 _created_at: Instant::now(),
-            }),
+            })),
             sender,
+            redactor: None,
         }
     }
 
     pub fn push(&self, msg: LogMsg) {
-        let _ = self.sender.send(msg.clone()); // live listeners
+        let msg = match &self.redactor {
+            Some(redactor) => redactor.redact_msg(msg),
+            None => msg,
+        };
         let bytes = msg.approx_bytes();
 
-        let mut inner = self.inner.write().unwrap();
-        while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
-            if let Some(front) = inner.history.pop_front() {
-                inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
-            } else {
-                break;
+        let mut writes = Vec::new();
+        {
+            let mut inner = self.inner.write().unwrap();
+            inner.history.push_back(StoredMsg {
+                msg: msg.clone(),
+                bytes,
+                timestamp: Instant::now(),
+            });
+            inner.total_bytes = inner.total_bytes.saturating_add(bytes);
+            while inner.total_bytes > inner.memory_limit && inner.history.len() > 1 {
+                writes.extend(inner.spill());
+            }
+
+            // Sent while holding the lock so live listeners see messages in the
+            // same order as their sequence numbers
+            let _ = self.sender.send(msg);
+        }
+
+        // Segments are written outside the lock, on the blocking pool when
+        // there is a runtime
+        for write in writes {
+            let inner = self.inner.clone();
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn_blocking(move || write.run(&inner));
+                }
+                Err(_) => write.run(&inner),
             }
         }
-        inner.history.push_back(StoredMsg {
-            msg,
-            bytes,
-            timestamp: Instant::now(),
-        });
-        inner.total_bytes = inner.total_bytes.saturating_add(bytes);
     }
 
     // Convenience
@@ -102,14 +345,69 @@ impl MsgStore {
     pub fn get_receiver(&self) -> broadcast::Receiver<LogMsg> {
         self.sender.subscribe()
     }
+
+    /// The full history, reading spilled messages back from disk once the
+    /// lock is released
     pub fn get_history(&self) -> Vec<LogMsg> {
-        self.inner
-            .read()
-            .unwrap()
-            .history
-            .iter()
-            .map(|s| s.msg.clone())
-            .collect()
+        let (locations, memory) = {
+            let inner = self.inner.read().unwrap();
+            let mut locations = Vec::new();
+            let mut seq = 0;
+            for segment in &inner.segments {
+                if segment.first_seq > seq {
+                    locations.push(Location::Evicted(segment.first_seq));
+                }
+                locations.push(match &segment.pending {
+                    Some(msgs) => Location::Memory(msgs.to_vec()),
+                    None => Location::Segment {
+                        path: segment.path.clone(),
+                        first_seq: segment.first_seq,
+                        end_seq: segment.end_seq(),
+                    },
+                });
+                seq = segment.end_seq();
+            }
+            if inner.memory_start > seq {
+                locations.push(Location::Evicted(inner.memory_start));
+            }
+            let memory: Vec<LogMsg> = inner
+                .history
+                .iter()
+                .map(|stored| stored.msg.clone())
+                .collect();
+            (locations, memory)
+        };
+
+        let mut history = Vec::new();
+        let mut seq = 0;
+        for location in locations {
+            match location {
+                Location::Evicted(until) => {
+                    history.push(LogMsg::Evicted(until - seq));
+                    seq = until;
+                }
+                Location::Memory(msgs) => {
+                    seq += msgs.len();
+                    history.extend(msgs);
+                }
+                Location::Segment {
+                    path,
+                    first_seq,
+                    end_seq,
+                } => {
+                    match read_segment(&path) {
+                        Ok(msgs) => history.extend(msgs),
+                        Err(e) => {
+                            tracing::warn!("Failed to read spilled log messages: {}", e);
+                            history.push(LogMsg::Evicted(end_seq - first_seq));
+                        }
+                    }
+                    seq = end_seq;
+                }
+            }
+        }
+        history.extend(memory);
+        history
     }
 
     /// Get memory usage statistics
@@ -129,19 +427,33 @@ impl MsgStore {
             oldest_message_age_secs,
             broadcast_receivers: self.sender.receiver_count(),
             history_capacity: inner.history.capacity(),
+            spilled_messages: inner.segments.iter().map(|segment| segment.len).sum(),
+            spilled_bytes: inner.spilled_bytes(),
+            evicted_messages: inner.evicted,
         }
     }
 
-    /// Force cleanup of old messages beyond age limit
+    /// Force cleanup of old messages beyond age limit, including spilled ones.
+    /// Replaying the history reports them as evicted.
     pub fn cleanup_old_messages(&self, max_age_secs: u64) {
         let mut inner = self.inner.write().unwrap();
         let now = Instant::now();
         let mut cleaned_count = 0;
 
+        while let Some(segment) = inner.segments.first() {
+            if now.duration_since(segment.newest).as_secs() <= max_age_secs {
+                break;
+            }
+            let segment = inner.segments.remove(0);
+            let _ = fs::remove_file(&segment.path);
+            cleaned_count += segment.len;
+        }
+
         while let Some(front) = inner.history.front() {
             if now.duration_since(front.timestamp).as_secs() > max_age_secs {
                 if let Some(old_msg) = inner.history.pop_front() {
                     inner.total_bytes = inner.total_bytes.saturating_sub(old_msg.bytes);
+                    inner.memory_start += 1;
                     cleaned_count += 1;
                 }
             } else {
@@ -149,22 +461,45 @@ impl MsgStore {
             }
         }
 
+        inner.evicted += cleaned_count;
         if cleaned_count > 0 {
             tracing::debug!("Cleaned up {} old messages", cleaned_count);
         }
     }
 
-    /// History then live, as `LogMsg`.
-    pub fn history_plus_stream(
-        &self,
-    ) -> futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>> {
-        let (history, rx) = (self.get_history(), self.get_receiver());
-
-        let hist = futures::stream::iter(history.into_iter().map(Ok::<_, std::io::Error>));
-        let live = BroadcastStream::new(rx)
-            .filter_map(|res| async move { res.ok().map(Ok::<_, std::io::Error>) });
+    /// History then live, as `LogMsg`. Spilled history is read back lazily, and
+    /// messages a slow subscriber missed on the live channel are replayed from the
+    /// store, so subscribers only see a gap, as `LogMsg::Evicted`, for messages
+    /// the store no longer has.
+    pub fn history_plus_stream(&self) -> BoxStream<'static, Result<LogMsg, std::io::Error>> {
+        // Pushes hold the write lock while sending, so the receiver starts
+        // exactly at `end`
+        let (end, rx) = {
+            let inner = self.inner.read().unwrap();
+            (inner.next_seq(), self.sender.subscribe())
+        };
+
+        let history = replay(self.inner.clone(), 0, end);
+        let live = futures::stream::unfold(
+            (self.inner.clone(), rx, end),
+            |(inner, mut rx, seq)| async move {
+                match rx.recv().await {
+                    Ok(msg) => Some((
+                        futures::stream::once(future::ready(Ok(msg))).boxed(),
+                        (inner, rx, seq + 1),
+                    )),
+                    Err(RecvError::Lagged(missed)) => {
+                        let missed = missed as usize;
+                        let recovered = replay(inner.clone(), seq, seq + missed);
+                        Some((recovered, (inner, rx, seq + missed)))
+                    }
+                    Err(RecvError::Closed) => None,
+                }
+            },
+        )
+        .flatten();
 
-        Box::pin(hist.chain(live))
+        Box::pin(history.chain(live))
     }
 
     pub fn stdout_chunked_stream(
@@ -236,12 +571,161 @@ impl MsgStore {
     pub fn log_memory_stats(&self) {
         let metrics = self.get_memory_metrics();
         tracing::info!(
-            "MsgStore metrics - Messages: {}, Bytes: {}, Oldest: {}s, Receivers: {}, Capacity: {}",
+            "MsgStore metrics - Messages: {}, Bytes: {}, Oldest: {}s, Receivers: {}, Capacity: {}, Spilled: {} ({} bytes), Evicted: {}",
             metrics.total_messages,
             metrics.total_bytes,
             metrics.oldest_message_age_secs,
             metrics.broadcast_receivers,
-            metrics.history_capacity
+            metrics.history_capacity,
+            metrics.spilled_messages,
+            metrics.spilled_bytes,
+            metrics.evicted_messages
+        );
+    }
+}
+
+fn read_segment(path: &PathBuf) -> std::io::Result<Vec<LogMsg>> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(|line| serde_json::from_str(line).map_err(std::io::Error::from))
+        .collect()
+}
+
+/// Messages `from..to` of a store, reading one segment at a time
+fn replay(
+    inner: Arc<RwLock<Inner>>,
+    from: usize,
+    to: usize,
+) -> BoxStream<'static, Result<LogMsg, std::io::Error>> {
+    futures::stream::unfold(from, move |seq| {
+        let inner = inner.clone();
+        async move {
+            if seq >= to {
+                return None;
+            }
+            let location = inner.read().unwrap().locate(seq, to);
+            let (msgs, next) = match location {
+                Location::Evicted(until) => {
+                    let until = until.min(to);
+                    (vec![LogMsg::Evicted(until - seq)], until)
+                }
+                Location::Segment {
+                    path,
+                    first_seq,
+                    end_seq,
+                } => {
+                    let until = end_seq.min(to);
+                    let msgs = tokio::fs::read_to_string(&path).await.and_then(|contents| {
+                        contents
+                            .lines()
+                            .skip(seq - first_seq)
+                            .take(until - seq)
+                            .map(|line| serde_json::from_str(line).map_err(std::io::Error::from))
+                            .collect::<Result<Vec<LogMsg>, _>>()
+                    });
+                    match msgs {
+                        Ok(msgs) => (msgs, until),
+                        Err(e) => {
+                            tracing::warn!("Failed to read spilled log messages: {}", e);
+                            (vec![LogMsg::Evicted(until - seq)], until)
+                        }
+                    }
+                }
+                Location::Memory(msgs) => {
+                    let next = seq + msgs.len();
+                    if msgs.is_empty() {
+                        return None;
+                    }
+                    (msgs, next)
+                }
+            };
+            Some((futures::stream::iter(msgs.into_iter().map(Ok)), next))
+        }
+    })
+    .flatten()
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stdout_messages(msgs: &[LogMsg]) -> Vec<String> {
+        msgs.iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn push_lines(store: &MsgStore, count: usize) -> Vec<String> {
+        let lines: Vec<String> = (0..count).map(|i| format!("line {i}\n")).collect();
+        for line in &lines {
+            store.push_stdout(line.clone());
+        }
+        lines
+    }
+
+    #[test]
+    fn spilled_history_is_complete() {
+        let store = MsgStore::with_limits(200, 100);
+        let lines = push_lines(&store, 50);
+
+        let metrics = store.get_memory_metrics();
+        assert!(metrics.spilled_messages > 0);
+        assert!(metrics.total_bytes <= 200);
+        assert_eq!(stdout_messages(&store.get_history()), lines);
+    }
+
+    #[tokio::test]
+    async fn stream_replays_spilled_history_then_live() {
+        let store = MsgStore::with_limits(200, 100);
+        let mut lines = push_lines(&store, 50);
+
+        let stream = store.history_plus_stream();
+        store.push_stdout("live\n");
+        store.push_finished();
+        lines.push("live\n".to_string());
+
+        let msgs: Vec<LogMsg> = stream
+            .take_while(|msg| future::ready(!matches!(msg, Ok(LogMsg::Finished))))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(stdout_messages(&msgs), lines);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn history_is_complete_while_segments_are_written() {
+        let store = MsgStore::with_limits(200, 100);
+        let lines = push_lines(&store, 50);
+
+        assert_eq!(stdout_messages(&store.get_history()), lines);
+    }
+
+    #[test]
+    fn redacts_pushed_output() {
+        let redactor = Redactor::new(vec!["supersecretvalue".to_string()], Vec::new());
+        let store = MsgStore::with_redactor(Arc::new(redactor));
+        store.push_stdout("token=supersecretvalue\n");
+
+        assert_eq!(
+            stdout_messages(&store.get_history()),
+            vec!["token=[REDACTED]\n".to_string()]
         );
     }
+
+    #[test]
+    fn cleanup_reports_evicted_messages() {
+        let store = MsgStore::with_limits(200, 100);
+        push_lines(&store, 50);
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        store.cleanup_old_messages(0);
+        store.push_stdout("after\n");
+
+        let history = store.get_history();
+        assert!(matches!(history[0], LogMsg::Evicted(50)));
+        assert_eq!(stdout_messages(&history), vec!["after\n".to_string()]);
+    }
 }
@@ -1,5 +1,12 @@
 //! Cross-platform shell command utilities
 
+use std::{future::Future, path::PathBuf};
+
+tokio::task_local! {
+    /// File that shell commands spawned in this scope record their exit status in
+    static EXIT_STATUS_FILE: PathBuf;
+}
+
 /// Returns the appropriate shell command and argument for the current platform.
 ///
 /// Returns (shell_program, shell_arg) where:
@@ -18,9 +25,61 @@ pub fn get_shell_command() -> (&'static str, &'static str) {
     }
 }
 
+/// Run `f` with shell commands wrapped by [`shell_command_line`] recording their
+/// exit status in `path`
+pub async fn with_exit_status_file<F: Future>(path: PathBuf, f: F) -> F::Output {
+    EXIT_STATUS_FILE.scope(path, f).await
+}
+
+/// Returns the command line to pass to the shell from [`get_shell_command`].
+///
+/// Inside [`with_exit_status_file`] on unix the command runs in a subshell and
+/// the shell writes its exit status to the file once it finishes, so the status
+/// can be read by a process that didn't spawn it.
+pub fn shell_command_line(command: &str) -> String {
+    if cfg!(windows) {
+        return command.to_string();
+    }
+    EXIT_STATUS_FILE
+        .try_with(|path| {
+            let path = path.to_string_lossy().replace('\'', "'\\''");
+            format!("(\n{command}\n)\nstatus=$?\necho $status > '{path}'\nexit $status")
+        })
+        .unwrap_or_else(|_| command.to_string())
+}
+
 /// Resolves the full path of an executable using the system's PATH environment variable.
 pub fn resolve_executable_path(executable: &str) -> Option<String> {
     which::which(executable)
         .ok()
         .map(|p| p.to_string_lossy().to_string())
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shell_command_line_records_exit_status() {
+        let path = std::env::temp_dir().join(format!("exit-status-{}", std::process::id()));
+        let status = with_exit_status_file(path.clone(), async {
+            let (shell_cmd, shell_arg) = get_shell_command();
+            tokio::process::Command::new(shell_cmd)
+                .arg(shell_arg)
+                .arg(shell_command_line("echo hi >/dev/null; exit 3"))
+                .status()
+                .await
+                .unwrap()
+        })
+        .await;
+
+        assert_eq!(status.code(), Some(3));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().trim(), "3");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_shell_command_line_unwrapped_outside_scope() {
+        assert_eq!(shell_command_line("true"), "true");
+    }
+}
@@ -4,6 +4,7 @@ import {
   Bot,
   Brain,
   CheckSquare,
+  ChevronDown,
   ChevronRight,
   ChevronUp,
   Edit,
@@ -26,8 +27,64 @@ type Props = {
   entry: NormalizedEntry;
   expansionKey: string;
   diffDeletable?: boolean;
+  // Scopes the collapsed state of subagent tasks, see subagentCollapseKey
+  processId?: string;
 };
 
+type TaskCreate = Extract<ActionType, { action: 'task_create' }>;
+
+export const subagentCollapseKey = (processId: string, taskId: string) =>
+  `subagent:${processId}:${taskId}`;
+
+const formatDuration = (ms: number) => {
+  const seconds = Math.round(ms / 1000);
+  if (seconds < 60) return `${seconds}s`;
+  return `${Math.floor(seconds / 60)}m ${seconds % 60}s`;
+};
+
+function SubagentTaskHeader({
+  task,
+  processId,
+}: {
+  task: TaskCreate;
+  processId?: string;
+}) {
+  const [isCollapsed, setIsCollapsed] = useExpandable(
+    subagentCollapseKey(processId ?? '', task.id ?? ''),
+    false
+  );
+  const statusClassName =
+    task.status === 'failed'
+      ? 'text-destructive'
+      : task.status === 'completed'
+        ? 'text-green-600'
+        : 'text-muted-foreground';
+
+  return (
+    <div className="mt-1 flex items-center gap-2 text-xs">
+      {task.id && processId && (
+        <button
+          onClick={() => setIsCollapsed()}
+          className="inline-flex items-center gap-1 text-muted-foreground hover:text-foreground transition-colors"
+        >
+          {isCollapsed ? (
+            <ChevronRight className="h-3 w-3" />
+          ) : (
+            <ChevronDown className="h-3 w-3" />
+          )}
+          {isCollapsed ? 'Show subagent' : 'Hide subagent'}
+        </button>
+      )}
+      <span className={statusClassName}>{task.status}</span>
+      {task.duration_ms != null && (
+        <span className="text-muted-foreground">
+          {formatDuration(Number(task.duration_ms))}
+        </span>
+      )}
+    </div>
+  );
+}
+
 const getEntryIcon = (entryType: NormalizedEntryType) => {
   if (entryType.type === 'user_message') {
     return <User className="h-4 w-4 text-blue-600" />;
@@ -132,7 +189,7 @@ const shouldRenderMarkdown = (entryType: NormalizedEntryType) => {
 
 import { useExpandable } from '@/stores/useExpandableStore';
 
-function DisplayConversationEntry({ entry, expansionKey }: Props) {
+function DisplayConversationEntry({ entry, expansionKey, processId }: Props) {
   const isErrorMessage = entry.entry_type.type === 'error_message';
   const hasMultipleLines = isErrorMessage && entry.content.includes('\n');
   const [isExpanded, setIsExpanded] = useExpandable(
@@ -149,8 +206,19 @@ function DisplayConversationEntry({ entry, expansionKey }: Props) {
         >)
       : null;
 
+  const subagentTask =
+    entry.entry_type.type === 'tool_use' &&
+    entry.entry_type.action_type.action === 'task_create'
+      ? (entry.entry_type.action_type as TaskCreate)
+      : null;
+
+  // Entries produced by a subagent are nested under its task
+  const className = entry.parent_id
+    ? 'px-4 py-1 ml-7 border-l-2 border-muted'
+    : 'px-4 py-1';
+
   return (
-    <div className="px-4 py-1">
+    <div className={className}>
       <div className="flex items-start gap-3">
         <div className="flex-shrink-0 mt-1">
           {isErrorMessage && hasMultipleLines ? (
@@ -213,6 +281,10 @@ function DisplayConversationEntry({ entry, expansionKey }: Props) {
             </div>
           )}
 
+          {subagentTask && (
+            <SubagentTaskHeader task={subagentTask} processId={processId} />
+          )}
+
           {fileEdit &&
             Array.isArray(fileEdit.changes) &&
             fileEdit.changes.map((change, idx) => {
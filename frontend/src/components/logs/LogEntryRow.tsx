@@ -45,6 +45,7 @@ function LogEntryRow({
                 entry={entry.payload as NormalizedEntry}
                 expansionKey={`${entry.processId}:${index}`}
                 diffDeletable={false}
+                processId={entry.processId}
               />
             );
           case 'process_start':
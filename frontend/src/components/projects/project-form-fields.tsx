@@ -1,8 +1,17 @@
 import { Label } from '@/components/ui/label';
 import { Input } from '@/components/ui/input';
 import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
 import { Alert, AlertDescription } from '@/components/ui/alert';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { AlertCircle, Folder } from 'lucide-react';
+import type { MergeStrategy } from 'shared/types';
 import {
   createScriptPlaceholderStrategy,
   ScriptPlaceholderContext,
@@ -10,6 +19,13 @@ import {
 import { useUserSystem } from '@/components/config-provider';
 import { CopyFilesField } from './copy-files-field';
 
+const MERGE_STRATEGIES: { value: MergeStrategy; label: string }[] = [
+  { value: 'squash', label: 'Squash' },
+  { value: 'merge_commit', label: 'Merge commit' },
+  { value: 'rebase', label: 'Rebase and fast-forward' },
+  { value: 'fast_forward', label: 'Fast-forward only' },
+];
+
 interface ProjectFormFieldsProps {
   isEditing: boolean;
   repoMode: 'existing' | 'new';
@@ -31,6 +47,12 @@ interface ProjectFormFieldsProps {
   setCleanupScript: (script: string) => void;
   copyFiles: string;
   setCopyFiles: (files: string) => void;
+  mergeStrategy: MergeStrategy;
+  setMergeStrategy: (strategy: MergeStrategy) => void;
+  syncRemoteBase: boolean;
+  setSyncRemoteBase: (sync: boolean) => void;
+  maxConcurrentAgents: string;
+  setMaxConcurrentAgents: (limit: string) => void;
   error: string;
   projectId?: string;
 }
@@ -56,6 +78,12 @@ export function ProjectFormFields({
   setCleanupScript,
   copyFiles,
   setCopyFiles,
+  mergeStrategy,
+  setMergeStrategy,
+  syncRemoteBase,
+  setSyncRemoteBase,
+  maxConcurrentAgents,
+  setMaxConcurrentAgents,
   error,
   projectId,
 }: ProjectFormFieldsProps) {
@@ -270,6 +298,64 @@ export function ProjectFormFields({
         </p>
       </div>
 
+      <div className="space-y-2">
+        <Label htmlFor="merge-strategy">Merge Strategy</Label>
+        <Select
+          value={mergeStrategy}
+          onValueChange={(value) => setMergeStrategy(value as MergeStrategy)}
+        >
+          <SelectTrigger id="merge-strategy">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {MERGE_STRATEGIES.map(({ value, label }) => (
+              <SelectItem key={value} value={value}>
+                {label}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="text-sm text-muted-foreground">
+          How task branches are merged into their base branch. Squash creates a
+          single commit, the other strategies keep the agent's commits.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <div className="flex items-center space-x-2">
+          <Checkbox
+            id="sync-remote-base"
+            checked={syncRemoteBase}
+            onCheckedChange={(checked: boolean) =>
+              setSyncRemoteBase(checked === true)
+            }
+          />
+          <Label htmlFor="sync-remote-base">Sync with remote</Label>
+        </div>
+        <p className="text-sm text-muted-foreground">
+          Fetch origin before creating a worktree or rebasing, and start
+          attempts from origin's base branch instead of the local one. Needs a
+          GitHub token to fetch.
+        </p>
+      </div>
+
+      <div className="space-y-2">
+        <Label htmlFor="max-concurrent-agents">Max concurrent agents</Label>
+        <Input
+          id="max-concurrent-agents"
+          type="number"
+          min={1}
+          step={1}
+          value={maxConcurrentAgents}
+          onChange={(e) => setMaxConcurrentAgents(e.target.value)}
+          placeholder="Use the global limit"
+        />
+        <p className="text-sm text-muted-foreground">
+          Coding agents of this project that may run at once. Further attempts
+          wait in the queue. Leave empty to only apply the global limit.
+        </p>
+      </div>
+
       {error && (
         <Alert variant="destructive">
           <AlertCircle className="h-4 w-4" />
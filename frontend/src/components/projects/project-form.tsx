@@ -12,7 +12,12 @@ import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { FolderPicker } from '@/components/ui/folder-picker';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
 import { ProjectFormFields } from './project-form-fields';
-import { CreateProject, Project, UpdateProject } from 'shared/types';
+import {
+  CreateProject,
+  MergeStrategy,
+  Project,
+  UpdateProject,
+} from 'shared/types';
 import { projectsApi } from '@/lib/api';
 
 interface ProjectFormProps {
@@ -36,6 +41,15 @@ export function ProjectForm({
     project?.cleanup_script ?? ''
   );
   const [copyFiles, setCopyFiles] = useState(project?.copy_files ?? '');
+  const [mergeStrategy, setMergeStrategy] = useState<MergeStrategy>(
+    project?.default_merge_strategy ?? 'squash'
+  );
+  const [syncRemoteBase, setSyncRemoteBase] = useState(
+    project?.sync_remote_base ?? false
+  );
+  const [maxConcurrentAgents, setMaxConcurrentAgents] = useState(
+    project?.max_concurrent_agents?.toString() ?? ''
+  );
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
   const [showFolderPicker, setShowFolderPicker] = useState(false);
@@ -54,6 +68,9 @@ export function ProjectForm({
       setDevScript(project.dev_script ?? '');
       setCleanupScript(project.cleanup_script ?? '');
       setCopyFiles(project.copy_files ?? '');
+      setMergeStrategy(project.default_merge_strategy ?? 'squash');
+      setSyncRemoteBase(project.sync_remote_base);
+      setMaxConcurrentAgents(project.max_concurrent_agents?.toString() ?? '');
     } else {
       setName('');
       setGitRepoPath('');
@@ -61,6 +78,9 @@ export function ProjectForm({
       setDevScript('');
       setCleanupScript('');
       setCopyFiles('');
+      setMergeStrategy('squash');
+      setSyncRemoteBase(false);
+      setMaxConcurrentAgents('');
     }
   }, [project]);
 
@@ -85,9 +105,18 @@ export function ProjectForm({
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
     setError('');
+
+    const maxAgentsInput = maxConcurrentAgents.trim();
+    if (maxAgentsInput && !/^[1-9]\d*$/.test(maxAgentsInput)) {
+      setError('Max concurrent agents must be a whole number of at least 1');
+      return;
+    }
+
     setLoading(true);
 
     try {
+      const maxAgents = maxAgentsInput ? BigInt(maxAgentsInput) : null;
+
       if (isEditing) {
         // Editing existing project (local mode only)
         let finalGitRepoPath = gitRepoPath;
@@ -102,6 +131,9 @@ export function ProjectForm({
           dev_script: devScript.trim() || null,
           cleanup_script: cleanupScript.trim() || null,
           copy_files: copyFiles.trim() || null,
+          max_concurrent_agents: maxAgents,
+          default_merge_strategy: mergeStrategy,
+          sync_remote_base: syncRemoteBase,
         };
 
         await projectsApi.update(project.id, updateData);
@@ -140,6 +172,9 @@ export function ProjectForm({
           dev_script: devScript.trim() || null,
           cleanup_script: cleanupScript.trim() || null,
           copy_files: copyFiles.trim() || null,
+          max_concurrent_agents: maxAgents,
+          default_merge_strategy: mergeStrategy,
+          sync_remote_base: syncRemoteBase,
         };
 
         await projectsApi.create(createData);
@@ -154,6 +189,9 @@ export function ProjectForm({
       setDevScript('');
       setCleanupScript('');
       setCopyFiles('');
+      setMergeStrategy('squash');
+      setSyncRemoteBase(false);
+      setMaxConcurrentAgents('');
       setParentPath('');
       setFolderName('');
     } catch (error) {
@@ -170,12 +208,18 @@ export function ProjectForm({
       setSetupScript(project.setup_script ?? '');
       setDevScript(project.dev_script ?? '');
       setCopyFiles(project.copy_files ?? '');
+      setMergeStrategy(project.default_merge_strategy ?? 'squash');
+      setSyncRemoteBase(project.sync_remote_base);
+      setMaxConcurrentAgents(project.max_concurrent_agents?.toString() ?? '');
     } else {
       setName('');
       setGitRepoPath('');
       setSetupScript('');
       setDevScript('');
       setCopyFiles('');
+      setMergeStrategy('squash');
+      setSyncRemoteBase(false);
+      setMaxConcurrentAgents('');
     }
     setParentPath('');
     setFolderName('');
@@ -228,6 +272,12 @@ export function ProjectForm({
                   setCleanupScript={setCleanupScript}
                   copyFiles={copyFiles}
                   setCopyFiles={setCopyFiles}
+                  mergeStrategy={mergeStrategy}
+                  setMergeStrategy={setMergeStrategy}
+                  syncRemoteBase={syncRemoteBase}
+                  setSyncRemoteBase={setSyncRemoteBase}
+                  maxConcurrentAgents={maxConcurrentAgents}
+                  setMaxConcurrentAgents={setMaxConcurrentAgents}
                   error={error}
                   projectId={(project as any)?.id}
                 />
@@ -339,6 +389,12 @@ export function ProjectForm({
               setCleanupScript={setCleanupScript}
               copyFiles={copyFiles}
               setCopyFiles={setCopyFiles}
+              mergeStrategy={mergeStrategy}
+              setMergeStrategy={setMergeStrategy}
+              syncRemoteBase={syncRemoteBase}
+              setSyncRemoteBase={setSyncRemoteBase}
+              maxConcurrentAgents={maxConcurrentAgents}
+              setMaxConcurrentAgents={setMaxConcurrentAgents}
               error={error}
               projectId={(project as any)?.id}
             />
@@ -138,6 +138,13 @@ export function TaskCard({
             </p>
           </div>
         )}
+        {task.tests_passed != null && task.tests_failed != null && (
+          <p
+            className={`text-xs ${Number(task.tests_failed) > 0 ? 'text-destructive' : 'text-muted-foreground'}`}
+          >
+            tests: {task.tests_passed} passed, {task.tests_failed} failed
+          </p>
+        )}
       </div>
     </KanbanCard>
   );
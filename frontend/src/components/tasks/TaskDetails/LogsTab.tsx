@@ -15,6 +15,7 @@ import {
 import { useProcessesLogs } from '@/hooks/useProcessesLogs';
 import { usePinnedTodos } from '@/hooks/usePinnedTodos';
 import LogEntryRow from '@/components/logs/LogEntryRow';
+import { subagentCollapseKey } from '@/components/NormalizedConversation/DisplayConversationEntry';
 import { PinnedTodoBox } from '@/components/PinnedTodoBox';
 import {
   shouldShowInLogs,
@@ -24,7 +25,8 @@ import {
   getLatestCodingAgent,
   PROCESS_STATUSES,
 } from '@/constants/processes';
-import type { ExecutionProcessStatus } from 'shared/types';
+import type { ExecutionProcessStatus, NormalizedEntry } from 'shared/types';
+import { useExpandableStore } from '@/stores/useExpandableStore';
 
 // Helper functions
 function addAll<T>(set: Set<T>, items: T[]): Set<T> {
@@ -241,14 +243,22 @@ function LogsTab() {
     state.autoCollapsed,
   ]);
 
-  // Filter entries to hide logs from collapsed processes
+  const expanded = useExpandableStore((s) => s.expanded);
+
+  // Filter entries to hide logs from collapsed processes and subagents
   const visibleEntries = useMemo(() => {
-    return entries.filter((entry) =>
-      entry.channel === 'process_start'
-        ? true
-        : !allCollapsedProcesses.has(entry.processId)
-    );
-  }, [entries, allCollapsedProcesses]);
+    return entries.filter((entry) => {
+      if (entry.channel === 'process_start') return true;
+      if (allCollapsedProcesses.has(entry.processId)) return false;
+      const parentId =
+        entry.channel === 'normalized'
+          ? (entry.payload as NormalizedEntry).parent_id
+          : null;
+      return (
+        !parentId || !expanded[subagentCollapseKey(entry.processId, parentId)]
+      );
+    });
+  }, [entries, allCollapsedProcesses, expanded]);
 
   // Memoized item content to prevent flickering
   const itemContent = useCallback(
@@ -11,7 +11,10 @@ import {
 import type { ExecutionProcessStatus, ExecutionProcess } from 'shared/types';
 import { useLogStream } from '@/hooks/useLogStream';
 import { useProcessConversation } from '@/hooks/useProcessConversation';
-import DisplayConversationEntry from '@/components/NormalizedConversation/DisplayConversationEntry';
+import DisplayConversationEntry, {
+  subagentCollapseKey,
+} from '@/components/NormalizedConversation/DisplayConversationEntry';
+import { useExpandableStore } from '@/stores/useExpandableStore';
 import RawLogText from '@/components/common/RawLogText';
 
 interface ProcessCardProps {
@@ -30,6 +33,13 @@ function ProcessCard({ process }: ProcessCardProps) {
     error: normalizedError,
   } = useProcessConversation(process.id, showLogs && isCodingAgent);
 
+  const expanded = useExpandableStore((s) => s.expanded);
+  const visibleEntries = entries.filter(
+    (entry) =>
+      !entry.parent_id ||
+      !expanded[subagentCollapseKey(process.id, entry.parent_id)]
+  );
+
   const logEndRef = useRef<HTMLDivElement>(null);
   const isConnected = isCodingAgent ? normalizedConnected : false;
   const error = isCodingAgent ? normalizedError : rawError;
@@ -151,17 +161,18 @@ function ProcessCard({ process }: ProcessCardProps) {
             {isCodingAgent ? (
               // Normalized conversation display for coding agents
               <div className="space-y-2 max-h-64 overflow-y-auto">
-                {entries.length === 0 ? (
+                {visibleEntries.length === 0 ? (
                   <div className="text-gray-400 text-sm">
                     No conversation entries available...
                   </div>
                 ) : (
-                  entries.map((entry, index) => (
+                  visibleEntries.map((entry, index) => (
                     <DisplayConversationEntry
                       key={entry.timestamp ?? index}
                       entry={entry}
                       expansionKey={`${process.id}:${index}`}
                       diffDeletable={false}
+                      processId={process.id}
                     />
                   ))
                 )}
@@ -7,18 +7,29 @@ import {
   Clock,
   Cog,
   ArrowLeft,
+  Undo2,
 } from 'lucide-react';
-import { TaskAttemptDataContext } from '@/components/context/taskDetailsContext.ts';
-import { executionProcessesApi } from '@/lib/api.ts';
+import {
+  TaskAttemptDataContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi, executionProcessesApi } from '@/lib/api.ts';
+import { Button } from '@/components/ui/button';
 import { ProfileVariantBadge } from '@/components/common/ProfileVariantBadge.tsx';
 import ProcessLogsViewer from './ProcessLogsViewer';
 import type { ExecutionProcessStatus, ExecutionProcess } from 'shared/types';
 import { useProcessSelection } from '@/contexts/ProcessSelectionContext';
 
 function ProcessesTab() {
-  const { attemptData, setAttemptData } = useContext(TaskAttemptDataContext);
+  const { attemptData, setAttemptData, fetchAttemptData } = useContext(
+    TaskAttemptDataContext
+  );
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
   const { selectedProcessId, setSelectedProcessId } = useProcessSelection();
   const [loadingProcessId, setLoadingProcessId] = useState<string | null>(null);
+  const [revertingProcessId, setRevertingProcessId] = useState<string | null>(
+    null
+  );
 
   const getStatusIcon = (status: ExecutionProcessStatus) => {
     switch (status) {
@@ -86,6 +97,38 @@ function ProcessesTab() {
     }
   }, [selectedProcessId, attemptData.runningProcessDetails]);
 
+  const canRevert = (process: ExecutionProcess) =>
+    process.checkpoint_commit !== null &&
+    !process.reverted &&
+    process.run_reason !== 'devserver' &&
+    !attemptData.processes.some(
+      (p) =>
+        p.run_reason !== 'devserver' &&
+        (p.status === 'running' || p.status === 'queued')
+    );
+
+  const handleRevert = async (process: ExecutionProcess) => {
+    if (!selectedAttempt?.id) return;
+    if (
+      !confirm(
+        'Discard the changes made by this execution and every later one? The branch and worktree are reset to the state before it started.'
+      )
+    ) {
+      return;
+    }
+    try {
+      setRevertingProcessId(process.id);
+      await attemptsApi.revertToCheckpoint(selectedAttempt.id, {
+        execution_process_id: process.id,
+      });
+      fetchAttemptData(selectedAttempt.id);
+    } catch (err) {
+      console.error('Failed to revert to checkpoint:', err);
+    } finally {
+      setRevertingProcessId(null);
+    }
+  };
+
   const handleProcessClick = async (process: ExecutionProcess) => {
     setSelectedProcessId(process.id);
 
@@ -166,6 +209,26 @@ function ProcessesTab() {
                         Exit: {process.exit_code.toString()}
                       </p>
                     )}
+                    {process.reverted && (
+                      <p className="text-xs text-muted-foreground mt-1">
+                        Changes reverted
+                      </p>
+                    )}
+                    {canRevert(process) && (
+                      <Button
+                        variant="outline"
+                        size="xs"
+                        className="mt-2 gap-1"
+                        onClick={(e) => {
+                          e.stopPropagation();
+                          handleRevert(process);
+                        }}
+                        disabled={revertingProcessId !== null}
+                      >
+                        <Undo2 className="h-3 w-3" />
+                        Revert to before
+                      </Button>
+                    )}
                   </div>
                 </div>
                 <div className="mt-3 text-xs text-muted-foreground">
@@ -46,6 +46,8 @@ export function TaskFollowUpSection() {
   const [selectedVariant, setSelectedVariant] = useState<string | null>(
     defaultFollowUpVariant
   );
+  // Profile to hand the attempt off to, null continues with its own profile
+  const [handoffProfile, setHandoffProfile] = useState<string | null>(null);
   const [isAnimating, setIsAnimating] = useState(false);
   const variantButtonRef = useRef<HTMLButtonElement>(null);
   const [showImageUpload, setShowImageUpload] = useState(false);
@@ -55,7 +57,8 @@ export function TaskFollowUpSection() {
   );
 
   // Get the profile from the selected attempt
-  const selectedProfile = selectedAttempt?.profile || null;
+  const attemptProfile = selectedAttempt?.profile || null;
+  const selectedProfile = handoffProfile ?? attemptProfile;
 
   const canSendFollowUp = useMemo(() => {
     if (
@@ -95,6 +98,17 @@ export function TaskFollowUpSection() {
     setSelectedVariant(defaultFollowUpVariant);
   }, [defaultFollowUpVariant]);
 
+  useEffect(() => {
+    setHandoffProfile(null);
+  }, [selectedAttempt?.id]);
+
+  const selectProfile = (label: string) => {
+    setHandoffProfile(label === attemptProfile ? null : label);
+    setSelectedVariant(
+      label === attemptProfile ? defaultFollowUpVariant : null
+    );
+  };
+
   const handleImageUploaded = useCallback((image: ImageResponse) => {
     const markdownText = `![${image.original_name}](${image.file_path})`;
     setFollowUpMessage((prev) => {
@@ -135,8 +149,10 @@ export function TaskFollowUpSection() {
         prompt: followUpMessage.trim(),
         variant: selectedVariant,
         image_ids: imageIds,
+        profile: handoffProfile,
       });
       setFollowUpMessage('');
+      setHandoffProfile(null);
       // Clear images and newly uploaded IDs after successful submission
       setImages([]);
       setNewlyUploadedImageIds([]);
@@ -224,6 +240,41 @@ export function TaskFollowUpSection() {
                 />
               </Button>
 
+              {/* Profile selector, another profile hands the attempt off */}
+              {profiles && profiles.length > 1 && (
+                <DropdownMenu>
+                  <DropdownMenuTrigger asChild>
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      className={cn(
+                        'h-10 w-28 px-2 flex items-center justify-between',
+                        handoffProfile && 'border-primary'
+                      )}
+                      disabled={!canSendFollowUp}
+                    >
+                      <span className="text-xs truncate flex-1 text-left">
+                        {selectedProfile}
+                      </span>
+                      <ChevronDown className="h-3 w-3 ml-1 flex-shrink-0" />
+                    </Button>
+                  </DropdownMenuTrigger>
+                  <DropdownMenuContent>
+                    {profiles.map((profile) => (
+                      <DropdownMenuItem
+                        key={profile.label}
+                        onClick={() => selectProfile(profile.label)}
+                        className={
+                          selectedProfile === profile.label ? 'bg-accent' : ''
+                        }
+                      >
+                        {profile.label}
+                      </DropdownMenuItem>
+                    ))}
+                  </DropdownMenuContent>
+                </DropdownMenu>
+              )}
+
               {/* Variant selector */}
               {(() => {
                 const hasVariants =
@@ -0,0 +1,229 @@
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Textarea } from '@/components/ui/textarea.tsx';
+import { useCallback, useContext, useEffect, useState } from 'react';
+import {
+  TaskAttemptDataContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi } from '@/lib/api.ts';
+import type {
+  ConflictedFile,
+  ConflictResolution,
+  RebaseConflicts,
+} from 'shared/types';
+
+const sideLabel = (side: string, deleted: boolean, binary: boolean) => {
+  if (deleted) return `Delete (${side})`;
+  if (binary) return `Use ${side} (binary)`;
+  return `Use ${side}`;
+};
+
+type Props = {
+  open: boolean;
+  setOpen: (open: boolean) => void;
+  setError: (error: string | null) => void;
+};
+
+function ConflictResolutionDialog({ open, setOpen, setError }: Props) {
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { fetchAttemptData } = useContext(TaskAttemptDataContext);
+  const [conflicts, setConflicts] = useState<RebaseConflicts | null>(null);
+  const [editing, setEditing] = useState<ConflictedFile | null>(null);
+  const [manualContent, setManualContent] = useState('');
+  const [busy, setBusy] = useState(false);
+
+  const loadConflicts = useCallback(async () => {
+    if (!selectedAttempt?.id) return;
+    try {
+      setConflicts(await attemptsApi.getConflicts(selectedAttempt.id));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load conflicts');
+    }
+  }, [selectedAttempt?.id, setError]);
+
+  useEffect(() => {
+    if (open) {
+      setEditing(null);
+      loadConflicts();
+    }
+  }, [open, loadConflicts]);
+
+  const run = async (action: () => Promise<unknown>, close = false) => {
+    if (!selectedAttempt?.id) return;
+    setBusy(true);
+    try {
+      await action();
+      setError(null);
+      if (close) {
+        setOpen(false);
+      } else {
+        await loadConflicts();
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Rebase action failed');
+      await loadConflicts();
+    } finally {
+      setBusy(false);
+      fetchAttemptData(selectedAttempt.id);
+    }
+  };
+
+  const resolve = (filePath: string, resolution: ConflictResolution) =>
+    run(async () => {
+      await attemptsApi.resolveConflict(selectedAttempt!.id, {
+        file_path: filePath,
+        resolution,
+      });
+      setEditing(null);
+    });
+
+  const startEditing = (file: ConflictedFile) => {
+    setEditing(file);
+    setManualContent(file.theirs ?? file.ours ?? '');
+  };
+
+  const files = conflicts?.files ?? [];
+
+  return (
+    <Dialog open={open} onOpenChange={setOpen}>
+      <DialogContent className="sm:max-w-[720px]">
+        <DialogHeader>
+          <DialogTitle>Resolve Rebase Conflicts</DialogTitle>
+          <DialogDescription>
+            {conflicts?.commit
+              ? `Applying ${conflicts.commit.slice(0, 7)}${
+                  conflicts.commit_summary
+                    ? ` (${conflicts.commit_summary})`
+                    : ''
+                } conflicted. `
+              : ''}
+            "Ours" is the new base branch, "theirs" is the task branch.
+          </DialogDescription>
+        </DialogHeader>
+
+        {editing ? (
+          <div className="space-y-2">
+            <p className="text-sm font-medium font-mono">{editing.path}</p>
+            <Textarea
+              value={manualContent}
+              onChange={(e) => setManualContent(e.target.value)}
+              className="font-mono text-xs min-h-[320px]"
+            />
+            <div className="flex justify-end gap-2">
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => setEditing(null)}
+                disabled={busy}
+              >
+                Back
+              </Button>
+              <Button
+                size="sm"
+                onClick={() =>
+                  resolve(editing.path, {
+                    type: 'manual',
+                    content: manualContent,
+                  })
+                }
+                disabled={busy}
+              >
+                Save Resolution
+              </Button>
+            </div>
+          </div>
+        ) : files.length === 0 ? (
+          <p className="text-sm text-muted-foreground">
+            {conflicts
+              ? 'All conflicts are resolved. Continue the rebase to apply the remaining commits.'
+              : 'No rebase in progress.'}
+          </p>
+        ) : (
+          <div className="space-y-2 max-h-[400px] overflow-y-auto">
+            {files.map((file) => (
+              <div
+                key={file.path}
+                className="flex items-center justify-between gap-2 border rounded p-2"
+              >
+                <span className="font-mono text-xs truncate">{file.path}</span>
+                <div className="flex gap-1 shrink-0">
+                  <Button
+                    variant="outline"
+                    size="xs"
+                    onClick={() => resolve(file.path, { type: 'ours' })}
+                    disabled={busy}
+                  >
+                    {sideLabel('ours', file.ours_deleted, file.ours_binary)}
+                  </Button>
+                  <Button
+                    variant="outline"
+                    size="xs"
+                    onClick={() => resolve(file.path, { type: 'theirs' })}
+                    disabled={busy}
+                  >
+                    {sideLabel(
+                      'theirs',
+                      file.theirs_deleted,
+                      file.theirs_binary
+                    )}
+                  </Button>
+                  <Button
+                    variant="outline"
+                    size="xs"
+                    onClick={() => startEditing(file)}
+                    disabled={busy || file.ours_binary || file.theirs_binary}
+                  >
+                    Edit
+                  </Button>
+                </div>
+              </div>
+            ))}
+          </div>
+        )}
+
+        <DialogFooter className="gap-2">
+          <Button
+            variant="destructive"
+            onClick={() =>
+              run(() => attemptsApi.abortRebase(selectedAttempt!.id), true)
+            }
+            disabled={busy || !conflicts}
+          >
+            Abort Rebase
+          </Button>
+          <Button
+            variant="outline"
+            onClick={() =>
+              run(
+                () =>
+                  attemptsApi.askAgentToResolveConflicts(selectedAttempt!.id),
+                true
+              )
+            }
+            disabled={busy || files.length === 0}
+          >
+            Ask Agent
+          </Button>
+          <Button
+            onClick={() =>
+              run(() => attemptsApi.continueRebase(selectedAttempt!.id), true)
+            }
+            disabled={busy || !conflicts || files.length > 0}
+          >
+            Continue Rebase
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+export default ConflictResolutionDialog;
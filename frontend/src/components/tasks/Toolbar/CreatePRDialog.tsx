@@ -10,6 +10,7 @@ import { Label } from '@radix-ui/react-label';
 import { Textarea } from '@/components/ui/textarea.tsx';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
+import { Checkbox } from '@/components/ui/checkbox';
 import {
   Select,
   SelectContent,
@@ -53,6 +54,7 @@ function CreatePrDialog({
   const [prBaseBranch, setPrBaseBranch] = useState(
     selectedAttempt?.base_branch || 'main'
   );
+  const [includeTranscript, setIncludeTranscript] = useState(false);
   const [showPatDialog, setShowPatDialog] = useState(false);
   const [patDialogError, setPatDialogError] = useState<string | null>(null);
   const [showGitHubLoginDialog, setShowGitHubLoginDialog] = useState(false);
@@ -81,6 +83,7 @@ function CreatePrDialog({
       title: prTitle,
       body: prBody || null,
       base_branch: prBaseBranch || null,
+      include_transcript: includeTranscript,
     });
 
     if (result.success) {
@@ -124,6 +127,7 @@ function CreatePrDialog({
     prBaseBranch,
     prBody,
     prTitle,
+    includeTranscript,
     setCreatingPR,
     setError,
     setShowCreatePRDialog,
@@ -198,6 +202,18 @@ function CreatePrDialog({
                 </SelectContent>
               </Select>
             </div>
+            <div className="flex items-center space-x-2">
+              <Checkbox
+                id="pr-include-transcript"
+                checked={includeTranscript}
+                onCheckedChange={(checked: boolean) =>
+                  setIncludeTranscript(checked === true)
+                }
+              />
+              <Label htmlFor="pr-include-transcript">
+                Append the agent transcript to the description
+              </Label>
+            </div>
           </div>
           <DialogFooter>
             <Button variant="outline" onClick={handleCancelCreatePR}>
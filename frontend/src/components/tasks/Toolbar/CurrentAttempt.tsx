@@ -1,11 +1,15 @@
 import {
+  AlertTriangle,
   ExternalLink,
   GitBranch as GitBranchIcon,
   GitPullRequest,
   History,
+  Layers,
+  ListChecks,
   Play,
   Plus,
   RefreshCw,
+  RotateCcw,
   Settings,
   StopCircle,
   ScrollText,
@@ -32,6 +36,9 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog.tsx';
 import BranchSelector from '@/components/tasks/BranchSelector.tsx';
+import ConflictResolutionDialog from '@/components/tasks/Toolbar/ConflictResolutionDialog.tsx';
+import PartialMergeDialog from '@/components/tasks/Toolbar/PartialMergeDialog.tsx';
+import RevertMergeDialog from '@/components/tasks/Toolbar/RevertMergeDialog.tsx';
 import { attemptsApi, executionProcessesApi } from '@/lib/api.ts';
 import {
   Dispatch,
@@ -43,7 +50,12 @@ import {
   useState,
 } from 'react';
 import type { ExecutionProcess } from 'shared/types';
-import type { GitBranch, TaskAttempt } from 'shared/types';
+import type {
+  GitBranch,
+  Merge,
+  StackedAttempt,
+  TaskAttempt,
+} from 'shared/types';
 import {
   TaskAttemptDataContext,
   TaskAttemptStoppingContext,
@@ -114,6 +126,11 @@ function CurrentAttempt({
     useState<ExecutionProcess | null>(null);
   const [isHoveringDevServer, setIsHoveringDevServer] = useState(false);
   const [showRebaseDialog, setShowRebaseDialog] = useState(false);
+  const [showConflictDialog, setShowConflictDialog] = useState(false);
+  const [showPartialMergeDialog, setShowPartialMergeDialog] = useState(false);
+  const [mergeToRevert, setMergeToRevert] = useState<Merge | null>(null);
+  const [stack, setStack] = useState<StackedAttempt[]>([]);
+  const [restacking, setRestacking] = useState(false);
   const [selectedRebaseBranch, setSelectedRebaseBranch] = useState<string>('');
   const [showStopConfirmation, setShowStopConfirmation] = useState(false);
   const [copied, setCopied] = useState(false);
@@ -278,8 +295,11 @@ function CurrentAttempt({
 
     try {
       setMerging(true);
-      await attemptsApi.merge(selectedAttempt.id);
-      setError(null); // Clear any previous errors on success
+      const result = await attemptsApi.merge(selectedAttempt.id);
+      // The merge went through, but the main checkout wasn't updated
+      setError(
+        result.checkout_warning ? `Merged. ${result.checkout_warning}` : null
+      );
       setMergeSuccess(true);
       setTimeout(() => setMergeSuccess(false), 2000);
       fetchAttemptData(selectedAttempt.id);
@@ -287,6 +307,8 @@ function CurrentAttempt({
       console.error('Failed to merge changes:', error);
       // @ts-expect-error it is type ApiError
       setError(error.message || 'Failed to merge changes');
+      // Conflicting merges leave a rebase stopped on the conflicts
+      fetchAttemptData(selectedAttempt.id);
     } finally {
       setMerging(false);
     }
@@ -302,11 +324,46 @@ function CurrentAttempt({
       fetchAttemptData(selectedAttempt.id);
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to rebase branch');
+      // A rebase that stopped on conflicts waits to be resolved
+      fetchAttemptData(selectedAttempt.id);
     } finally {
       setRebasing(false);
     }
   };
 
+  useEffect(() => {
+    if (!selectedAttempt?.id) {
+      setStack([]);
+      return;
+    }
+    attemptsApi
+      .getStack(selectedAttempt.id)
+      .then(setStack)
+      .catch(() => setStack([]));
+  }, [selectedAttempt?.id, branchStatus]);
+
+  const hasStackedAttempts = stack.some(
+    (entry) => entry.attempt.parent_attempt_id === selectedAttempt?.id
+  );
+
+  const handleRestackClick = async () => {
+    if (!selectedAttempt?.id) return;
+
+    try {
+      setRestacking(true);
+      const results = await attemptsApi.restack(selectedAttempt.id);
+      const failed = results.find((result) => result.error);
+      setError(
+        failed ? `Failed to restack stacked attempts: ${failed.error}` : null
+      );
+      fetchAttemptData(selectedAttempt.id);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to restack');
+    } finally {
+      setRestacking(false);
+    }
+  };
+
   const handleRebaseWithNewBranch = async (newBaseBranch: string) => {
     if (!projectId || !selectedAttempt?.id || !selectedAttempt?.task_id) return;
 
@@ -320,6 +377,8 @@ function CurrentAttempt({
       setShowRebaseDialog(false);
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to rebase branch');
+      // A rebase that stopped on conflicts waits to be resolved
+      fetchAttemptData(selectedAttempt.id);
     } finally {
       setRebasing(false);
     }
@@ -402,6 +461,14 @@ function CurrentAttempt({
     };
   }, [branchStatus?.merges]);
 
+  // The latest merge that landed and can still be reverted
+  const revertableMerge = useMemo(() => {
+    const latest = mergeInfo.latestMerge;
+    if (!latest || latest.revert) return null;
+    if (latest.type === 'pr' && latest.pr_info.status !== 'merged') return null;
+    return latest;
+  }, [mergeInfo.latestMerge]);
+
   const handleCopyWorktreePath = useCallback(async () => {
     try {
       await navigator.clipboard.writeText(selectedAttempt.container_ref || '');
@@ -526,6 +593,69 @@ function CurrentAttempt({
             <span className="text-sm font-medium truncate">
               {branchStatus?.base_branch_name || selectedBranchDisplayName}
             </span>
+            {((branchStatus?.base_remote_commits_ahead ?? 0) > 0 ||
+              (branchStatus?.base_remote_commits_behind ?? 0) > 0) && (
+              <TooltipProvider>
+                <Tooltip>
+                  <TooltipTrigger asChild>
+                    <span className="text-xs text-orange-700 whitespace-nowrap">
+                      {[
+                        branchStatus?.base_remote_commits_ahead
+                          ? `↑${branchStatus.base_remote_commits_ahead}`
+                          : null,
+                        branchStatus?.base_remote_commits_behind
+                          ? `↓${branchStatus.base_remote_commits_behind}`
+                          : null,
+                      ]
+                        .filter(Boolean)
+                        .join(' ')}
+                    </span>
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>
+                      Local {branchStatus?.base_branch_name} is{' '}
+                      {branchStatus?.base_remote_commits_ahead ?? 0} ahead and{' '}
+                      {branchStatus?.base_remote_commits_behind ?? 0} behind
+                      origin/{branchStatus?.base_branch_name} as of the last
+                      fetch
+                    </p>
+                  </TooltipContent>
+                </Tooltip>
+              </TooltipProvider>
+            )}
+            {stack.length > 1 && (
+              <TooltipProvider>
+                <Tooltip>
+                  <TooltipTrigger asChild>
+                    <Layers className="h-3 w-3 text-muted-foreground" />
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p className="font-medium">Stacked attempts</p>
+                    <ul className="text-xs">
+                      {stack.map((entry) => (
+                        <li
+                          key={entry.attempt.id}
+                          style={{ paddingLeft: `${entry.depth * 12}px` }}
+                          className={
+                            entry.attempt.id === selectedAttempt?.id
+                              ? 'font-semibold'
+                              : undefined
+                          }
+                        >
+                          {entry.task_title}{' '}
+                          <span className="font-mono">
+                            ({entry.attempt.branch})
+                          </span>
+                          {entry.commits_behind_parent
+                            ? `, ${entry.commits_behind_parent} behind`
+                            : ''}
+                        </li>
+                      ))}
+                    </ul>
+                  </TooltipContent>
+                </Tooltip>
+              </TooltipProvider>
+            )}
           </div>
         </div>
 
@@ -555,6 +685,31 @@ function CurrentAttempt({
                       {statusInfo.text}
                     </span>
                   )}
+                  {mergeInfo.latestMerge?.revert && (
+                    <span className="text-xs text-muted-foreground">
+                      (reverted)
+                    </span>
+                  )}
+                  {revertableMerge && (
+                    <TooltipProvider>
+                      <Tooltip>
+                        <TooltipTrigger asChild>
+                          <Button
+                            variant="ghost"
+                            size="xs"
+                            onClick={() => setMergeToRevert(revertableMerge)}
+                            disabled={isAttemptRunning}
+                            className="h-4 w-4 p-0 hover:bg-muted"
+                          >
+                            <RotateCcw className="h-3 w-3" />
+                          </Button>
+                        </TooltipTrigger>
+                        <TooltipContent>
+                          <p>Revert this merge</p>
+                        </TooltipContent>
+                      </Tooltip>
+                    </TooltipProvider>
+                  )}
                 </div>
               );
             })()}
@@ -686,20 +841,75 @@ function CurrentAttempt({
           {/* Git Operations */}
           {selectedAttempt && branchStatus && !mergeInfo.hasMergedPR && (
             <>
-              {(branchStatus.commits_behind ?? 0) > 0 && (
+              {branchStatus.merge_preview &&
+                !branchStatus.merge_preview.clean &&
+                !branchStatus.rebase_in_progress && (
+                  <TooltipProvider>
+                    <Tooltip>
+                      <TooltipTrigger asChild>
+                        <span className="flex items-center gap-1 text-xs text-red-700">
+                          <AlertTriangle className="h-3 w-3" />
+                          {branchStatus.merge_preview.conflicted_files.length ===
+                          1
+                            ? 'Conflicts in 1 file'
+                            : `Conflicts in ${branchStatus.merge_preview.conflicted_files.length} files`}
+                        </span>
+                      </TooltipTrigger>
+                      <TooltipContent>
+                        <p>
+                          Merging into {branchStatus.base_branch_name} would
+                          conflict in:
+                        </p>
+                        <ul className="font-mono text-xs">
+                          {branchStatus.merge_preview.conflicted_files.map(
+                            (path) => (
+                              <li key={path}>{path}</li>
+                            )
+                          )}
+                        </ul>
+                      </TooltipContent>
+                    </Tooltip>
+                  </TooltipProvider>
+                )}
+              {hasStackedAttempts && (
                 <Button
-                  onClick={handleRebaseClick}
-                  disabled={rebasing || isAttemptRunning}
+                  onClick={handleRestackClick}
+                  disabled={restacking || isAttemptRunning}
                   variant="outline"
                   size="xs"
-                  className="border-orange-300 text-orange-700 hover:bg-orange-50 gap-1"
+                  className="gap-1"
                 >
-                  <RefreshCw
-                    className={`h-3 w-3 ${rebasing ? 'animate-spin' : ''}`}
-                  />
-                  {rebasing ? 'Rebasing...' : `Rebase`}
+                  <Layers className="h-3 w-3" />
+                  {restacking ? 'Restacking...' : 'Restack'}
                 </Button>
               )}
+              {branchStatus.rebase_in_progress ? (
+                <Button
+                  onClick={() => setShowConflictDialog(true)}
+                  disabled={isAttemptRunning}
+                  variant="outline"
+                  size="xs"
+                  className="border-red-300 text-red-700 hover:bg-red-50 gap-1"
+                >
+                  <AlertTriangle className="h-3 w-3" />
+                  Resolve Conflicts
+                </Button>
+              ) : (
+                (branchStatus.commits_behind ?? 0) > 0 && (
+                  <Button
+                    onClick={handleRebaseClick}
+                    disabled={rebasing || isAttemptRunning}
+                    variant="outline"
+                    size="xs"
+                    className="border-orange-300 text-orange-700 hover:bg-orange-50 gap-1"
+                  >
+                    <RefreshCw
+                      className={`h-3 w-3 ${rebasing ? 'animate-spin' : ''}`}
+                    />
+                    {rebasing ? 'Rebasing...' : `Rebase`}
+                  </Button>
+                )
+              )}
               <>
                 <Button
                   onClick={handlePRButtonClick}
@@ -750,6 +960,28 @@ function CurrentAttempt({
                   <GitBranchIcon className="h-3 w-3" />
                   {mergeSuccess ? 'Merged!' : merging ? 'Merging...' : 'Merge'}
                 </Button>
+                <TooltipProvider>
+                  <Tooltip>
+                    <TooltipTrigger asChild>
+                      <Button
+                        onClick={() => setShowPartialMergeDialog(true)}
+                        disabled={
+                          mergeInfo.hasOpenPR ||
+                          merging ||
+                          isAttemptRunning ||
+                          branchStatus.rebase_in_progress
+                        }
+                        variant="outline"
+                        size="xs"
+                      >
+                        <ListChecks className="h-3 w-3" />
+                      </Button>
+                    </TooltipTrigger>
+                    <TooltipContent>
+                      <p>Merge selected files or hunks</p>
+                    </TooltipContent>
+                  </Tooltip>
+                </TooltipProvider>
               </>
             </>
           )}
@@ -818,6 +1050,24 @@ function CurrentAttempt({
         </div>
       </div>
 
+      <ConflictResolutionDialog
+        open={showConflictDialog}
+        setOpen={setShowConflictDialog}
+        setError={setError}
+      />
+
+      <PartialMergeDialog
+        open={showPartialMergeDialog}
+        setOpen={setShowPartialMergeDialog}
+        setError={setError}
+      />
+
+      <RevertMergeDialog
+        merge={mergeToRevert}
+        setMerge={setMergeToRevert}
+        setError={setError}
+      />
+
       {/* Rebase Dialog */}
       <Dialog open={showRebaseDialog} onOpenChange={setShowRebaseDialog}>
         <DialogContent className="sm:max-w-md">
@@ -0,0 +1,258 @@
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import { ChevronDown, ChevronRight } from 'lucide-react';
+import { useCallback, useContext, useEffect, useState } from 'react';
+import {
+  TaskAttemptDataContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi } from '@/lib/api.ts';
+import type { FileHunks, FileSelection } from 'shared/types';
+
+type Props = {
+  open: boolean;
+  setOpen: (open: boolean) => void;
+  setError: (error: string | null) => void;
+};
+
+// Whole file, or the indices of the picked hunks
+type Picked = 'all' | number[];
+
+function PartialMergeDialog({ open, setOpen, setError }: Props) {
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { fetchAttemptData } = useContext(TaskAttemptDataContext);
+  const [files, setFiles] = useState<FileHunks[]>([]);
+  const [picked, setPicked] = useState<Record<string, Picked>>({});
+  const [expanded, setExpanded] = useState<string | null>(null);
+  const [includeUncommitted, setIncludeUncommitted] = useState(false);
+  const [newBranch, setNewBranch] = useState('');
+  const [loading, setLoading] = useState(false);
+  const [merging, setMerging] = useState(false);
+
+  const loadHunks = useCallback(async () => {
+    if (!selectedAttempt?.id) return;
+    setLoading(true);
+    try {
+      setFiles(
+        await attemptsApi.getSelectableHunks(
+          selectedAttempt.id,
+          includeUncommitted
+        )
+      );
+      setPicked({});
+      setExpanded(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load changes');
+    } finally {
+      setLoading(false);
+    }
+  }, [selectedAttempt?.id, includeUncommitted, setError]);
+
+  useEffect(() => {
+    if (open) {
+      loadHunks();
+    }
+  }, [open, loadHunks]);
+
+  const toggleFile = (path: string) => {
+    setPicked((prev) => {
+      const next = { ...prev };
+      if (next[path]) {
+        delete next[path];
+      } else {
+        next[path] = 'all';
+      }
+      return next;
+    });
+  };
+
+  const toggleHunk = (file: FileHunks, idx: number) => {
+    setPicked((prev) => {
+      const current = prev[file.path];
+      const hunks =
+        current === 'all'
+          ? file.hunks.map((_, i) => i)
+          : current
+            ? [...current]
+            : [];
+      const nextHunks = hunks.includes(idx)
+        ? hunks.filter((i) => i !== idx)
+        : [...hunks, idx];
+      const next = { ...prev };
+      if (nextHunks.length === 0) {
+        delete next[file.path];
+      } else if (nextHunks.length === file.hunks.length) {
+        next[file.path] = 'all';
+      } else {
+        next[file.path] = nextHunks.sort((a, b) => a - b);
+      }
+      return next;
+    });
+  };
+
+  const isHunkPicked = (path: string, idx: number) => {
+    const current = picked[path];
+    return current === 'all' || (current?.includes(idx) ?? false);
+  };
+
+  const handleMerge = async () => {
+    if (!selectedAttempt?.id) return;
+    const selection: FileSelection[] = Object.entries(picked).map(
+      ([path, hunks]) => ({
+        path,
+        hunks: hunks === 'all' ? null : hunks,
+      })
+    );
+    setMerging(true);
+    try {
+      const result = await attemptsApi.partialMerge(selectedAttempt.id, {
+        files: selection,
+        include_uncommitted: includeUncommitted,
+        new_branch: newBranch.trim() || null,
+      });
+      setError(
+        result.checkout_warning ? `Merged. ${result.checkout_warning}` : null
+      );
+      setOpen(false);
+      fetchAttemptData(selectedAttempt.id);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Partial merge failed');
+    } finally {
+      setMerging(false);
+    }
+  };
+
+  const pickedCount = Object.keys(picked).length;
+
+  return (
+    <Dialog open={open} onOpenChange={setOpen}>
+      <DialogContent className="sm:max-w-[720px]">
+        <DialogHeader>
+          <DialogTitle>Merge Selected Changes</DialogTitle>
+          <DialogDescription>
+            Pick the files or hunks to commit onto{' '}
+            {newBranch.trim() || selectedAttempt?.base_branch}. Everything else
+            is left out and noted on the merge.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-3">
+          <div className="flex items-center space-x-2">
+            <Checkbox
+              id="partial-include-uncommitted"
+              checked={includeUncommitted}
+              onCheckedChange={(checked: boolean) =>
+                setIncludeUncommitted(checked === true)
+              }
+            />
+            <Label htmlFor="partial-include-uncommitted">
+              Include uncommitted changes in the worktree
+            </Label>
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="partial-new-branch">
+              New branch (leave empty to commit onto the base branch)
+            </Label>
+            <Input
+              id="partial-new-branch"
+              value={newBranch}
+              onChange={(e) => setNewBranch(e.target.value)}
+              placeholder={selectedAttempt?.base_branch}
+            />
+          </div>
+
+          {loading ? (
+            <p className="text-sm text-muted-foreground">Loading changes...</p>
+          ) : files.length === 0 ? (
+            <p className="text-sm text-muted-foreground">
+              This attempt has no changes.
+            </p>
+          ) : (
+            <div className="space-y-1 max-h-[360px] overflow-y-auto">
+              {files.map((file) => (
+                <div key={file.path} className="border rounded">
+                  <div className="flex items-center gap-2 p-2">
+                    <Checkbox
+                      checked={picked[file.path] !== undefined}
+                      onCheckedChange={() => toggleFile(file.path)}
+                    />
+                    <span className="font-mono text-xs truncate flex-1">
+                      {file.path}
+                    </span>
+                    {Array.isArray(picked[file.path]) && (
+                      <span className="text-xs text-muted-foreground">
+                        {(picked[file.path] as number[]).length} of{' '}
+                        {file.hunks.length} hunks
+                      </span>
+                    )}
+                    {file.hunks.length > 1 && (
+                      <Button
+                        variant="ghost"
+                        size="xs"
+                        onClick={() =>
+                          setExpanded(
+                            expanded === file.path ? null : file.path
+                          )
+                        }
+                      >
+                        {expanded === file.path ? (
+                          <ChevronDown className="h-3 w-3" />
+                        ) : (
+                          <ChevronRight className="h-3 w-3" />
+                        )}
+                      </Button>
+                    )}
+                  </div>
+                  {expanded === file.path && (
+                    <div className="space-y-1 px-2 pb-2">
+                      {file.hunks.map((hunk, idx) => (
+                        <div key={idx} className="flex items-start gap-2">
+                          <Checkbox
+                            checked={isHunkPicked(file.path, idx)}
+                            onCheckedChange={() => toggleHunk(file, idx)}
+                          />
+                          <pre className="flex-1 text-xs bg-muted rounded p-1 overflow-x-auto">
+                            {hunk.header}
+                            {'\n'}
+                            {hunk.content}
+                          </pre>
+                        </div>
+                      ))}
+                    </div>
+                  )}
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+
+        <DialogFooter>
+          <Button variant="outline" onClick={() => setOpen(false)}>
+            Cancel
+          </Button>
+          <Button
+            onClick={handleMerge}
+            disabled={merging || pickedCount === 0}
+            className="bg-green-600 hover:bg-green-700"
+          >
+            {merging
+              ? 'Merging...'
+              : `Merge ${pickedCount} ${pickedCount === 1 ? 'file' : 'files'}`}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+export default PartialMergeDialog;
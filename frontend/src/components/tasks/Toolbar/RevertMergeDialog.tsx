@@ -0,0 +1,109 @@
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
+import { useContext, useState } from 'react';
+import {
+  TaskAttemptDataContext,
+  TaskSelectedAttemptContext,
+} from '@/components/context/taskDetailsContext.ts';
+import { attemptsApi } from '@/lib/api.ts';
+import type { Merge, TaskStatus } from 'shared/types';
+
+type Props = {
+  merge: Merge | null;
+  setMerge: (merge: Merge | null) => void;
+  setError: (error: string | null) => void;
+};
+
+function RevertMergeDialog({ merge, setMerge, setError }: Props) {
+  const { selectedAttempt } = useContext(TaskSelectedAttemptContext);
+  const { fetchAttemptData } = useContext(TaskAttemptDataContext);
+  const [taskStatus, setTaskStatus] = useState<'keep' | TaskStatus>(
+    'inprogress'
+  );
+  const [reverting, setReverting] = useState(false);
+
+  const handleRevert = async () => {
+    if (!selectedAttempt?.id || !merge) return;
+    setReverting(true);
+    try {
+      const { revert, checkout_warning } = await attemptsApi.revertMerge(
+        selectedAttempt.id,
+        {
+          merge_id: merge.id,
+          task_status: taskStatus === 'keep' ? null : taskStatus,
+        }
+      );
+      // The revert went through, but the main checkout wasn't updated
+      setError(checkout_warning ? `Reverted. ${checkout_warning}` : null);
+      setMerge(null);
+      if (revert.pr_url) {
+        window.open(revert.pr_url, '_blank');
+      }
+      fetchAttemptData(selectedAttempt.id);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to revert merge');
+    } finally {
+      setReverting(false);
+    }
+  };
+
+  return (
+    <Dialog open={merge !== null} onOpenChange={() => setMerge(null)}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>Revert Merge</DialogTitle>
+          <DialogDescription>
+            {merge?.type === 'pr'
+              ? `A revert branch is pushed and a pull request is opened to revert PR #${merge.pr_info.number} on ${merge.target_branch_name}.`
+              : `A commit reverting this attempt's merge is added to ${merge?.target_branch_name}.`}
+          </DialogDescription>
+        </DialogHeader>
+        <div className="space-y-2">
+          <Label>Task status afterwards</Label>
+          <Select
+            value={taskStatus}
+            onValueChange={(value) => setTaskStatus(value as typeof taskStatus)}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="inprogress">In Progress</SelectItem>
+              <SelectItem value="todo">To Do</SelectItem>
+              <SelectItem value="keep">Keep current status</SelectItem>
+            </SelectContent>
+          </Select>
+        </div>
+        <DialogFooter>
+          <Button variant="outline" onClick={() => setMerge(null)}>
+            Cancel
+          </Button>
+          <Button
+            variant="destructive"
+            onClick={handleRevert}
+            disabled={reverting}
+          >
+            {reverting ? 'Reverting...' : 'Revert'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+export default RevertMergeDialog;
@@ -58,6 +58,14 @@ export const useLogStream = (processId: string): UseLogStreamResult => {
       }
     });
 
+    // Part of the log is no longer available on the server
+    eventSource.addEventListener('evicted', (event) => {
+      addLogEntry({
+        type: 'STDERR',
+        content: `[${event.data} earlier log messages are no longer available]`,
+      });
+    });
+
     eventSource.addEventListener('finished', () => {
       eventSource.close();
     });
@@ -15,12 +15,24 @@ import {
   DirectoryListResponse,
   EditorType,
   ExecutionProcess,
+  FileHunks,
   GitBranch,
   Project,
   CreateProject,
+  MergeTaskAttemptRequest,
+  MergeTaskAttemptResponse,
+  PartialMergeRequest,
+  RebaseConflicts,
   RebaseTaskAttemptRequest,
   RepositoryInfo,
+  ResolveConflictRequest,
+  RestackResult,
+  RevertMergeRequest,
+  RevertMergeResponse,
+  RevertToCheckpointRequest,
+  StackedAttempt,
   SearchResult,
+  SelectionCommit,
   Task,
   TaskAttempt,
   TaskTemplate,
@@ -194,7 +206,9 @@ export const projectsApi = {
   create: async (data: CreateProject): Promise<Project> => {
     const response = await makeRequest('/api/projects', {
       method: 'POST',
-      body: JSON.stringify(data),
+      body: JSON.stringify(data, (_key, value) =>
+        typeof value === 'bigint' ? Number(value) : value
+      ),
     });
     return handleApiResponse<Project>(response);
   },
@@ -202,7 +216,9 @@ export const projectsApi = {
   update: async (id: string, data: UpdateProject): Promise<Project> => {
     const response = await makeRequest(`/api/projects/${id}`, {
       method: 'PUT',
-      body: JSON.stringify(data),
+      body: JSON.stringify(data, (_key, value) =>
+        typeof value === 'bigint' ? Number(value) : value
+      ),
     });
     return handleApiResponse<Project>(response);
   },
@@ -370,11 +386,135 @@ export const attemptsApi = {
     return handleApiResponse<BranchStatus>(response);
   },
 
-  merge: async (attemptId: string): Promise<void> => {
+  merge: async (
+    attemptId: string,
+    data: MergeTaskAttemptRequest = { strategy: null, update_checkout: false }
+  ): Promise<MergeTaskAttemptResponse> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/merge`,
       {
         method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<MergeTaskAttemptResponse>(response);
+  },
+
+  getStack: async (attemptId: string): Promise<StackedAttempt[]> => {
+    const response = await makeRequest(`/api/task-attempts/${attemptId}/stack`);
+    return handleApiResponse<StackedAttempt[]>(response);
+  },
+
+  restack: async (attemptId: string): Promise<RestackResult[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/restack`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<RestackResult[]>(response);
+  },
+
+  getConflicts: async (attemptId: string): Promise<RebaseConflicts | null> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts`
+    );
+    return handleApiResponse<RebaseConflicts | null>(response);
+  },
+
+  resolveConflict: async (
+    attemptId: string,
+    data: ResolveConflictRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/resolve`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  askAgentToResolveConflicts: async (
+    attemptId: string
+  ): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/conflicts/ask-agent`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+
+  continueRebase: async (attemptId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/rebase/continue`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  abortRebase: async (attemptId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/rebase/abort`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getSelectableHunks: async (
+    attemptId: string,
+    includeUncommitted: boolean
+  ): Promise<FileHunks[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/partial-merge/hunks?include_uncommitted=${includeUncommitted}`
+    );
+    return handleApiResponse<FileHunks[]>(response);
+  },
+
+  partialMerge: async (
+    attemptId: string,
+    data: PartialMergeRequest
+  ): Promise<SelectionCommit> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/partial-merge`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SelectionCommit>(response);
+  },
+
+  revertMerge: async (
+    attemptId: string,
+    data: RevertMergeRequest
+  ): Promise<RevertMergeResponse> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/revert-merge`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RevertMergeResponse>(response);
+  },
+
+  revertToCheckpoint: async (
+    attemptId: string,
+    data: RevertToCheckpointRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/revert-to-checkpoint`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
       }
     );
     return handleApiResponse<void>(response);
@@ -8,18 +8,78 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, 
+/**
+ * Maximum number of coding agents allowed to run at once for this project
+ */
+max_concurrent_agents: bigint | null, 
+/**
+ * Strategy for direct merges that don't pick one, squash when unset
+ */
+default_merge_strategy: MergeStrategy | null, 
+/**
+ * Fetch origin before creating worktrees or rebasing, and base attempts on
+ * `origin/<branch>` rather than the local branch
+ */
+sync_remote_base: boolean, created_at: Date, updated_at: Date, };
 
-export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, current_branch: string | null, created_at: Date, updated_at: Date, };
+export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, max_concurrent_agents: bigint | null, default_merge_strategy: MergeStrategy | null, sync_remote_base: boolean, current_branch: string | null, created_at: Date, updated_at: Date, };
 
-export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
+export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, max_concurrent_agents: bigint | null, default_merge_strategy: MergeStrategy | null, sync_remote_base: boolean, };
 
-export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
+export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, max_concurrent_agents: bigint | null, default_merge_strategy: MergeStrategy | null, sync_remote_base: boolean | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
+export type ProjectRetryPolicy = { id: string, project_id: string, run_reason: ExecutionProcessRunReason, 
+/**
+ * Total number of runs, including the first one
+ */
+max_attempts: bigint, backoff_seconds: bigint, backoff_multiplier: number, retryable_exit_codes: number[], retryable_stderr_patterns: string[], created_at: string, updated_at: string, };
+
+export type UpsertProjectRetryPolicy = { run_reason: ExecutionProcessRunReason, max_attempts: bigint, backoff_seconds: bigint, backoff_multiplier: number | null, retryable_exit_codes: Array<bigint>, retryable_stderr_patterns: Array<string>, };
+
+export type ProjectRedactionRules = { project_id: string, enabled: boolean, 
+/**
+ * Mask GitHub, AWS, Anthropic and OpenAI keys and JWTs
+ */
+builtin_patterns: boolean, 
+/**
+ * Mask values from env files listed in the project's copy files
+ */
+copy_files_secrets: boolean, custom_patterns: string[], created_at: string, updated_at: string, };
+
+export type UpdateProjectRedactionRules = { enabled: boolean, builtin_patterns: boolean, copy_files_secrets: boolean, custom_patterns: Array<string>, };
+
+export type CommitSigningFormat = "ssh" | "gpg";
+
+export type ProjectCommitSettings = { project_id: string, 
+/**
+ * Author and committer of agent commits, the git config identity when unset
+ */
+author_name: string | null, author_email: string | null, 
+/**
+ * Credit the git config identity with a `Co-authored-by` trailer when
+ * commits use the identity above
+ */
+co_authored_by: boolean, task_id_trailer: boolean, attempt_id_trailer: boolean, 
+/**
+ * Name the attempt's agent profile in a trailer
+ */
+profile_trailer: boolean, 
+/**
+ * Unsigned when unset
+ */
+signing_format: CommitSigningFormat | null, 
+/**
+ * SSH key path or GPG key id, git config `user.signingkey` when unset
+ */
+signing_key: string | null, created_at: string, updated_at: string, };
+
+export type UpdateProjectCommitSettings = { author_name: string | null, author_email: string | null, co_authored_by: boolean, task_id_trailer: boolean, attempt_id_trailer: boolean, profile_trailer: boolean, signing_format: CommitSigningFormat | null, signing_key: string | null, };
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, vibe_kanban: JsonValue, is_toml_config: boolean, };
@@ -38,13 +98,72 @@ export type CreateTaskTemplate = { project_id: string | null, title: string, des
 
 export type UpdateTaskTemplate = { title: string | null, description: string | null, template_name: string | null, };
 
+export type TaskSchedule = { id: string, project_id: string, task_id: string | null, task_template_id: string | null, 
+/**
+ * The task last created from the template, reused until it's done
+ */
+template_task_id: string | null, cron_expression: string, profile: string, variant: string | null, base_branch: string, enabled: boolean, last_run_at: string | null, next_run_at: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskSchedule = { project_id: string, task_id: string | null, task_template_id: string | null, cron_expression: string, profile: string, variant: string | null, base_branch: string, };
+
+export type UpdateTaskSchedule = { cron_expression: string | null, profile: string | null, 
+/**
+ * Replaces the variant, `null` clears it
+ */
+variant: string | null, base_branch: string | null, enabled: boolean | null, };
+
+export type SearchResultKind = "task" | "prompt" | "summary" | "entry";
+
+export type SearchHit = { kind: SearchResultKind, project_id: string, task_id: string, task_attempt_id: string | null, execution_process_id: string | null, 
+/**
+ * Index of the matching entry in the execution's normalized conversation
+ */
+entry_index: bigint | null, entry_type: string | null, action_type: string | null, 
+/**
+ * Matching text with the matched terms wrapped in `**`
+ */
+snippet: string, 
+/**
+ * Frontend path of the matching task or attempt
+ */
+link: string, created_at: string, };
+
+export type LogStorageUsage = { execution_count: bigint, 
+/**
+ * Size of the logs before compression
+ */
+log_bytes: bigint, 
+/**
+ * Size of the logs as stored
+ */
+stored_log_bytes: bigint, 
+/**
+ * Size of the persisted normalized conversations, which are kept when raw
+ * logs are purged
+ */
+normalized_entry_bytes: bigint, };
+
+export type TestRun = { id: string, execution_process_id: string, task_attempt_id: string, runner: string, 
+/**
+ * The agent's command, `None` for script runs
+ */
+command: string | null, passed: bigint, failed: bigint, skipped: bigint, failing_tests: string[], 
+/**
+ * Order of the run within its execution
+ */
+sequence: bigint, created_at: string, };
+
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
 export type TaskType = "feature" | "bugfix" | "hotfix" | "chore";
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, task_type: TaskType, parent_task_attempt: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, task_type: TaskType, parent_task_attempt: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, profile: string, };
+export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, task_type: TaskType, parent_task_attempt: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_queued_attempt: boolean, has_merged_attempt: boolean, last_attempt_failed: boolean, profile: string, 
+/**
+ * Counts of the latest test run in the task's attempts
+ */
+tests_passed: bigint | null, tests_failed: bigint | null, };
 
 export type CreateTask = { project_id: string, title: string, description: string | null, task_type: TaskType, parent_task_attempt: string | null, image_ids: Array<string> | null, };
 
@@ -66,15 +185,63 @@ export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, }
 
 export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
 
-export type CreateFollowUpAttempt = { prompt: string, variant: string | null, image_ids: Array<string> | null, };
+export type CreateFollowUpAttempt = { prompt: string, variant: string | null, image_ids: Array<string> | null, 
+/**
+ * Continue with a different profile. If it uses a different agent, the
+ * attempt is handed off to a fresh session of that agent.
+ */
+profile: string | null, };
+
+export type CreateGitHubPrRequest = { title: string, body: string | null, base_branch: string | null, 
+/**
+ * Append the attempt's transcript to the PR body
+ */
+include_transcript: boolean, };
+
+export type TranscriptFormat = "markdown" | "html" | "json";
+
+export type TranscriptProcess = { execution_process: ExecutionProcess, 
+/**
+ * Normalized conversation of coding agent runs
+ */
+entries: Array<NormalizedEntry>, 
+/**
+ * Raw stdout and stderr of script runs
+ */
+output: string | null, };
+
+export type TranscriptBundle = { task: Task, task_attempt: TaskAttempt, processes: Array<TranscriptProcess>, diffs: Array<Diff>, exported_at: string, };
 
-export type CreateGitHubPrRequest = { title: string, body: string | null, base_branch: string | null, };
+export type FileActivityEntry = { execution_process_id: string, entry_index: number, };
+
+export type FileActivity = { path: string, reads: number, edits: number, 
+/**
+ * Added by the final diff, i.e. not in the base branch
+ */
+created: boolean, 
+/**
+ * Deleted by the final diff
+ */
+deleted: boolean, 
+/**
+ * New path, when the agent renamed the file
+ */
+renamed_to: string | null, 
+/**
+ * `None` for files that only show up in the diff, e.g. changed by a
+ * script or a shell command
+ */
+first_entry: FileActivityEntry | null, last_entry: FileActivityEntry | null, in_final_diff: boolean, };
 
 export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
 export enum GitHubServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
 
-export type Config = { config_version: string, theme: ThemeMode, profile: ProfileVariantLabel, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, };
+export type Config = { config_version: string, theme: ThemeMode, profile: ProfileVariantLabel, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, jira: JiraConfig, analytics_enabled: boolean | null, workspace_dir: string | null, 
+/**
+ * Maximum number of coding agents running at once across all projects
+ */
+max_concurrent_agents: number | null, log_retention: LogRetentionConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -88,6 +255,22 @@ export type GitHubConfig = { pat: string | null, oauth_token: string | null, use
 
 export enum SoundFile { ABSTRACT_SOUND1 = "ABSTRACT_SOUND1", ABSTRACT_SOUND2 = "ABSTRACT_SOUND2", ABSTRACT_SOUND3 = "ABSTRACT_SOUND3", ABSTRACT_SOUND4 = "ABSTRACT_SOUND4", COW_MOOING = "COW_MOOING", PHONE_VIBRATION = "PHONE_VIBRATION", ROOSTER = "ROOSTER" }
 
+export type LogRetentionConfig = { 
+/**
+ * Delete logs of executions that finished more than this many days ago
+ */
+max_age_days: number | null, 
+/**
+ * Delete the oldest logs once all stored logs take up more than this
+ */
+max_total_mb: bigint | null, 
+/**
+ * Only keep logs for the most recent attempts of each task
+ */
+keep_last_attempts: number | null, };
+
+export type JiraConfig = { enabled: boolean, default_client_id: string | null, default_client_secret: string | null, auto_sync_enabled: boolean, sync_interval_minutes: number, };
+
 export type DeviceFlowStartResponse = { user_code: string, verification_uri: string, expires_in: number, interval: number, };
 
 export enum DevicePollStatus { SLOW_DOWN = "SLOW_DOWN", AUTHORIZATION_PENDING = "AUTHORIZATION_PENDING", SUCCESS = "SUCCESS" }
@@ -96,6 +279,55 @@ export enum CheckTokenResponse { VALID = "VALID", INVALID = "INVALID" }
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
+export type MergePreview = { 
+/**
+ * Whether the task branch merges into its base branch without conflicts
+ */
+clean: boolean, conflicted_files: Array<string>, 
+/**
+ * Base branch commit the merge was simulated against
+ */
+base_commit: string, };
+
+export type ConflictedFile = { path: string, base: string | null, ours: string | null, theirs: string | null, ours_deleted: boolean, theirs_deleted: boolean, ours_binary: boolean, theirs_binary: boolean, };
+
+export type RebaseConflicts = { 
+/**
+ * The task branch commit that didn't apply cleanly
+ */
+commit: string | null, commit_summary: string | null, 
+/**
+ * Empty once every file is resolved and the rebase can continue
+ */
+files: Array<ConflictedFile>, };
+
+export type ConflictResolution = { "type": "ours" } | { "type": "theirs" } | { "type": "manual", content: string, };
+
+export type FileSelection = { path: string, hunks: Array<number> | null, };
+
+export type FileHunks = { path: string, 
+/**
+ * Empty for binary files, which can only be taken whole
+ */
+hunks: Array<HunkInfo>, };
+
+export type HunkInfo = { header: string, 
+/**
+ * The hunk's lines, prefixed with ' ', '+' or '-'
+ */
+content: string, };
+
+export type SelectionCommit = { commit: string, 
+/**
+ * Files and hunks of the attempt diff that were left out
+ */
+excluded: Array<string>, 
+/**
+ * Set when the target branch is checked out in the main repository, whose
+ * files weren't updated
+ */
+checkout_warning: string | null, };
+
 export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, };
 
 export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
@@ -160,28 +392,170 @@ export type CodingAgentFollowUpRequest = { prompt: string, session_id: string, p
 
 export type CreateTaskAttemptBody = { task_id: string, profile_variant_label: ProfileVariantLabel | null, base_branch: string, };
 
+export type MergeTaskAttemptRequest = { 
+/**
+ * Falls back to the project's default merge strategy
+ */
+strategy: MergeStrategy | null, 
+/**
+ * Also update the files of the main repository if it has the base branch
+ * checked out
+ */
+update_checkout: boolean, };
+
+export type MergeTaskAttemptResponse = { merge_commit: string, 
+/**
+ * Set when the base branch is checked out in the main repository and its
+ * files are now behind the branch
+ */
+checkout_warning: string | null, };
+
 export type RebaseTaskAttemptRequest = { new_base_branch: string | null, };
 
-export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, base_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, };
+export type ResolveConflictRequest = { file_path: string, resolution: ConflictResolution, };
 
-export type TaskAttempt = { id: string, task_id: string, container_ref: string | null, branch: string | null, base_branch: string, profile: string, worktree_deleted: boolean, setup_completed_at: string | null, created_at: string, updated_at: string, };
+export type RevertToCheckpointRequest = { 
+/**
+ * Execution to undo, along with every execution after it
+ */
+execution_process_id: string, };
 
-export type ExecutionProcess = { id: string, task_attempt_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+export type PartialMergeRequest = { files: Array<FileSelection>, 
+/**
+ * Also take uncommitted changes from the worktree
+ */
+include_uncommitted: boolean, 
+/**
+ * Commit onto a new branch created from the base branch instead of the base
+ * branch itself
+ */
+new_branch: string | null, };
 
-export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed";
+export type RevertMergeRequest = { merge_id: string, 
+/**
+ * Move the task back to `todo` or `inprogress` once reverted
+ */
+task_status: TaskStatus | null, };
+
+export type RevertMergeResponse = { revert: MergeRevert, 
+/**
+ * Set when the target branch is checked out in the main repository and its
+ * files are now behind the branch
+ */
+checkout_warning: string | null, };
+
+export type StackedAttempt = { attempt: TaskAttempt, task_title: string, 
+/**
+ * 0 for the attempt at the bottom of the stack
+ */
+depth: number, 
+/**
+ * Commits on the parent attempt's branch this attempt's branch doesn't have
+ */
+commits_behind_parent: number | null, };
+
+export type RestackResult = { attempt_id: string, 
+/**
+ * Why the attempt couldn't be restacked, the attempts on top of it are skipped
+ */
+error: string | null, };
+
+export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, base_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * A rebase stopped on conflicts and waits to be continued or aborted
+ */
+rebase_in_progress: boolean, 
+/**
+ * Whether the branch would merge cleanly into the base branch as it is now
+ */
+merge_preview: MergePreview | null, 
+/**
+ * Commits the local base branch has that `origin/<base>` doesn't, as of
+ * the last fetch. `None` when the base has no remote branch.
+ */
+base_remote_commits_ahead: number | null, 
+/**
+ * Commits on `origin/<base>` the local base branch doesn't have yet
+ */
+base_remote_commits_behind: number | null, };
+
+export type TaskAttempt = { id: string, task_id: string, container_ref: string | null, branch: string | null, base_branch: string, profile: string, worktree_deleted: boolean, setup_completed_at: string | null, 
+/**
+ * Attempt whose branch this attempt's branch is based on, for stacked attempts
+ */
+parent_attempt_id: string | null, 
+/**
+ * Parent attempt's tip the branch was last based on, the upstream when
+ * restacking it
+ */
+stacked_on_commit: string | null, created_at: string, updated_at: string, };
+
+export type ExecutionProcess = { id: string, task_attempt_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+/**
+ * The failed execution process this one is a retry of
+ */
+retry_of: string | null, 
+/**
+ * Attempt branch HEAD before this execution started
+ */
+checkpoint_commit: string | null, 
+/**
+ * Whether this execution's changes were discarded by a checkpoint revert
+ */
+reverted: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
+
+export type ExecutionProcessStatus = "queued" | "running" | "completed" | "failed" | "killed";
 
 export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
 
+export type ExecutionQueueEntry = { id: string, execution_process_id: string, project_id: string, priority: bigint, created_at: string, };
+
+export type UpdateQueuePriority = { priority: bigint, };
+
+export type RenormalizeRequest = { 
+/**
+ * Execution processes to re-normalize, all finished coding agent runs if omitted
+ */
+execution_ids: Array<string> | null, };
+
+export type RenormalizeResponse = { 
+/**
+ * Number of executions queued for re-normalization
+ */
+queued: number, };
+
+export type PurgeProjectLogsRequest = { 
+/**
+ * Only purge logs of executions that completed before this time
+ */
+before: string | null, };
+
+export type PurgeProjectLogsResponse = { purged: number, };
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
-export type DirectMerge = { id: string, task_attempt_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
+export type DirectMerge = { id: string, task_attempt_id: string, merge_commit: string, target_branch_name: string, strategy: MergeStrategy, 
+/**
+ * Set when only part of the attempt's changes were merged, describing what
+ * was left out
+ */
+note: string | null, 
+/**
+ * Target branch tip before the merge, unset for merges recorded before it
+ * was kept
+ */
+base_commit: string | null, revert: MergeRevert | null, created_at: string, };
 
-export type PrMerge = { id: string, task_attempt_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, };
+export type PrMerge = { id: string, task_attempt_id: string, created_at: string, target_branch_name: string, pr_info: PullRequestInfo, revert: MergeRevert | null, };
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
+export type MergeStrategy = "squash" | "merge_commit" | "rebase" | "fast_forward";
+
 export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
 
+export type MergeRevert = { commit: string, pr_number: bigint | null, pr_url: string | null, reverted_at: string, };
+
 export type EventPatch = { op: string, path: string, value: EventPatchInner, };
 
 export type EventPatchInner = { db_op: string, record: RecordTypes, };
@@ -190,7 +564,12 @@ export type RecordTypes = { "type": "TASK", "data": Task } | { "type": "TASK_ATT
 
 export type NormalizedConversation = { entries: Array<NormalizedEntry>, session_id: string | null, executor_type: string, prompt: string | null, summary: string | null, };
 
-export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
+export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, 
+/**
+ * Tool use id of the subagent task this entry was produced by, see
+ * [`ActionType::TaskCreate`]
+ */
+parent_id: string | null, };
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, } | { "type": "system_message" } | { "type": "error_message" } | { "type": "thinking" };
 
@@ -204,7 +583,17 @@ unified_diff: string,
  */
 has_line_numbers: boolean, };
 
-export type ActionType = { "action": "file_read", path: string, } | { "action": "file_edit", path: string, changes: Array<FileChange>, } | { "action": "command_run", command: string, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "task_create", description: string, } | { "action": "plan_presentation", plan: string, } | { "action": "todo_management", todos: Array<TodoItem>, operation: string, } | { "action": "other", description: string, };
+export type ActionType = { "action": "file_read", path: string, } | { "action": "file_edit", path: string, changes: Array<FileChange>, } | { "action": "command_run", command: string, } | { "action": "test_run", command: string, summary: TestRunSummary, } | { "action": "search", query: string, } | { "action": "web_fetch", url: string, } | { "action": "task_create", description: string, 
+/**
+ * Tool use id, referenced by the `parent_id` of the subagent's entries
+ */
+id: string | null, status: SubagentStatus, duration_ms: bigint | null, } | { "action": "plan_presentation", plan: string, } | { "action": "todo_management", todos: Array<TodoItem>, operation: string, } | { "action": "other", description: string, };
+
+export type SubagentStatus = "running" | "completed" | "failed";
+
+export type TestRunner = "cargo" | "pytest" | "jest" | "vitest";
+
+export type TestRunSummary = { runner: TestRunner, passed: number, failed: number, skipped: number, failing_tests: Array<string>, };
 
 export type TodoItem = { content: string, status: string, priority: string | null, };
 